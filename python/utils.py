@@ -11,9 +11,10 @@ def get_params(folder, params):
     return df
 
 
-# Get dataframe with all results
+# Get dataframe with all results, indexed by problem file (the seed in the second column is dropped)
 def get_results(folder, names):
-    df = pd.read_csv(folder + "/results.csv", names=names)
+    df = pd.read_csv(folder + "/results.csv", names=["seed"] + names)
+    df = df.drop(columns="seed")
     df.columns = df.columns.astype(str)
 
     return df
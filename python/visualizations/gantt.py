@@ -21,36 +21,63 @@ def visualize_schedule(solution: str, instance: str):
 
     production_start = datetime.combine(date.today(), time()) + timedelta(hours=8)
 
-    for stage in range(solution["stages"]):
-        for machine in range(solution["machines"][stage]):
-            for machine_run, (job, completion_time) in enumerate(
-                solution["machine_completions"][stage][machine]
-            ):
-                # get current job's processing time
-                production_time = instance["processing_times"][job][stage]
-
-                # get current job's setup time
-                if machine_run == 0:
-                    setup_time = instance["setup_times"][stage][job][job]
-                else:
-                    prev_job, _ = solution["machine_completions"][stage][machine][
-                        machine_run - 1
-                    ]
-                    setup_time = instance["setup_times"][stage][prev_job][job]
-
-                end_time = production_start + timedelta(minutes=completion_time)
-                # Start time is completion time - (processing time + setup time)
-                start_time = production_start + timedelta(
-                    minutes=completion_time - production_time - setup_time
-                )
+    # Newer solutions record the setup and processing start of every operation
+    if solution.get("operations"):
+        for operation in solution["operations"]:
+            if operation["machine"] is None:
+                continue
+            machine = f"{operation['stage'] + 1, operation['machine'] + 1}"
+            if operation["processing_start"] > operation["setup_start"]:
                 schedule.append(
                     dict(
-                        Machine=f"{stage + 1, machine + 1}",
-                        Start=start_time,
-                        End=end_time,
-                        Job=f"J{job+1}",
+                        Machine=machine,
+                        Start=production_start
+                        + timedelta(minutes=operation["setup_start"]),
+                        End=production_start
+                        + timedelta(minutes=operation["processing_start"]),
+                        Job="Setup",
                     )
                 )
+            schedule.append(
+                dict(
+                    Machine=machine,
+                    Start=production_start
+                    + timedelta(minutes=operation["processing_start"]),
+                    End=production_start + timedelta(minutes=operation["completion"]),
+                    Job=f"J{operation['job']+1}",
+                )
+            )
+    else:
+        for stage in range(solution["stages"]):
+            for machine in range(solution["machines"][stage]):
+                for machine_run, (job, completion_time) in enumerate(
+                    solution["machine_completions"][stage][machine]
+                ):
+                    # get current job's processing time
+                    production_time = instance["processing_times"][job][stage]
+
+                    # get current job's setup time
+                    if machine_run == 0:
+                        setup_time = instance["setup_times"][stage][job][job]
+                    else:
+                        prev_job, _ = solution["machine_completions"][stage][machine][
+                            machine_run - 1
+                        ]
+                        setup_time = instance["setup_times"][stage][prev_job][job]
+
+                    end_time = production_start + timedelta(minutes=completion_time)
+                    # Start time is completion time - (processing time + setup time)
+                    start_time = production_start + timedelta(
+                        minutes=completion_time - production_time - setup_time
+                    )
+                    schedule.append(
+                        dict(
+                            Machine=f"{stage + 1, machine + 1}",
+                            Start=start_time,
+                            End=end_time,
+                            Job=f"J{job+1}",
+                        )
+                    )
 
     df = pd.DataFrame(schedule)
 
@@ -0,0 +1,348 @@
+/*
+Branch and bound over job permutations, for proving optimal permutations of small instances (up to about 10 jobs).
+
+A node is a prefix of the permutation, and its children append one of the remaining jobs. The search is depth
+first, visiting the children in order of their lower bound, and starts from the NEH permutation as incumbent.
+A node is pruned when its lower bound is at least the makespan of the incumbent, and the search stops as soon as
+the incumbent reaches the lower bound of the instance (see common::bounds).
+
+Lower bound of a node:
+    With the Fifo and PermutationOrder decoding rules, stage 0 is dispatched in permutation order, so the stage 0
+    schedule of the prefix is final: the ready times a_m of the stage 0 machines and the stage 0 completions of
+    the prefix jobs are known. Later stages are dispatched in order of ready times, so appending a job can
+    change the schedule of the prefix in them, and they are only bounded through the heads of the jobs:
+        head of a prefix job: its stage 0 completion (or release date if it skips stage 0)
+        head of a remaining job: max(r_j, min_m a_m) + its stage 0 operation
+    plus the operations of the job before the stage. The bound is the max of
+        - the job bound: max_j head_j + the operations of j after stage 0
+        - the stage 0 bound: ceil((sum_m a_m + remaining stage 0 workload + min remaining tail) / m_0)
+        - the stage bounds of common::bounds for all later stages, with the heads above
+    With the other rules the stage 0 schedule of a prefix is not final, and all nodes have the instance bound.
+
+The bounds hold for feasible schedules, and only permutations that decode to feasible schedules are accepted as
+incumbent (see common::instance::feasibility), so the result is the optimal permutation among those for the
+decoding rule. This is not always the optimal schedule of the instance, as the decoder does not consider all
+schedules.
+*/
+
+use std::time::Duration;
+
+use crate::common::{
+    bounds,
+    construction::neh::NEH,
+    instance::Solution,
+    makespan::Makespan,
+    termination::{Progress, Termination},
+};
+
+pub struct BnbSolution {
+    pub permutation: Vec<u32>,
+    pub makespan: u32,
+    // True if the search completed, so that no permutation has a lower makespan
+    pub optimal: bool,
+    // Lower bound of the instance
+    pub lower_bound: u32,
+    // Number of nodes whose children were bounded
+    pub nodes: u64,
+}
+
+struct Search<'a> {
+    makespan: &'a mut Makespan,
+    progress: Progress,
+    // Whether the stage 0 schedule of a prefix is final, see the top of the file
+    fixed_stage_0: bool,
+    // operations[job][stage], processing time plus minimum setup time
+    operations: Vec<Vec<u32>>,
+    lower_bound: u32,
+    best: Option<(Vec<u32>, u32)>,
+    nodes: u64,
+    terminated: bool,
+}
+
+// Finds the permutation with the lowest makespan, or the best one found before the termination criteria are met
+pub fn branch_and_bound(makespan: &mut Makespan, termination: &Termination) -> BnbSolution {
+    let instance = makespan.instance.clone();
+    let lower_bound = bounds::lower_bound(&instance);
+    let progress = Progress::new(termination, makespan.count, Duration::ZERO);
+
+    let mut search = Search {
+        fixed_stage_0: makespan.rule.fixed_dispatch_order(),
+        operations: bounds::operation_times(&instance),
+        makespan,
+        progress,
+        lower_bound,
+        best: None,
+        nodes: 0,
+        terminated: false,
+    };
+
+    let (neh, _) = NEH::neh(search.makespan);
+    search.leaf(&neh);
+
+    let mut remaining: Vec<u32> = (0..instance.jobs).collect();
+    search.branch(
+        &mut Vec::with_capacity(instance.jobs as usize),
+        &mut remaining,
+    );
+
+    let optimal = !search.terminated;
+    let nodes = search.nodes;
+    // No permutation decodes to a feasible schedule
+    let (permutation, makespan) = search.best.unwrap_or_else(|| NEH::neh(search.makespan));
+
+    BnbSolution {
+        permutation,
+        makespan,
+        optimal,
+        lower_bound,
+        nodes,
+    }
+}
+
+impl Search<'_> {
+    fn best_makespan(&self) -> u32 {
+        self.best.as_ref().map_or(u32::MAX, |b| b.1)
+    }
+
+    fn branch(&mut self, prefix: &mut Vec<u32>, remaining: &mut Vec<u32>) {
+        if self.best_makespan() <= self.lower_bound {
+            return;
+        }
+        if self.progress.is_terminated(self.makespan.count) {
+            self.terminated = true;
+            return;
+        }
+        if remaining.is_empty() {
+            self.leaf(prefix);
+            return;
+        }
+        self.nodes += 1;
+
+        let mut children: Vec<(u32, u32)> = (0..remaining.len())
+            .map(|i| {
+                let job = remaining.swap_remove(i);
+                prefix.push(job);
+                let bound = self.bound(prefix, remaining);
+                prefix.pop();
+                remaining.push(job);
+                let last = remaining.len() - 1;
+                remaining.swap(i, last);
+                (bound, job)
+            })
+            .collect();
+        children.sort_unstable();
+
+        for (bound, job) in children {
+            if bound >= self.best_makespan() || self.terminated {
+                break;
+            }
+            let i = remaining.iter().position(|&j| j == job).unwrap();
+            remaining.remove(i);
+            prefix.push(job);
+            self.branch(prefix, remaining);
+            prefix.pop();
+            remaining.insert(i, job);
+        }
+    }
+
+    // Makes the permutation the incumbent if it has a lower makespan and decodes to a feasible schedule
+    fn leaf(&mut self, permutation: &Vec<u32>) {
+        let (mks, machine_completions) = self.makespan.makespan(permutation);
+        self.progress.next_generation(self.best_makespan().min(mks));
+
+        if mks < self.best_makespan() {
+            let instance = &self.makespan.instance;
+            if Solution::new(machine_completions, mks, instance)
+                .check(instance)
+                .is_empty()
+            {
+                self.best = Some((permutation.clone(), mks));
+            }
+        }
+    }
+
+    // Lower bound of all permutations starting with the prefix, see the top of the file
+    fn bound(&mut self, prefix: &Vec<u32>, remaining: &[u32]) -> u32 {
+        if remaining.is_empty() {
+            return self.makespan.makespan(prefix).0;
+        }
+        if !self.fixed_stage_0 {
+            return self.lower_bound;
+        }
+        let (_, machine_completions) = self.makespan.makespan(prefix);
+        let instance = &self.makespan.instance;
+        let ops = &self.operations;
+        let stages = instance.stages as usize;
+
+        // Ready times of the stage 0 machines, and the time each prefix job completes stage 0
+        let ready: Vec<u32> = machine_completions[0]
+            .iter()
+            .map(|runs| runs.last().map_or(0, |r| r.1))
+            .collect();
+        let min_ready = *ready.iter().min().unwrap();
+        let mut completions = vec![None; instance.jobs as usize];
+        for &(job, completion) in machine_completions[0].iter().flatten() {
+            completions[job as usize] = Some(completion);
+        }
+
+        // Time each job is ready after stage 0
+        let head = |job: u32| -> u32 {
+            let r = instance.release_date(job);
+            match completions[job as usize] {
+                Some(completion) => completion,
+                None if ops[job as usize][0] == 0 => r,
+                None => r.max(min_ready) + ops[job as usize][0],
+            }
+        };
+        let jobs: Vec<u32> = prefix.iter().chain(remaining.iter()).copied().collect();
+        let heads: Vec<u32> = jobs.iter().map(|&j| head(j)).collect();
+
+        let mut bound = jobs
+            .iter()
+            .zip(heads.iter())
+            .map(|(&j, &h)| h + ops[j as usize][1..].iter().sum::<u32>())
+            .max()
+            .unwrap();
+
+        let visiting: Vec<usize> = remaining
+            .iter()
+            .map(|&j| j as usize)
+            .filter(|&j| ops[j][0] != 0)
+            .collect();
+        if let Some(min_tail) = visiting
+            .iter()
+            .map(|&j| ops[j][1..].iter().sum::<u32>())
+            .min()
+        {
+            let workload: u32 = visiting.iter().map(|&j| ops[j][0]).sum();
+            let total = ready.iter().sum::<u32>() + workload + min_tail;
+            bound = bound.max(total.div_ceil(ready.len() as u32));
+        }
+
+        for stage in 1..stages {
+            let mut stage_heads = Vec::new();
+            let mut tails = Vec::new();
+            let mut workload = 0;
+            for (&j, &h) in jobs.iter().zip(heads.iter()) {
+                let j = j as usize;
+                if ops[j][stage] != 0 {
+                    stage_heads.push(h + ops[j][1..stage].iter().sum::<u32>());
+                    tails.push(ops[j][stage + 1..].iter().sum::<u32>());
+                    workload += ops[j][stage];
+                }
+            }
+            if stage_heads.is_empty() {
+                continue;
+            }
+            stage_heads.sort_unstable();
+            tails.sort_unstable();
+
+            let machines = (instance.machines[stage] as usize).min(stage_heads.len());
+            let stage_bound = (1..=machines)
+                .map(|u| {
+                    let total = stage_heads[..u].iter().sum::<u32>()
+                        + workload
+                        + tails[..u].iter().sum::<u32>();
+                    total.div_ceil(u as u32)
+                })
+                .min()
+                .unwrap();
+            bound = bound.max(stage_bound);
+        }
+
+        bound.max(self.lower_bound)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+    use rand::{prelude::StdRng, SeedableRng};
+
+    use crate::{
+        common::{
+            decoding::DecodingType,
+            instance::{parse, Instance, Solution},
+            makespan::Makespan,
+            termination::Termination,
+        },
+        iterated_greedy::{iterated_greedy::iterated_greedy, options::Options},
+    };
+
+    use super::branch_and_bound;
+
+    // The first jobs of an instance
+    fn first_jobs(instance: &Instance, jobs: usize) -> Instance {
+        Instance {
+            jobs: jobs as u32,
+            processing_times: instance.processing_times[..jobs].to_vec(),
+            setup_times: instance
+                .setup_times
+                .iter()
+                .map(|stage| stage[..jobs].iter().map(|s| s[..jobs].to_vec()).collect())
+                .collect(),
+            ..instance.clone()
+        }
+    }
+
+    // Lowest makespan of all permutations that decode to feasible schedules
+    fn brute_force(instance: &Instance, rule: DecodingType) -> u32 {
+        let mut makespan = Makespan::new(instance, rule);
+        (0..instance.jobs)
+            .permutations(instance.jobs as usize)
+            .filter_map(|permutation| {
+                let (mks, machine_completions) = makespan.makespan(&permutation);
+                Solution::new(machine_completions, mks, instance)
+                    .check(instance)
+                    .is_empty()
+                    .then_some(mks)
+            })
+            .min()
+            .unwrap()
+    }
+
+    #[test]
+    fn optimal_permutations() {
+        let test = parse("./instances/ruiz/test/test.json").unwrap();
+        let n20m2 = first_jobs(&parse("./instances/ruiz/json/n20m2-43.json").unwrap(), 7);
+
+        for instance in [test, n20m2] {
+            for rule in [DecodingType::Fifo, DecodingType::FirstComplete] {
+                let mut makespan = Makespan::new(&instance, rule);
+                let result = branch_and_bound(&mut makespan, &Termination::default());
+
+                assert!(result.optimal);
+                assert_eq!(result.makespan, brute_force(&instance, rule), "{:?}", rule);
+                assert!(result.lower_bound <= result.makespan);
+                assert_eq!(makespan.makespan(&result.permutation).0, result.makespan);
+            }
+        }
+    }
+
+    #[test]
+    fn iterated_greedy_gap() {
+        let instance = first_jobs(&parse("./instances/ruiz/json/n20m2-43.json").unwrap(), 8);
+        let mut makespan = Makespan::new_incremental(&instance, DecodingType::Fifo);
+        let optimum = branch_and_bound(&mut makespan, &Termination::default());
+        assert!(optimum.optimal);
+
+        let options = Options {
+            termination: Termination {
+                max_evaluations: Some(5000),
+                ..Termination::default()
+            },
+            write_improvement: false,
+            ..Options::default()
+        };
+        let mut rng = StdRng::seed_from_u64(123);
+        let (_, value) = iterated_greedy(&mut makespan, None, Some(options), &mut rng);
+
+        assert!(value >= optimum.makespan);
+        assert!(
+            value <= optimum.makespan * 101 / 100,
+            "{} > {}",
+            value,
+            optimum.makespan
+        );
+    }
+}
@@ -0,0 +1 @@
+pub mod branch_and_bound;
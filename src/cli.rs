@@ -0,0 +1,517 @@
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    fmt::Display,
+    fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+use clap::{ArgEnum, Parser, Subcommand};
+
+use tio4905::{
+    common::{
+        best_known::{self, BestKnown},
+        bounds,
+        construction::solver::Solver,
+        instance::{parse, ruiz, Solution},
+        objective::ObjectiveType,
+        statistics::{self, Run, Summary},
+        termination::Termination,
+        utils,
+    },
+    genetic_algorithm::{
+        entities::options::{Args, Options},
+        ga,
+    },
+    Algorithm, IgOptions, IteratedGreedy, SolverConfig,
+};
+
+/*
+Subcommands of the program. Instances are given as files, directories (all files in the directory) or
+glob patterns, and are either JSON files or benchmark text files (see common::instance::ruiz).
+
+tio4905 solve ga instances/ruiz/json/n20m2-01.json --time-limit 5000 --seed 7
+tio4905 solve ig "instances/ruiz/json/n20m2-*.json" -o solutions/ig_n20m2
+tio4905 solve sa instances/ruiz/json/n20m2-01.json --max-evaluations 100000
+tio4905 solve tabu instances/ruiz/json/n50m4-10.json --max-evaluations 100000
+tio4905 solve ga instances/ruiz/json/n20m2-01.json -l --set ls_type=Tabu
+tio4905 solve vns instances/ruiz/json/n50m4-10.json --max-evaluations 100000
+tio4905 solve alns instances/ruiz/json/n50m4-10.json --max-evaluations 100000
+tio4905 solve bnb instances/ruiz/test/test.json
+tio4905 bench instances/ruiz/json --algorithms neh,mddr,gch
+tio4905 bench "instances/ruiz/json/n20*.json" --algorithms ig,ga --replications 5 --best-known bks.csv --target-rpd 1
+tio4905 validate instances/ruiz/json
+tio4905 validate instances/ruiz/json/n20m2-01.json --solution solutions/ga/n20m2-01.json
+tio4905 tune ga instances/ruiz/json/n20m2-0*.json
+tio4905 convert "instances/ruiz/n=20/m=8/n=20, m=8.xls"
+
+solve writes the solution of each instance to <output>/<instance>.json, and bench to
+<output>/<algorithm>/<instance>.json (the best solution of the replications). Both write a row for each
+instance, algorithm and replication to <output>/results.csv, with the columns in RESULTS_HEADER, and the
+statistics of the replications to summary.csv and summary.json (see common::statistics). Replication r is
+run with seed + r, which is the seed column of its row. The improvement over the iterations of a run is written
+to <output>/improvement (see common::utils::write_makespan_improvement). The GA options (-s, -l, --decoding,
+--objective, --config and --set) are used by all algorithms where they apply.
+*/
+
+// The seed is written after the instance by utils::write_results_with_seeds
+const RESULTS_HEADER: [&str; 7] = [
+    "instance",
+    "algorithm",
+    "replication",
+    "objective",
+    "value",
+    "makespan",
+    "time_ms",
+];
+
+#[derive(Parser, Debug)]
+#[clap(author, version, about, long_about = None)]
+pub struct Cli {
+    #[clap(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Solve instances with one algorithm, and write the solutions and results.csv
+    Solve {
+        #[clap(arg_enum)]
+        algorithm: Algorithm,
+
+        /// Instance files, directories or glob patterns
+        #[clap(required = true)]
+        instances: Vec<String>,
+
+        #[clap(flatten)]
+        run: RunArgs,
+
+        #[clap(flatten)]
+        replications: ReplicationArgs,
+
+        #[clap(flatten)]
+        args: Args,
+    },
+
+    /// Solve instances with several algorithms, and write the solutions and results.csv
+    Bench {
+        /// Instance files, directories or glob patterns
+        #[clap(required = true)]
+        instances: Vec<String>,
+
+        /// Algorithms to compare
+        #[clap(
+            long,
+            arg_enum,
+            use_value_delimiter = true,
+            default_value = "neh,mddr,gch,ig,ga"
+        )]
+        algorithms: Vec<Algorithm>,
+
+        #[clap(flatten)]
+        run: RunArgs,
+
+        #[clap(flatten)]
+        replications: ReplicationArgs,
+
+        #[clap(flatten)]
+        args: Args,
+    },
+
+    /// Check that instances are valid, or that a solution is a feasible schedule of an instance
+    Validate {
+        /// Instance files, directories or glob patterns
+        #[clap(required = true)]
+        instances: Vec<String>,
+
+        /// Solution file to check against the (single) instance, all violations are reported
+        #[clap(long)]
+        solution: Option<PathBuf>,
+    },
+
+    /// Run the parameter grid of the GA (OptionsGrid) or IG on instances, and write results.csv
+    Tune {
+        #[clap(arg_enum)]
+        algorithm: Tunable,
+
+        /// Instance files, directories or glob patterns
+        #[clap(required = true)]
+        instances: Vec<String>,
+
+        #[clap(flatten)]
+        run: RunArgs,
+
+        #[clap(flatten)]
+        args: Args,
+    },
+
+    /// Convert all instances in a benchmark workbook (.xls) to JSON files
+    Convert {
+        workbook: PathBuf,
+
+        /// Folder for the JSON files
+        #[clap(short, long, default_value = "./instances/ruiz/json")]
+        output: PathBuf,
+    },
+}
+
+#[derive(clap::Args, Debug)]
+pub struct RunArgs {
+    /// Folder for solutions and results [default: ./solutions/<algorithm>, or ./solutions/bench]
+    #[clap(short, long)]
+    pub output: Option<PathBuf>,
+
+    #[clap(flatten)]
+    pub termination: Termination,
+
+    /// Seed of the random number generator [default: 123]
+    #[clap(long)]
+    pub seed: Option<u64>,
+
+    /// Ignore time limits, so that runs with the same seed give the same results. Requires
+    /// --max-evaluations or --max-generations (or a limit in the config file)
+    #[clap(long)]
+    pub reproducible: bool,
+}
+
+#[derive(clap::Args, Debug)]
+pub struct ReplicationArgs {
+    /// Number of runs of each algorithm on each instance
+    #[clap(short, long, default_value = "1")]
+    pub replications: usize,
+
+    /// File with best known values by instance (CSV rows of instance and value, or a JSON object) for the RPD
+    /// [default: the registry with the makespan objective, none with other objectives]
+    #[clap(long)]
+    pub best_known: Option<PathBuf>,
+
+    /// Registry of best known solutions, updated when a run finds a better feasible schedule
+    #[clap(long, default_value = best_known::REGISTRY)]
+    pub registry: PathBuf,
+
+    /// Do not update the registry of best known solutions
+    #[clap(long)]
+    pub no_registry: bool,
+
+    /// Stop runs within this percentage of the best known value, and report the time to reach it
+    #[clap(long, requires = "best-known")]
+    pub target_rpd: Option<f64>,
+}
+
+#[derive(ArgEnum, Clone, Copy, Debug)]
+pub enum Tunable {
+    Ga,
+    Ig,
+}
+
+pub fn run(cli: Cli) {
+    match cli.command {
+        Command::Solve {
+            algorithm,
+            instances,
+            run,
+            replications,
+            args,
+        } => {
+            let options = options(&args, &run);
+            let output = run
+                .output
+                .unwrap_or_else(|| PathBuf::from("./solutions").join(algorithm.name()));
+
+            solve_all(
+                &problem_files(&instances),
+                &[algorithm],
+                &options,
+                &replications,
+                &output,
+                false,
+            );
+        }
+        Command::Bench {
+            instances,
+            algorithms,
+            run,
+            replications,
+            args,
+        } => {
+            let options = options(&args, &run);
+            let output = run
+                .output
+                .unwrap_or_else(|| PathBuf::from("./solutions/bench"));
+
+            solve_all(
+                &problem_files(&instances),
+                &algorithms,
+                &options,
+                &replications,
+                &output,
+                true,
+            );
+        }
+        Command::Validate {
+            instances,
+            solution,
+        } => validate(&instances, solution),
+        Command::Tune {
+            algorithm,
+            instances,
+            run,
+            args,
+        } => {
+            let problem_files = problem_files(&instances);
+            let mut options = options(&args, &run);
+
+            match algorithm {
+                Tunable::Ga => {
+                    if let Some(output) = run.output {
+                        options.improvement_folder = output.join("improvement");
+                        options.solution_folder = output;
+                    }
+                    ga::run_all(problem_files, &options);
+                }
+                Tunable::Ig => {
+                    let output = run
+                        .output
+                        .unwrap_or_else(|| PathBuf::from("./solutions/ig"));
+                    let ig_options = IgOptions {
+                        termination: options.termination.clone(),
+                        seed: options.seed,
+                        improvement_folder: output.join("improvement"),
+                        ..IgOptions::default()
+                    };
+                    IteratedGreedy::run_all(
+                        problem_files,
+                        output.to_str().unwrap(),
+                        ig_options,
+                        options.decoding,
+                        options.objective,
+                        &options.objective_weights,
+                    );
+                }
+            }
+        }
+        Command::Convert { workbook, output } => match ruiz::workbook_to_json(&workbook, &output) {
+            Ok(files) => println!("Wrote {} instances to {}", files.len(), output.display()),
+            Err(e) => exit_with(format!("Could not convert {}: {}", workbook.display(), e)),
+        },
+    }
+}
+
+fn exit_with<D: Display>(message: D) -> ! {
+    eprintln!("{}", message);
+    process::exit(1);
+}
+
+// Defaults in params.rs, overridden by the config file and flags
+fn options(args: &Args, run: &RunArgs) -> Options {
+    let mut options = Options::from_args(args).unwrap_or_else(|e| exit_with(e));
+
+    options.termination.set(&run.termination);
+    if let Some(seed) = run.seed {
+        options.seed = seed;
+    }
+    if run.reproducible {
+        options.termination.time_limit = None;
+        options.termination.cpu_time_limit = None;
+        if !options.termination.is_reproducible() {
+            exit_with("--reproducible requires --max-evaluations or --max-generations");
+        }
+    }
+
+    options
+}
+
+// Expands the instance arguments, and makes sure all problem files can be solved before running any algorithm
+fn problem_files(instances: &[String]) -> Vec<PathBuf> {
+    let problem_files = utils::expand_problem_files(instances).unwrap_or_else(|e| exit_with(e));
+
+    let invalid = utils::invalid_problem_files(&problem_files);
+    if !invalid.is_empty() {
+        for (path, error) in invalid.iter() {
+            eprintln!("Invalid problem file {}: {}", path.display(), error);
+        }
+        process::exit(1);
+    }
+
+    problem_files
+}
+
+// Solves each problem file with each algorithm in all replications, and writes the best solutions, results.csv and
+// the summaries to the output folder. Solutions are written to a folder per algorithm if by_algorithm is true
+fn solve_all(
+    problem_files: &[PathBuf],
+    algorithms: &[Algorithm],
+    options: &Options,
+    replications: &ReplicationArgs,
+    output: &Path,
+    by_algorithm: bool,
+) {
+    let read_best_known = |path: &Path| match path.exists() {
+        true => statistics::read_best_known(path)
+            .unwrap_or_else(|e| exit_with(format!("Could not read {}: {}", path.display(), e))),
+        false => HashMap::new(),
+    };
+    // Makespans of the registry, updated as runs improve them
+    let mut registry = read_best_known(&replications.registry);
+    // Best known values at the start, so that improvements of the registry have a negative RPD. The registry
+    // holds makespans, so it gives no best known values for other objectives
+    let best_known = match &replications.best_known {
+        Some(path) if !path.exists() => exit_with(format!("{} does not exist", path.display())),
+        Some(path) => read_best_known(path),
+        None if matches!(options.objective, ObjectiveType::Makespan) => registry.clone(),
+        None => HashMap::new(),
+    };
+
+    let mut results = Vec::new();
+    let mut summaries = Vec::new();
+
+    for problem_file in problem_files.iter() {
+        let instance = parse(problem_file).unwrap();
+        let name = problem_file.file_stem().unwrap().to_str().unwrap();
+        let best_known = best_known.get(name).copied();
+        let lower_bound = match options.objective {
+            ObjectiveType::Makespan => Some(bounds::lower_bound(&instance)),
+            _ => None,
+        };
+
+        // The target given by the best known value, or else the target of the termination criteria
+        let target = match (best_known, replications.target_rpd) {
+            (Some(b), Some(rpd)) => Some(statistics::target_value(b, rpd)),
+            _ => options.termination.target,
+        };
+
+        for &algorithm in algorithms.iter() {
+            let mut runs = Vec::with_capacity(replications.replications);
+            let mut best_solution: Option<Solution> = None;
+
+            for replication in 0..replications.replications.max(1) {
+                let seed = options.seed + replication as u64;
+                let mut options = Options {
+                    problem_file: Cow::Owned(problem_file.clone()),
+                    seed,
+                    improvement_folder: output.join("improvement"),
+                    ..options.clone()
+                };
+                options.termination.target = target;
+                let result =
+                    tio4905::solve(&instance, SolverConfig::from_options(algorithm, &options));
+
+                println!(
+                    "{} {} ({}): {:?} {}",
+                    name,
+                    algorithm.name(),
+                    replication,
+                    options.objective,
+                    result.value
+                );
+
+                results.push((
+                    seed,
+                    vec![
+                        problem_file.display().to_string(),
+                        algorithm.name().to_string(),
+                        replication.to_string(),
+                        format!("{:?}", options.objective),
+                        result.value.to_string(),
+                        result.makespan.to_string(),
+                        result.elapsed.as_millis().to_string(),
+                    ],
+                ));
+
+                if !replications.no_registry
+                    && registry.get(name).is_none_or(|&b| result.makespan < b)
+                {
+                    let run = BestKnown {
+                        makespan: result.makespan,
+                        algorithm: algorithm.name().to_string(),
+                        seed,
+                        decoding: options.decoding,
+                        permutation: result.permutation.clone(),
+                        solution: result.solution.clone(),
+                    };
+                    match best_known::update(&replications.registry, name, &instance, run) {
+                        Ok(true) => {
+                            registry.insert(name.to_string(), result.makespan);
+                            println!("{}: new best known makespan {}", name, result.makespan);
+                        }
+                        Ok(false) => (),
+                        Err(e) => eprintln!("Best known solution of {} not updated: {}", name, e),
+                    }
+                }
+
+                if runs.iter().all(|r: &Run| result.value < r.value) {
+                    best_solution = Some(result.solution);
+                }
+                runs.push(Run {
+                    value: result.value,
+                    elapsed: result.elapsed,
+                });
+            }
+
+            let folder = match by_algorithm {
+                true => output.join(algorithm.name()),
+                false => output.to_path_buf(),
+            };
+            fs::create_dir_all(&folder).unwrap_or_else(|e| exit_with(e));
+            let file = folder.join(format!("{}.json", name));
+            best_solution.unwrap().write(file.display().to_string());
+
+            summaries.push(Summary::new(
+                name,
+                algorithm.name(),
+                &runs,
+                best_known,
+                lower_bound,
+                target,
+            ));
+        }
+    }
+
+    utils::write_results_with_seeds(output.to_str().unwrap(), Some(&RESULTS_HEADER), &results)
+        .unwrap_or_else(|e| exit_with(e));
+    statistics::write_summary(output, &summaries).unwrap_or_else(|e| exit_with(e));
+    println!(
+        "Results are stored in `{}` and `{}`",
+        output.join("results.csv").display(),
+        output.join("summary.csv").display()
+    );
+}
+
+// Checks that all instances are valid, or that the solution is feasible for the instance
+fn validate(instances: &[String], solution: Option<PathBuf>) {
+    let problem_files = utils::expand_problem_files(instances).unwrap_or_else(|e| exit_with(e));
+
+    let solution = match solution {
+        Some(solution) => solution,
+        None => {
+            let invalid = utils::invalid_problem_files(&problem_files);
+            for (path, error) in invalid.iter() {
+                println!("Invalid problem file {}: {}", path.display(), error);
+            }
+            match invalid.is_empty() {
+                true => println!("All {} problem files are valid", problem_files.len()),
+                false => process::exit(1),
+            }
+            return;
+        }
+    };
+
+    let instance = match problem_files.as_slice() {
+        [problem_file] => parse(problem_file).unwrap_or_else(|e| {
+            exit_with(format!("Could not read {}: {}", problem_file.display(), e))
+        }),
+        _ => exit_with("A solution is checked against exactly one instance"),
+    };
+    let violations = Solution::read(&solution)
+        .unwrap_or_else(|e| exit_with(format!("Could not read {}: {}", solution.display(), e)))
+        .check(&instance);
+
+    for violation in violations.iter() {
+        println!("{}", violation);
+    }
+    match violations.is_empty() {
+        true => println!("{} is feasible", solution.display()),
+        false => process::exit(1),
+    }
+}
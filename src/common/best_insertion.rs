@@ -3,8 +3,6 @@ use rand::{
     Rng,
 };
 
-use crate::genetic_algorithm::params;
-
 use super::makespan::Makespan;
 
 /// Inserts a block (size >= 1 jobs) to the best position, or a random one if desired
@@ -15,17 +13,17 @@ use super::makespan::Makespan;
 /// * jobs - The current (partial) job permutation
 /// * block - The (remaining) jobs to insert into the current permutation
 /// * makespan - Makespan struct for makespan calculation
-/// * allow_rnd - whether to allow returning a random insertion
+/// * keep_best - probability of returning the best insertion instead of a random one (None always returns the best)
 pub fn find_best_insertion(
     jobs: Vec<u32>,
     block: &[u32],
     makespan: &mut Makespan,
-    allow_rnd: bool,
+    keep_best: Option<f32>,
     rng: &mut StdRng,
 ) -> (Vec<u32>, u32) {
     let n_jobs = jobs.len();
     let mut jobs: Vec<u32> = block.iter().cloned().chain(jobs.iter().cloned()).collect();
-    let (mut best_makespan, _) = makespan.makespan(&jobs);
+    let mut best_makespan = makespan.evaluate(&jobs);
     let k = block.len();
 
     // Store one random solution which may be returned
@@ -39,10 +37,10 @@ pub fn find_best_insertion(
     for i in 0..n_jobs {
         // Shift block one step to the right
         jobs[i..i + k + 1].rotate_right(1);
-        let (new_makespan, _) = makespan.makespan(&jobs);
+        let new_makespan = makespan.evaluate(&jobs);
 
         // Update random solution if we are at the random index
-        if allow_rnd && i == random_idx {
+        if keep_best.is_some() && i == random_idx {
             random_jobs = jobs.iter().cloned().collect();
             random_makespan = new_makespan;
         }
@@ -58,7 +56,7 @@ pub fn find_best_insertion(
     }
 
     // Return either one of the best solutions, or the chosen random solution
-    if allow_rnd && rng.gen::<f32>() >= params::KEEP_BEST {
+    if keep_best.is_some_and(|keep_best| rng.gen::<f32>() >= keep_best) {
         (random_jobs, random_makespan)
     } else {
         (best_jobs.choose(rng).unwrap().to_vec(), best_makespan)
@@ -0,0 +1,246 @@
+use std::{
+    collections::BTreeMap,
+    error::Error,
+    fs::{self, File, OpenOptions},
+    io::{BufReader, ErrorKind},
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, Instant},
+};
+
+use serde_derive::{Deserialize, Serialize};
+
+use super::{
+    decoding::DecodingType,
+    instance::{Instance, Solution},
+};
+
+/*
+Registry of the best known solutions (BKS), a JSON object keyed by instance name (the file stem of the instance,
+e.g. n20m2-01). Each entry holds the best makespan found so far, with the permutation, decoding rule, algorithm
+and seed of the run that found it and the full solution, so that improvements can be reproduced and checked.
+
+{
+    "n20m2-01": { "makespan": 621, "algorithm": "ig", "seed": 123, "decoding": "Fifo", "permutation": [...], "solution": {...} },
+    ...
+}
+
+update only replaces an entry with a better schedule that passes the feasibility check (see
+common::instance::feasibility). The registry is locked with a lock file while it is read and written, and
+written to a temporary file that replaces the registry, so concurrent runs never lose or corrupt entries.
+*/
+
+pub const REGISTRY: &str = "./solutions/best_known.json";
+
+// Time to wait for the lock of another run before giving up
+const LOCK_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Serialize, Deserialize)]
+pub struct BestKnown {
+    pub makespan: u32,
+    pub algorithm: String,
+    pub seed: u64,
+    pub decoding: DecodingType,
+    // Empty if the schedule was not given by a permutation (MDDR)
+    pub permutation: Vec<u32>,
+    pub solution: Solution,
+}
+
+pub type Registry = BTreeMap<String, BestKnown>;
+
+// Reads the registry, which is empty if the file does not exist
+pub fn read<P: AsRef<Path>>(path: P) -> Result<Registry, Box<dyn Error>> {
+    match File::open(path) {
+        Ok(file) => Ok(serde_json::from_reader(BufReader::new(file))?),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(Registry::new()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+// Stores the solution as the best known solution of the instance if its makespan is better than the one in the
+// registry. Returns true if the registry was updated, and an error if the solution is not a feasible schedule
+pub fn update<P: AsRef<Path>>(
+    path: P,
+    name: &str,
+    instance: &Instance,
+    best_known: BestKnown,
+) -> Result<bool, Box<dyn Error>> {
+    let path = path.as_ref();
+
+    let violations = best_known.solution.check(instance);
+    if let Some(violation) = violations.first() {
+        return Err(format!(
+            "Infeasible solution ({} violations), e.g. {}",
+            violations.len(),
+            violation
+        )
+        .into());
+    }
+    if best_known.solution.makespan() != best_known.makespan {
+        return Err(format!(
+            "The makespan {} does not match the solution makespan {}",
+            best_known.makespan,
+            best_known.solution.makespan()
+        )
+        .into());
+    }
+
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        fs::create_dir_all(parent)?;
+    }
+
+    let _lock = Lock::acquire(path)?;
+
+    let mut registry = read(path)?;
+    if registry
+        .get(name)
+        .is_some_and(|b| b.makespan <= best_known.makespan)
+    {
+        return Ok(false);
+    }
+    registry.insert(name.to_string(), best_known);
+
+    let tmp = with_suffix(path, "tmp");
+    fs::write(&tmp, serde_json::to_string_pretty(&registry)?)?;
+    fs::rename(&tmp, path)?;
+
+    Ok(true)
+}
+
+// Lock file next to the registry, removed when dropped
+struct Lock(PathBuf);
+
+impl Lock {
+    fn acquire(path: &Path) -> Result<Lock, Box<dyn Error>> {
+        let lock = with_suffix(path, "lock");
+        let start = Instant::now();
+
+        loop {
+            match OpenOptions::new().write(true).create_new(true).open(&lock) {
+                Ok(_) => return Ok(Lock(lock)),
+                Err(e)
+                    if e.kind() == ErrorKind::AlreadyExists && start.elapsed() < LOCK_TIMEOUT =>
+                {
+                    thread::sleep(Duration::from_millis(10))
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    return Err(format!(
+                        "{} is locked, remove {} if no other run is using it",
+                        path.display(),
+                        lock.display()
+                    )
+                    .into())
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+impl Drop for Lock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut file = path.as_os_str().to_owned();
+    file.push(".");
+    file.push(suffix);
+    PathBuf::from(file)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use crate::{
+        common::{
+            construction::neh::NEH,
+            decoding::DecodingType,
+            instance::{parse, Solution},
+            makespan::Makespan,
+            termination::Termination,
+        },
+        solve, Algorithm, SolverConfig,
+    };
+
+    use super::{read, update, BestKnown};
+
+    #[test]
+    fn updates_with_better_feasible_solutions() {
+        let path = env::temp_dir().join("tio4905_best_known_registry.json");
+        let _ = fs::remove_file(&path);
+
+        let instance = parse("./instances/ruiz/json/n20m2-43.json").unwrap();
+        let mut makespan = Makespan::new_incremental(&instance, DecodingType::Fifo);
+        let (permutation, value) = NEH::neh(&mut makespan);
+
+        let mut best_known = |value: u32| {
+            let (mks, machine_completions) = makespan.makespan(&permutation);
+            BestKnown {
+                makespan: value,
+                algorithm: "neh".to_string(),
+                seed: 0,
+                decoding: DecodingType::Fifo,
+                permutation: permutation.clone(),
+                solution: Solution::new(machine_completions, mks, &instance),
+            }
+        };
+
+        assert!(update(&path, "n20m2-43", &instance, best_known(value)).unwrap());
+        assert!(!update(&path, "n20m2-43", &instance, best_known(value)).unwrap());
+        // The makespan must be the one of the solution
+        assert!(update(&path, "n20m2-43", &instance, best_known(value - 1)).is_err());
+
+        let registry = read(&path).unwrap();
+        assert_eq!(registry["n20m2-43"].makespan, value);
+        assert_eq!(registry["n20m2-43"].permutation, permutation);
+
+        fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn updates_from_solver_runs() {
+        let path = env::temp_dir().join("tio4905_best_known_solver_runs.json");
+        let _ = fs::remove_file(&path);
+
+        // Some jobs skip stages in n50m4-10
+        let instance = parse("./instances/ruiz/json/n50m4-10.json").unwrap();
+        for algorithm in [Algorithm::Ig, Algorithm::Mddr] {
+            let config = SolverConfig {
+                algorithm,
+                termination: Termination {
+                    max_evaluations: Some(2000),
+                    ..Termination::default()
+                },
+                ..Default::default()
+            };
+            let seed = config.seed;
+            let decoding = config.decoding;
+            let result = solve(&instance, config);
+
+            let run = BestKnown {
+                makespan: result.makespan,
+                algorithm: algorithm.name().to_string(),
+                seed,
+                decoding,
+                permutation: result.permutation,
+                solution: result.solution,
+            };
+            let best = read(&path)
+                .unwrap()
+                .get("n50m4-10")
+                .map_or(u32::MAX, |b| b.makespan);
+            assert_eq!(
+                update(&path, "n50m4-10", &instance, run).unwrap(),
+                result.makespan < best,
+                "{:?}",
+                algorithm
+            );
+        }
+
+        assert!(read(&path).unwrap().contains_key("n50m4-10"));
+        fs::remove_file(&path).unwrap();
+    }
+}
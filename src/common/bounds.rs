@@ -0,0 +1,183 @@
+use super::instance::Instance;
+
+/*
+Lower bounds on the makespan of an instance. Setups are not anticipatory (a job is set up on a machine after it
+arrives in the stage, see common::makespan), so each operation takes at least its processing time plus its
+minimum setup time: the shortest setup from any job visiting the stage, or the initial setup of the job.
+
+Job-based bound: no job completes before its release date plus its operations in all stages it visits.
+    LB_job = max_j (r_j + sum_s (p_js + min_setup_js))
+
+Stage-based bound: the operations of stage s are shared by its m_s machines. A machine that processes a job can
+not start before the head of its first job (the time the job needs to reach the stage), and the makespan is at
+least its completion plus the tail of its last job (the time the job needs after the stage). If u machines of
+the stage are used, summing over them gives
+    u * Cmax >= (u smallest heads) + W_s + (u smallest tails),  where W_s = sum_j (p_js + min_setup_js)
+    LB_s = min over u in 1..=min(m_s, n_s) of ceil(((u smallest heads) + W_s + (u smallest tails)) / u)
+which is at least min head + W_s / m_s + min tail.
+
+The lower bound of the instance is the max of the job-based and all stage-based bounds. The processing and
+setup times are the shortest on any machine for unrelated machines (see instance.rs), so the bounds are also
+valid for them and for eligibility constraints.
+*/
+
+// Bounds of an instance
+#[derive(Clone, Debug, PartialEq)]
+pub struct LowerBounds {
+    pub job: u32,
+    // stages[stage]
+    pub stages: Vec<u32>,
+    // Max of the job and stage bounds
+    pub lower_bound: u32,
+}
+
+// Computes all bounds of the instance
+pub fn lower_bounds(instance: &Instance) -> LowerBounds {
+    let operations = operation_times(instance);
+
+    let job = job_bound(instance, &operations);
+    let stages: Vec<u32> = (0..instance.stages as usize)
+        .map(|stage| stage_bound(instance, &operations, stage))
+        .collect();
+    let lower_bound = stages.iter().copied().fold(job, u32::max);
+
+    LowerBounds {
+        job,
+        stages,
+        lower_bound,
+    }
+}
+
+// The best lower bound on the makespan of the instance
+pub fn lower_bound(instance: &Instance) -> u32 {
+    lower_bounds(instance).lower_bound
+}
+
+// Minimum setup time of the job in the stage, from any job visiting the stage or the initial setup
+pub fn min_setup_time(instance: &Instance, job: usize, stage: usize) -> u32 {
+    (0..instance.jobs as usize)
+        .filter(|&prev| prev == job || instance.processing_times[prev][stage] != 0)
+        .map(|prev| instance.setup_times[stage][prev][job])
+        .min()
+        .unwrap_or(0)
+}
+
+// operations[job][stage]: processing time plus minimum setup time, 0 for stages the job skips
+pub fn operation_times(instance: &Instance) -> Vec<Vec<u32>> {
+    (0..instance.jobs as usize)
+        .map(|job| {
+            (0..instance.stages as usize)
+                .map(|stage| match instance.processing_times[job][stage] {
+                    0 => 0,
+                    p => p + min_setup_time(instance, job, stage),
+                })
+                .collect()
+        })
+        .collect()
+}
+
+// The stage with the most work per machine, the sum of its operations divided by its machines
+pub fn bottleneck_stage(instance: &Instance, operations: &[Vec<u32>]) -> usize {
+    let work = |stage: usize| -> f64 {
+        operations.iter().map(|ops| ops[stage]).sum::<u32>() as f64
+            / instance.machines[stage] as f64
+    };
+    (0..instance.stages as usize)
+        .max_by(|&a, &b| work(a).total_cmp(&work(b)))
+        .unwrap()
+}
+
+fn job_bound(instance: &Instance, operations: &[Vec<u32>]) -> u32 {
+    operations
+        .iter()
+        .enumerate()
+        .map(|(job, times)| instance.release_date(job as u32) + times.iter().sum::<u32>())
+        .max()
+        .unwrap_or(0)
+}
+
+fn stage_bound(instance: &Instance, operations: &[Vec<u32>], stage: usize) -> u32 {
+    let visiting: Vec<usize> = (0..instance.jobs as usize)
+        .filter(|&job| instance.processing_times[job][stage] != 0)
+        .collect();
+    if visiting.is_empty() {
+        return 0;
+    }
+
+    let mut heads: Vec<u32> = visiting
+        .iter()
+        .map(|&job| {
+            instance.release_date(job as u32) + operations[job][..stage].iter().sum::<u32>()
+        })
+        .collect();
+    let mut tails: Vec<u32> = visiting
+        .iter()
+        .map(|&job| operations[job][stage + 1..].iter().sum())
+        .collect();
+    heads.sort_unstable();
+    tails.sort_unstable();
+
+    let workload: u32 = visiting.iter().map(|&job| operations[job][stage]).sum();
+    let machines = (instance.machines[stage] as usize).min(visiting.len());
+
+    (1..=machines)
+        .map(|u| {
+            let total = heads[..u].iter().sum::<u32>() + workload + tails[..u].iter().sum::<u32>();
+            total.div_ceil(u as u32)
+        })
+        .min()
+        .unwrap()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{
+        construction::neh::NEH,
+        decoding::DecodingType,
+        instance::{parse, Instance},
+        makespan::Makespan,
+    };
+
+    use super::{lower_bound, lower_bounds, min_setup_time};
+
+    #[test]
+    fn bounds_of_small_instance() {
+        // Job 1 skips stage 1, which has a single machine
+        let instance = Instance {
+            jobs: 3,
+            stages: 2,
+            machines: vec![2, 1],
+            processing_times: vec![vec![4, 6], vec![3, 0], vec![5, 7]],
+            setup_times: vec![
+                vec![vec![1, 2, 3], vec![2, 2, 2], vec![4, 1, 2]],
+                vec![vec![3, 9, 1], vec![0, 0, 0], vec![2, 9, 5]],
+            ],
+            ..Default::default()
+        };
+
+        // The setup from job 1 does not count in stage 1, as job 1 skips it
+        assert_eq!(min_setup_time(&instance, 2, 1), 1);
+        assert_eq!(min_setup_time(&instance, 0, 1), 2);
+
+        let bounds = lower_bounds(&instance);
+        // Operations: job 0 (5, 8), job 1 (4, 0), job 2 (7, 8)
+        assert_eq!(bounds.job, 15);
+        // Stage 0 with heads 0 and tails 8, 0, 8: min(0 + 16 + 0, ceil((0 + 16 + 0 + 8) / 2)) = 12
+        assert_eq!(bounds.stages[0], 12);
+        // Stage 1 on one machine with heads 5, 7: 5 + 16
+        assert_eq!(bounds.stages[1], 21);
+        assert_eq!(bounds.lower_bound, 21);
+    }
+
+    #[test]
+    fn bounds_are_below_makespans() {
+        for file in ["n20m2-01", "n20m2-43", "n50m4-10", "n80m8-1"] {
+            let instance = parse(format!("./instances/ruiz/json/{}.json", file)).unwrap();
+            let mut makespan = Makespan::new_incremental(&instance, DecodingType::Fifo);
+            let (_, mks) = NEH::neh(&mut makespan);
+
+            let bound = lower_bound(&instance);
+            assert!(bound > 0 && bound <= mks, "{}: {} > {}", file, bound, mks);
+        }
+    }
+}
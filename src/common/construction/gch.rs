@@ -29,11 +29,11 @@ impl Constructor for GCH<'_> {
             let job = jobs.remove(jobs.len() - 1);
 
             // Find current job's best insertion point in output jobs
-            (c, makespan) = find_best_insertion(c, &[job], &mut self.makespan, false, self.rng);
+            (c, makespan) = find_best_insertion(c, &[job], &mut self.makespan, None, self.rng);
         }
 
         let mut c = Chromosome::from(c);
-        c.makespan = Some(makespan);
+        c.fitness = Some(makespan);
         c.updated = false;
         return c;
     }
@@ -52,7 +52,7 @@ mod test {
     use rand::{prelude::StdRng, SeedableRng};
 
     use crate::{
-        common::{instance::parse, makespan::Makespan},
+        common::{decoding::DecodingType, instance::parse, makespan::Makespan},
         genetic_algorithm::{entities::chromosome::Chromosome, params},
     };
 
@@ -62,7 +62,7 @@ mod test {
     fn test_heuristic_performance() {
         let instance = parse(params::PROBLEM_FILE).unwrap();
 
-        let mut makespan = Makespan::new(&instance);
+        let mut makespan = Makespan::new(&instance, DecodingType::Fifo);
 
         let mut rng = StdRng::seed_from_u64(123);
 
@@ -71,7 +71,7 @@ mod test {
             rng: &mut rng,
         };
 
-        let mut makespan = Makespan::new(&instance);
+        let mut makespan = Makespan::new(&instance, DecodingType::Fifo);
 
         let mut heuristic: Vec<Chromosome> = constructor.take(10).collect();
         let mut random: Vec<Chromosome> = Vec::new();
@@ -79,12 +79,12 @@ mod test {
             random.push(Chromosome::new(&instance, &mut rng));
         }
 
-        heuristic.iter_mut().for_each(|c| c.makespan(&mut makespan));
-        random.iter_mut().for_each(|c| c.makespan(&mut makespan));
+        heuristic.iter_mut().for_each(|c| c.evaluate(&mut makespan));
+        random.iter_mut().for_each(|c| c.evaluate(&mut makespan));
 
         assert!(
-            heuristic.iter().map(|s| s.makespan.unwrap()).sum::<u32>()
-                <= random.iter().map(|s| s.makespan.unwrap()).sum::<u32>()
+            heuristic.iter().map(|s| s.fitness.unwrap()).sum::<u32>()
+                <= random.iter().map(|s| s.fitness.unwrap()).sum::<u32>()
         )
     }
 
@@ -92,7 +92,7 @@ mod test {
     fn test_number_unique_solutions() {
         let instance = parse(params::PROBLEM_FILE).unwrap();
 
-        let mut makespan = Makespan::new(&instance);
+        let mut makespan = Makespan::new(&instance, DecodingType::Fifo);
 
         let mut rng = StdRng::seed_from_u64(123);
 
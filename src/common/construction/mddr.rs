@@ -34,11 +34,11 @@ impl MDDR {
             }
         }
 
-        // Previous stage completions initially set to zero
+        // Previous stage completions initially set to the release dates
         let mut prev_stage_completions: Vec<(u32, u32)> = Vec::with_capacity(n_jobs as usize);
 
         for job in 0..n_jobs {
-            prev_stage_completions.push((job as u32, 0));
+            prev_stage_completions.push((job as u32, instance.release_date(job as u32)));
         }
 
         for stage in 0..n_stages {
@@ -72,6 +72,7 @@ impl MDDR {
 #[cfg(test)]
 mod mddr_test {
     use crate::common::{
+        decoding::DecodingType,
         instance::{parse, Instance},
         makespan::Makespan,
     };
@@ -81,7 +82,7 @@ mod mddr_test {
     #[test]
     fn test_mddr() {
         let instance = mini_instance();
-        let mut makespan = Makespan::new(&instance);
+        let mut makespan = Makespan::new(&instance, DecodingType::Fifo);
         let (mks, machine_completions) = MDDR::mddr(&mut makespan);
 
         println!("{:?}", machine_completions);
@@ -91,7 +92,7 @@ mod mddr_test {
     #[test]
     fn test_mddr2() {
         let instance = test_instance();
-        let mut makespan = Makespan::new(&instance);
+        let mut makespan = Makespan::new(&instance, DecodingType::Fifo);
         let (mks, machine_completions) = MDDR::mddr(&mut makespan);
 
         println!("{:?}", machine_completions);
@@ -101,7 +102,7 @@ mod mddr_test {
     #[test]
     fn test_mddr3() {
         let instance = parse("./instances/ruiz/json/n20m2-18.json").unwrap();
-        let mut makespan = Makespan::new(&instance);
+        let mut makespan = Makespan::new(&instance, DecodingType::Fifo);
         let (mks, machine_completions) = MDDR::mddr(&mut makespan);
 
         println!("{:?}", machine_completions);
@@ -118,6 +119,7 @@ mod mddr_test {
                 vec![vec![1, 1, 1], vec![1, 1, 1], vec![1, 1, 1]],
                 vec![vec![1, 1, 1], vec![1, 1, 1], vec![1, 1, 1]],
             ],
+            ..Default::default()
         }
     }
 
@@ -149,6 +151,7 @@ mod mddr_test {
                     vec![1, 1, 1, 1, 1],
                 ],
             ],
+            ..Default::default()
         }
     }
 }
@@ -1,7 +1,9 @@
-use serde_derive::Serialize;
+use serde_derive::{Deserialize, Serialize};
 
 use crate::genetic_algorithm::entities::chromosome::Chromosome;
 
+use self::neh::{NehOrder, TieBreak};
+
 pub mod gch;
 pub mod mddr;
 pub mod neh;
@@ -9,11 +11,13 @@ pub mod neh_permutation;
 pub mod solver;
 
 #[allow(dead_code)]
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
 pub enum Construction {
     Random,
     MDDR(f32),
     NEH,
+    // NEH with another initial order or tie-breaking rule, see neh.rs
+    NEHVariant(NehOrder, TieBreak),
 }
 
 pub trait Constructor {
@@ -1,10 +1,57 @@
+use rand::{
+    prelude::{SliceRandom, StdRng},
+    Rng,
+};
+use serde_derive::{Deserialize, Serialize};
+
 use crate::{
-    common::{instance::Instance, makespan::Makespan},
+    common::{bounds, instance::Instance, makespan::Makespan},
     iterated_greedy::options::Options,
 };
 
 use super::solver::Solver;
 
+/*
+Variants of the NEH from the literature on the hybrid flow shop with sequence dependent setup times, given by
+the initial order of the jobs and the rule breaking ties between the best insertion positions.
+
+Initial orders (NEH::neh uses TotalProcessing):
+    TotalProcessing: descending sum of the processing times of the job
+    ProcessingAndSetup: descending sum over the stages the job visits of its processing time and its average
+                        setup time from the other jobs visiting the stage
+    Bottleneck: descending operation time (processing plus minimum setup, see common::bounds) on the stage with
+                the most work per machine, ties by TotalProcessing
+    Johnson: Johnson's rule on two virtual stages, the first and the last half of the stages (the middle stage
+             of an odd number of stages is in both), with the operation times divided by the machines of the
+             stage. Jobs with a < b first in ascending a, then the other jobs in descending b
+    Random: a random order
+
+Tie-breaking between insertion positions with the same objective value (NEH::neh uses First):
+    First / Last: the first or last of the positions
+    IdleTime: the position with the least idle time of the machines, the time a machine is not busy with
+              processing or setups before its last job completes (the first of equal idle times)
+    Random: a random position of the ties
+*/
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum NehOrder {
+    TotalProcessing,
+    ProcessingAndSetup,
+    Bottleneck,
+    Johnson,
+    Random,
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum TieBreak {
+    First,
+    Last,
+    IdleTime,
+    Random,
+}
+
 pub struct NEH {}
 
 impl NEH {
@@ -21,6 +68,25 @@ impl NEH {
         }
         return schedule;
     }
+
+    // NEH with the initial order and tie-breaking rule, see the top of the file
+    pub fn neh_variant(
+        makespan: &mut Makespan,
+        order: NehOrder,
+        tie_break: TieBreak,
+        rng: &mut StdRng,
+    ) -> (Vec<u32>, u32) {
+        let job_order = initial_order(&makespan.instance, order, rng);
+        let mut schedule: (Vec<u32>, u32) = (
+            Vec::with_capacity(makespan.instance.jobs as usize),
+            u32::MAX,
+        );
+
+        for job in job_order {
+            schedule = insert_job_tie_break(makespan, &schedule.0, job, tie_break, rng);
+        }
+        schedule
+    }
 }
 
 impl Solver for NEH {
@@ -59,7 +125,7 @@ pub fn insert_job(makespan: &mut Makespan, schedule: &Vec<u32>, next_job: &u32)
         let mut test_schedule: Vec<u32> = schedule.clone();
         test_schedule.insert(index, *next_job);
         // Find the makespan of the test schedule
-        let (time, _) = makespan.makespan(&test_schedule);
+        let time = makespan.evaluate(&test_schedule);
         // If the test schedule has a makespan lower than the current best, update the time and set the new schedule as the current best
         if min_time.1 > time {
             min_time = (test_schedule, time);
@@ -69,12 +135,156 @@ pub fn insert_job(makespan: &mut Makespan, schedule: &Vec<u32>, next_job: &u32)
     return min_time;
 }
 
+// Initial order of the jobs of the NEH variants, see the top of the file
+pub fn initial_order(instance: &Instance, order: NehOrder, rng: &mut StdRng) -> Vec<u32> {
+    let n_jobs = instance.jobs as usize;
+    let n_stages = instance.stages as usize;
+
+    match order {
+        NehOrder::TotalProcessing => sort_jobs(instance),
+        NehOrder::ProcessingAndSetup => {
+            let key: Vec<f64> = (0..n_jobs)
+                .map(|job| {
+                    (0..n_stages)
+                        .filter(|&stage| instance.processing_times[job][stage] != 0)
+                        .map(|stage| {
+                            let setups: Vec<u32> = (0..n_jobs)
+                                .filter(|&prev| {
+                                    prev != job && instance.processing_times[prev][stage] != 0
+                                })
+                                .map(|prev| instance.setup_times[stage][prev][job])
+                                .collect();
+                            let average = match setups.len() {
+                                0 => instance.setup_times[stage][job][job] as f64,
+                                len => setups.iter().sum::<u32>() as f64 / len as f64,
+                            };
+                            instance.processing_times[job][stage] as f64 + average
+                        })
+                        .sum()
+                })
+                .collect();
+
+            let mut jobs: Vec<u32> = (0..instance.jobs).collect();
+            jobs.sort_by(|&a, &b| key[b as usize].total_cmp(&key[a as usize]));
+            jobs
+        }
+        NehOrder::Bottleneck => {
+            let operations = bounds::operation_times(instance);
+            let bottleneck = bounds::bottleneck_stage(instance, &operations);
+
+            let mut jobs = sort_jobs(instance);
+            jobs.sort_by(|&a, &b| {
+                operations[b as usize][bottleneck].cmp(&operations[a as usize][bottleneck])
+            });
+            jobs
+        }
+        NehOrder::Johnson => {
+            let operations = bounds::operation_times(instance);
+            let time = |job: usize, stage: usize| -> f64 {
+                operations[job][stage] as f64 / instance.machines[stage] as f64
+            };
+            // Times of the jobs on the two virtual stages
+            let (a, b): (Vec<f64>, Vec<f64>) = (0..n_jobs)
+                .map(|job| {
+                    (
+                        (0..n_stages.div_ceil(2))
+                            .map(|stage| time(job, stage))
+                            .sum::<f64>(),
+                        (n_stages / 2..n_stages)
+                            .map(|stage| time(job, stage))
+                            .sum::<f64>(),
+                    )
+                })
+                .unzip();
+
+            let (mut first, mut last): (Vec<u32>, Vec<u32>) =
+                (0..instance.jobs).partition(|&job| a[job as usize] < b[job as usize]);
+            first.sort_by(|&i, &j| a[i as usize].total_cmp(&a[j as usize]));
+            last.sort_by(|&i, &j| b[j as usize].total_cmp(&b[i as usize]));
+            first.append(&mut last);
+            first
+        }
+        NehOrder::Random => {
+            let mut jobs: Vec<u32> = (0..instance.jobs).collect();
+            jobs.shuffle(rng);
+            jobs
+        }
+    }
+}
+
+// Returns the schedule with the lowest objective value after inserting the next job in all positions, with ties
+// broken by the rule, and the objective value of the schedule
+pub fn insert_job_tie_break(
+    makespan: &mut Makespan,
+    schedule: &[u32],
+    next_job: u32,
+    tie_break: TieBreak,
+    rng: &mut StdRng,
+) -> (Vec<u32>, u32) {
+    let candidates: Vec<(Vec<u32>, u32)> = (0..=schedule.len())
+        .map(|index| {
+            let mut test_schedule = schedule.to_vec();
+            test_schedule.insert(index, next_job);
+            let time = makespan.evaluate(&test_schedule);
+            (test_schedule, time)
+        })
+        .collect();
+
+    let best = candidates.iter().map(|c| c.1).min().unwrap();
+    let mut ties: Vec<(Vec<u32>, u32)> = candidates.into_iter().filter(|c| c.1 == best).collect();
+
+    let index = match tie_break {
+        _ if ties.len() == 1 => 0,
+        TieBreak::First => 0,
+        TieBreak::Last => ties.len() - 1,
+        TieBreak::IdleTime => {
+            let idle: Vec<u32> = ties
+                .iter()
+                .map(|(test_schedule, _)| {
+                    let (_, machine_completions) = makespan.makespan(test_schedule);
+                    idle_time(&makespan.instance, &machine_completions)
+                })
+                .collect();
+            (0..ties.len()).min_by_key(|&i| (idle[i], i)).unwrap()
+        }
+        TieBreak::Random => rng.gen_range(0..ties.len()),
+    };
+    ties.swap_remove(index)
+}
+
+// Total time the machines are not processing or setting up before their last job completes
+pub fn idle_time(instance: &Instance, machine_completions: &[Vec<Vec<(u32, u32)>>]) -> u32 {
+    let mut idle = 0;
+    for (stage, machines) in machine_completions.iter().enumerate() {
+        for (machine, runs) in machines.iter().enumerate() {
+            let (stage, machine) = (stage as u32, machine as u32);
+            let mut busy = 0;
+            for (i, &(job, _)) in runs.iter().enumerate() {
+                // Jobs skipping the stage are dispatched without taking time, as in the decoding
+                if instance.processing_times[job as usize][stage as usize] == 0 {
+                    continue;
+                }
+                let prev_job = if i == 0 { job } else { runs[i - 1].0 };
+                busy += instance.processing_time(job, stage, machine)
+                    + instance.setup_time(stage, machine, prev_job, job);
+            }
+            idle += runs.last().map_or(0, |&(_, completion)| completion - busy);
+        }
+    }
+    idle
+}
+
 #[cfg(test)]
 mod test {
-    use crate::common::{instance::parse, instance::Instance, makespan::Makespan};
+    use crate::common::{
+        decoding::DecodingType, instance::parse, instance::Instance, makespan::Makespan,
+    };
+    use rand::{prelude::StdRng, SeedableRng};
     use std::env;
 
-    use super::{insert_job, sort_jobs, NEH};
+    use super::{
+        initial_order, insert_job, insert_job_tie_break, sort_jobs, NehOrder, TieBreak, NEH,
+    };
 
     #[test]
     fn sort_jobs_test() {
@@ -84,10 +294,7 @@ mod test {
             _ => "./instances/ruiz/json/n20m2-1.0json",
         };
         let i: Instance = parse(path).unwrap();
-        let m: Makespan = Makespan {
-            count: 1,
-            instance: i,
-        };
+        let m: Makespan = Makespan::new(&i, DecodingType::Fifo);
         let order = sort_jobs(&m.instance);
         println!("{:?}", order);
     }
@@ -100,10 +307,7 @@ mod test {
             _ => "./instances/ruiz/json/n20m2-01.json",
         };
         let i: Instance = parse(path).unwrap();
-        let mut m: Makespan = Makespan {
-            count: 1,
-            instance: i,
-        };
+        let mut m: Makespan = Makespan::new(&i, DecodingType::Fifo);
         let order: Vec<u32> = sort_jobs(&m.instance);
         let schedule: Vec<u32> = order[0..4].to_vec();
         let _new_schedule = insert_job(&mut m, &schedule, &order[5]);
@@ -111,7 +315,7 @@ mod test {
 
     fn neh_from_file(path: &str) -> u32 {
         let i: Instance = parse(path).unwrap();
-        let mut m = Makespan::new(&i);
+        let mut m = Makespan::new(&i, DecodingType::Fifo);
 
         let schedule = NEH::neh(&mut m);
         let (make, _) = m.makespan(&schedule.0);
@@ -142,11 +346,72 @@ mod test {
         assert_eq!(makespan, 598);
     }
 
+    #[test]
+    fn neh_variants() {
+        let i: Instance = parse("./instances/ruiz/json/n20m2-43.json").unwrap();
+        let mut m = Makespan::new_incremental(&i, DecodingType::Fifo);
+        let mut rng = StdRng::seed_from_u64(123);
+        let jobs: Vec<u32> = (0..i.jobs).collect();
+
+        // The default variant is the NEH
+        let neh = NEH::neh(&mut m);
+        assert_eq!(
+            NEH::neh_variant(&mut m, NehOrder::TotalProcessing, TieBreak::First, &mut rng),
+            neh
+        );
+
+        for order in [
+            NehOrder::TotalProcessing,
+            NehOrder::ProcessingAndSetup,
+            NehOrder::Bottleneck,
+            NehOrder::Johnson,
+            NehOrder::Random,
+        ] {
+            let mut sorted = initial_order(&i, order, &mut rng);
+            sorted.sort();
+            assert_eq!(sorted, jobs, "{:?}", order);
+
+            for tie_break in [
+                TieBreak::First,
+                TieBreak::Last,
+                TieBreak::IdleTime,
+                TieBreak::Random,
+            ] {
+                let (schedule, value) = NEH::neh_variant(&mut m, order, tie_break, &mut rng);
+                let mut sorted = schedule.clone();
+                sorted.sort();
+                assert_eq!(sorted, jobs);
+                assert_eq!(m.evaluate(&schedule), value);
+            }
+        }
+    }
+
+    #[test]
+    fn tie_breaking() {
+        let i: Instance = parse("./instances/ruiz/json/n20m2-43.json").unwrap();
+        let mut m = Makespan::new_incremental(&i, DecodingType::Fifo);
+        let mut rng = StdRng::seed_from_u64(123);
+        let schedule: Vec<u32> = (0..10).collect();
+
+        // The positions of the tie-breaking rules are all among the best insertions
+        let first = insert_job_tie_break(&mut m, &schedule, 10, TieBreak::First, &mut rng);
+        assert_eq!(first, insert_job(&mut m, &schedule, &10));
+        for tie_break in [TieBreak::Last, TieBreak::IdleTime, TieBreak::Random] {
+            let (inserted, value) =
+                insert_job_tie_break(&mut m, &schedule, 10, tie_break, &mut rng);
+            assert_eq!(value, first.1);
+            assert_eq!(m.evaluate(&inserted), value);
+        }
+        let last = insert_job_tie_break(&mut m, &schedule, 10, TieBreak::Last, &mut rng);
+        let position = |s: &Vec<u32>| s.iter().position(|&job| job == 10).unwrap();
+        assert!(position(&last.0) >= position(&first.0));
+    }
+
     #[test]
     fn schedule() {
         let i: Instance = parse("./instances/ruiz/json/n20m2-01.json").unwrap();
         let jobs = (0..i.jobs).collect();
-        let mut m = Makespan::new(&i);
+        let mut m = Makespan::new(&i, DecodingType::Fifo);
 
         let (make, mc) = m.makespan(&jobs);
         dbg!(make);
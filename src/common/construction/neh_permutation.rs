@@ -33,7 +33,7 @@ impl NehP {
         // Note: this is simply the job_completion for the previous stage, but it needs to be artificially made the first time
         let mut prev_stage_completions: Vec<(u32, u32)> = Vec::with_capacity(n_jobs);
         for job in 0..n_jobs {
-            prev_stage_completions.push((job_order[job], 0));
+            prev_stage_completions.push((job_order[job], instance.release_date(job_order[job])));
         }
 
         // Iterate permutation, find best machine for jobs in the initial order and assign it
@@ -83,7 +83,14 @@ impl Solver for NehP {
 
 #[cfg(test)]
 mod test {
-    use crate::common::{construction::solver::Solver, instance::parse, makespan::Makespan};
+    use crate::{
+        common::{
+            construction::solver::Solver, decoding::DecodingType, instance::parse,
+            makespan::Makespan, objective::ObjectiveType, utils,
+        },
+        genetic_algorithm::params,
+        iterated_greedy::options::Options,
+    };
 
     use super::NehP;
 
@@ -101,7 +108,7 @@ mod test {
     fn test_neh_permutation() {
         let instance = parse("./instances/ruiz/json/n20m2-01.json").unwrap();
 
-        let mut makespan = Makespan::new(&instance);
+        let mut makespan = Makespan::new(&instance, DecodingType::Fifo);
 
         let (_, mks) = NehP::neh_p(&mut makespan);
 
@@ -110,6 +117,13 @@ mod test {
 
     #[test]
     fn run_all() {
-        NehP::run_all("./solutions/neh_permutation");
+        NehP::run_all(
+            utils::get_problem_files(true),
+            "./solutions/neh_permutation",
+            Options::default(),
+            DecodingType::Fifo,
+            ObjectiveType::Makespan,
+            &params::OBJECTIVE_WEIGHTS,
+        );
     }
 }
@@ -2,13 +2,16 @@ use lexical_sort::natural_lexical_cmp;
 use rayon::prelude::*;
 use std::{
     borrow::Cow,
+    path::PathBuf,
     sync::{Arc, Mutex},
 };
 
 use crate::{
     common::{
+        decoding::DecodingType,
         instance::{parser::parse, Instance},
         makespan::Makespan,
+        objective::ObjectiveType,
         utils,
     },
     iterated_greedy::options::{Options, OptionsGrid},
@@ -17,16 +20,16 @@ use crate::{
 pub trait Solver {
     fn run(makespan: &mut Makespan, options: Option<Options>) -> u32;
 
-    fn run_all(result_folder: &str) {
-        // Get vector of all problem files (twice as we have to consume them)
-        let problem_files = utils::get_problem_files(true);
-        let problem_files_consumed = utils::get_problem_files(true);
-
-        // Make sure problem files are in same order
-        assert_eq!(
-            &problem_files, &problem_files_consumed,
-            "Order of problem files does not equal"
-        );
+    // Runs all problem files for all parameter combinations of the options grid, starting from the given options
+    fn run_all(
+        problem_files: Vec<PathBuf>,
+        result_folder: &str,
+        options: Options,
+        decoding: DecodingType,
+        objective: ObjectiveType,
+        objective_weights: &[(ObjectiveType, u32)],
+    ) {
+        let problem_files_consumed = problem_files.clone();
 
         let num_problems = problem_files.len();
 
@@ -49,7 +52,9 @@ pub trait Solver {
                 // Store filename and result from each parameter combination in vector
                 let mut row = Vec::new();
 
-                let i: Instance = parse(problem_files_consumed.get(idx).unwrap()).unwrap();
+                let path = problem_files_consumed.get(idx).unwrap();
+                let i: Instance = parse(path)
+                    .unwrap_or_else(|e| panic!("Invalid problem file {}: {}", path.display(), e));
 
                 row.push(String::from(
                     problem_files_consumed.get(idx).unwrap().to_str().unwrap(),
@@ -57,12 +62,14 @@ pub trait Solver {
 
                 let options = Options {
                     problem_file: Cow::Owned(problem_file),
-                    ..Options::default()
+                    ..options.clone()
                 };
 
                 let all_options = OptionsGrid::default().get_options(options);
 
-                let mut m: Makespan = Makespan::new(&i);
+                let mut m: Makespan = Makespan::new_incremental(&i, decoding);
+                m.objective = objective;
+                m.objective_weights = objective_weights.to_vec();
 
                 all_options.into_iter().for_each(|option| {
                     let makespan = Self::run(&mut m, Some(option));
@@ -82,7 +89,7 @@ pub trait Solver {
             .unwrap()
             .sort_by(|a, b| natural_lexical_cmp(&a[0], &b[0]));
 
-        utils::write_results(result_folder, &results.lock().unwrap()).unwrap();
+        utils::write_results(result_folder, None, options.seed, &results.lock().unwrap()).unwrap();
 
         println!(
             "All problems run, results are stored in `{}`",
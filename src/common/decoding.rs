@@ -0,0 +1,480 @@
+use core::cmp::max;
+
+use clap::ArgEnum;
+use serde_derive::{Deserialize, Serialize};
+
+use super::{instance::Instance, makespan::Makespan};
+
+/*
+A decoding rule decides, for one stage at a time, in which order the jobs are dispatched and which
+machine each job is put on. All rules schedule the first stage in permutation order, so that the
+permutation still decides the schedule. Jobs with zero processing time in a stage skip it: they are not put
+on a machine, and leave the stage when they are ready (see `Makespan::fifo`).
+
+Skipped stages were decoded differently before: a job skipping a stage was given the completion time of the
+job dispatched before it in the stage. That time can be before the job is ready, so the job could start its
+next stage before completing its previous one, and the decoded schedules of instances with skipped stages
+(almost all Ruiz instances) were often not feasible (see common::instance::feasibility). Makespans of those
+instances, e.g. in the result files under solutions/, are therefore not comparable to the current decoder.
+Instances where all jobs visit all stages decode as before.
+
+prev_stage_completions[job](job_number, ready_time) is in the order the jobs left the previous stage,
+which is the permutation order in the first stage
+*/
+
+#[allow(dead_code)]
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum DecodingType {
+    Fifo,
+    FirstComplete,
+    ShortestSetup,
+    LeastLoaded,
+    PermutationOrder,
+}
+
+pub trait DecodingRule {
+    fn schedule(
+        stage: &u32,
+        job_order: &[u32],
+        prev_stage_completions: Vec<(u32, u32)>,
+        job_completions: &mut Vec<Vec<(u32, u32)>>,
+        machine_completions: &mut Vec<Vec<Vec<(u32, u32)>>>,
+        instance: &Instance,
+    );
+}
+
+pub struct Fifo;
+pub struct FirstComplete;
+pub struct ShortestSetup;
+pub struct LeastLoaded;
+pub struct PermutationOrder;
+
+impl DecodingType {
+    // Whether the dispatch order of a stage is known before the stage is scheduled, which is required by `Makespan::evaluate`
+    pub fn fixed_dispatch_order(&self) -> bool {
+        matches!(self, DecodingType::Fifo | DecodingType::PermutationOrder)
+    }
+}
+
+// Schedules all jobs of the stage with the given rule
+pub fn schedule_stage(
+    rule: DecodingType,
+    stage: &u32,
+    job_order: &[u32],
+    prev_stage_completions: Vec<(u32, u32)>,
+    job_completions: &mut Vec<Vec<(u32, u32)>>,
+    machine_completions: &mut Vec<Vec<Vec<(u32, u32)>>>,
+    instance: &Instance,
+) {
+    let schedule = match rule {
+        DecodingType::Fifo => Fifo::schedule,
+        DecodingType::FirstComplete => FirstComplete::schedule,
+        DecodingType::ShortestSetup => ShortestSetup::schedule,
+        DecodingType::LeastLoaded => LeastLoaded::schedule,
+        DecodingType::PermutationOrder => PermutationOrder::schedule,
+    };
+
+    schedule(
+        stage,
+        job_order,
+        prev_stage_completions,
+        job_completions,
+        machine_completions,
+        instance,
+    );
+}
+
+impl DecodingRule for Fifo {
+    // Jobs are dispatched in order of ready times, each on the machine that completes it the quickest
+    fn schedule(
+        stage: &u32,
+        _job_order: &[u32],
+        mut prev_stage_completions: Vec<(u32, u32)>,
+        job_completions: &mut Vec<Vec<(u32, u32)>>,
+        machine_completions: &mut Vec<Vec<Vec<(u32, u32)>>>,
+        instance: &Instance,
+    ) {
+        // Sort according to ready times of each job, except in the first stage where the permutation is used
+        if *stage != 0 {
+            prev_stage_completions.sort_by_key(|&(_, time)| time);
+        }
+
+        for (job, prev_stage_completion_time) in prev_stage_completions.iter() {
+            Makespan::fifo(
+                job,
+                stage,
+                prev_stage_completion_time,
+                job_completions,
+                machine_completions,
+                instance,
+            );
+        }
+    }
+}
+
+impl DecodingRule for FirstComplete {
+    // The job that can be completed the quickest is scheduled next, on the machine that completes it
+    fn schedule(
+        stage: &u32,
+        job_order: &[u32],
+        mut prev_stage_completions: Vec<(u32, u32)>,
+        job_completions: &mut Vec<Vec<(u32, u32)>>,
+        machine_completions: &mut Vec<Vec<Vec<(u32, u32)>>>,
+        instance: &Instance,
+    ) {
+        if *stage == 0 {
+            return Fifo::schedule(
+                stage,
+                job_order,
+                prev_stage_completions,
+                job_completions,
+                machine_completions,
+                instance,
+            );
+        }
+
+        while !prev_stage_completions.is_empty() {
+            Makespan::earliest_completion_time(
+                stage,
+                &mut prev_stage_completions,
+                job_completions,
+                machine_completions,
+                instance,
+            );
+        }
+    }
+}
+
+impl DecodingRule for ShortestSetup {
+    // Whenever a machine becomes available, the waiting job with the shortest setup time on it is scheduled
+    fn schedule(
+        stage: &u32,
+        job_order: &[u32],
+        mut prev_stage_completions: Vec<(u32, u32)>,
+        job_completions: &mut Vec<Vec<(u32, u32)>>,
+        machine_completions: &mut Vec<Vec<Vec<(u32, u32)>>>,
+        instance: &Instance,
+    ) {
+        if *stage == 0 {
+            return Fifo::schedule(
+                stage,
+                job_order,
+                prev_stage_completions,
+                job_completions,
+                machine_completions,
+                instance,
+            );
+        }
+
+        let s = *stage as usize;
+        // Keep jobs in order of ready times, so ties are broken by the job that arrived first
+        prev_stage_completions.sort_by_key(|&(_, time)| time);
+
+        while !prev_stage_completions.is_empty() {
+            // Machines in the order they become available
+            let mut machines: Vec<(usize, u32)> = machine_completions[s]
+                .iter()
+                .map(|runs| runs.last().map_or(0, |&(_, time)| time))
+                .enumerate()
+                .collect();
+            machines.sort_by_key(|&(_, time)| time);
+
+            let eligible = |job: u32, machine: usize| {
+                instance.processing_times[job as usize][s] == 0
+                    || instance.is_eligible(job, *stage, machine as u32)
+            };
+            let setup = |job: u32, machine: usize| match instance.processing_times[job as usize][s]
+            {
+                0 => 0,
+                // If the machine has not processed any jobs yet, setup_times[stage][job][job] gives the initial setup time
+                _ => {
+                    let prev_job = machine_completions[s][machine].last().map(|&(job, _)| job);
+                    instance.setup_time(*stage, machine as u32, prev_job.unwrap_or(job), job)
+                }
+            };
+
+            // The first machine to become available that any of the remaining jobs can be processed on
+            let (machine, machine_ready_time, index) = machines
+                .iter()
+                .find_map(|&(machine, machine_ready_time)| {
+                    let mut waiting = prev_stage_completions
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, &(job, _))| eligible(job, machine))
+                        .peekable();
+
+                    // Jobs waiting when the machine is available, or the first job to arrive if none are
+                    let first_ready = waiting.peek()?.1 .1;
+                    let cutoff = max(machine_ready_time, first_ready);
+
+                    let index = waiting
+                        .take_while(|(_, &(_, time))| time <= cutoff)
+                        .min_by_key(|(_, &(job, _))| setup(job, machine))
+                        .unwrap()
+                        .0;
+                    Some((machine, machine_ready_time, index))
+                })
+                .unwrap();
+            let (job, prev_stage_completion_time) = prev_stage_completions.remove(index);
+
+            if instance.processing_times[job as usize][s] == 0 {
+                Makespan::fifo(
+                    &job,
+                    stage,
+                    &prev_stage_completion_time,
+                    job_completions,
+                    machine_completions,
+                    instance,
+                );
+                continue;
+            }
+
+            let time = max(machine_ready_time, prev_stage_completion_time)
+                + setup(job, machine)
+                + instance.processing_time(job, *stage, machine as u32);
+
+            job_completions[s].push((job, time));
+            machine_completions[s][machine].push((job, time));
+        }
+    }
+}
+
+impl DecodingRule for LeastLoaded {
+    // Jobs are dispatched in order of ready times, each on the machine with the least setup and processing time assigned so far
+    fn schedule(
+        stage: &u32,
+        _job_order: &[u32],
+        mut prev_stage_completions: Vec<(u32, u32)>,
+        job_completions: &mut Vec<Vec<(u32, u32)>>,
+        machine_completions: &mut Vec<Vec<Vec<(u32, u32)>>>,
+        instance: &Instance,
+    ) {
+        let s = *stage as usize;
+        if *stage != 0 {
+            prev_stage_completions.sort_by_key(|&(_, time)| time);
+        }
+
+        // loads[machine] is the total setup and processing time assigned to the machine
+        let mut loads: Vec<u32> = vec![0; machine_completions[s].len()];
+
+        for (job, prev_stage_completion_time) in prev_stage_completions.iter() {
+            if instance.processing_times[*job as usize][s] == 0 {
+                Makespan::fifo(
+                    job,
+                    stage,
+                    prev_stage_completion_time,
+                    job_completions,
+                    machine_completions,
+                    instance,
+                );
+                continue;
+            }
+
+            // Least loaded machine, ties broken by the earliest completion
+            let (time, machine, busy) = machine_completions[s]
+                .iter()
+                .enumerate()
+                .filter(|&(machine, _)| instance.is_eligible(*job, *stage, machine as u32))
+                .map(|(machine, runs)| {
+                    let (prev_job, machine_ready_time) = *runs.last().unwrap_or(&(*job, 0));
+                    let busy = instance.setup_time(*stage, machine as u32, prev_job, *job)
+                        + instance.processing_time(*job, *stage, machine as u32);
+                    let time = max(machine_ready_time, *prev_stage_completion_time) + busy;
+                    (time, machine, busy)
+                })
+                .min_by_key(|&(time, machine, _)| (loads[machine], time))
+                .unwrap();
+
+            loads[machine] += busy;
+            job_completions[s].push((*job, time));
+            machine_completions[s][machine].push((*job, time));
+        }
+    }
+}
+
+impl DecodingRule for PermutationOrder {
+    // Jobs are dispatched in permutation order in every stage, each on the machine that completes it the quickest
+    fn schedule(
+        stage: &u32,
+        job_order: &[u32],
+        prev_stage_completions: Vec<(u32, u32)>,
+        job_completions: &mut Vec<Vec<(u32, u32)>>,
+        machine_completions: &mut Vec<Vec<Vec<(u32, u32)>>>,
+        instance: &Instance,
+    ) {
+        for (job, prev_stage_completion_time) in
+            permutation_order(job_order, &prev_stage_completions, instance)
+        {
+            Makespan::fifo(
+                &job,
+                stage,
+                &prev_stage_completion_time,
+                job_completions,
+                machine_completions,
+                instance,
+            );
+        }
+    }
+}
+
+// Ready times of the jobs, listed in permutation order
+pub fn permutation_order(
+    job_order: &[u32],
+    prev_stage_completions: &[(u32, u32)],
+    instance: &Instance,
+) -> Vec<(u32, u32)> {
+    let mut ready_times: Vec<u32> = vec![0; instance.jobs as usize];
+    for (job, time) in prev_stage_completions.iter() {
+        ready_times[*job as usize] = *time;
+    }
+
+    job_order
+        .iter()
+        .map(|&job| (job, ready_times[job as usize]))
+        .collect()
+}
+
+#[cfg(test)]
+mod decoding_test {
+    use itertools::Itertools;
+
+    use crate::common::{
+        construction::mddr::MDDR,
+        instance::{parse, Instance},
+        makespan::Makespan,
+    };
+
+    use super::DecodingType;
+
+    const RULES: [DecodingType; 5] = [
+        DecodingType::Fifo,
+        DecodingType::FirstComplete,
+        DecodingType::ShortestSetup,
+        DecodingType::LeastLoaded,
+        DecodingType::PermutationOrder,
+    ];
+
+    #[test]
+    fn all_rules_schedule_every_job() {
+        let instance = parse("./instances/ruiz/json/n20m4-01.json").unwrap();
+        let schedule: Vec<u32> = (0..instance.jobs).rev().collect();
+
+        for rule in RULES {
+            let mut makespan = Makespan::new(&instance, rule);
+            let (mks, machine_completions) = makespan.makespan(&schedule);
+
+            for (stage, stage_completions) in machine_completions.iter().enumerate() {
+                // Every job processed in the stage is on exactly one machine
+                let jobs = stage_completions
+                    .iter()
+                    .flatten()
+                    .map(|(j, _)| *j)
+                    .filter(|&j| instance.processing_times[j as usize][stage] != 0)
+                    .sorted()
+                    .collect::<Vec<u32>>();
+                let visiting = (0..instance.jobs)
+                    .filter(|&j| instance.processing_times[j as usize][stage] != 0)
+                    .collect::<Vec<u32>>();
+                assert_eq!(jobs, visiting, "{:?}", rule);
+
+                // Completions on a machine are increasing, and never later than the makespan
+                for runs in stage_completions.iter() {
+                    assert!(runs.windows(2).all(|w| w[0].1 <= w[1].1), "{:?}", rule);
+                    assert!(runs.iter().all(|(_, t)| *t <= mks), "{:?}", rule);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn evaluate_equals_makespan_for_all_rules() {
+        let instance = parse("./instances/ruiz/json/n20m4-01.json").unwrap();
+        let schedule: Vec<u32> = (0..instance.jobs).collect();
+
+        for rule in RULES {
+            let mut full = Makespan::new(&instance, rule);
+            let mut incremental = Makespan::new_incremental(&instance, rule);
+
+            for index in 0..schedule.len() {
+                let mut candidate = schedule.clone();
+                let job = candidate.remove(index);
+                candidate.insert(0, job);
+
+                assert_eq!(
+                    incremental.evaluate(&candidate),
+                    full.makespan(&candidate).0,
+                    "{:?}",
+                    rule
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn rules_give_different_makespans() {
+        let instance = parse("./instances/ruiz/json/n50m8-01.json").unwrap();
+        let schedule: Vec<u32> = (0..instance.jobs).collect();
+
+        let makespans = RULES
+            .iter()
+            .map(|&rule| Makespan::new(&instance, rule).makespan(&schedule).0)
+            .collect::<Vec<u32>>();
+
+        assert!(makespans.iter().unique().count() > 1);
+    }
+
+    // Job j can only be processed on machine j % machines and the machines with the same parity as j
+    fn restricted_instance() -> Instance {
+        let mut instance = parse("./instances/ruiz/json/n20m4-01.json").unwrap();
+        instance.eligibility = Some(
+            (0..instance.jobs as usize)
+                .map(|job| {
+                    instance
+                        .machines
+                        .iter()
+                        .map(|&machines| {
+                            (0..machines as usize)
+                                .map(|m| m == job % machines as usize || (job + m) % 2 == 0)
+                                .collect()
+                        })
+                        .collect()
+                })
+                .collect(),
+        );
+        assert!(instance.validate().is_ok());
+        instance
+    }
+
+    fn assert_eligible(instance: &Instance, machine_completions: &[Vec<Vec<(u32, u32)>>]) {
+        for (stage, stage_completions) in machine_completions.iter().enumerate() {
+            for (machine, runs) in stage_completions.iter().enumerate() {
+                for &(job, _) in runs.iter() {
+                    assert!(
+                        instance.processing_times[job as usize][stage] == 0
+                            || instance.is_eligible(job, stage as u32, machine as u32),
+                        "job {} on machine {} in stage {}",
+                        job,
+                        machine,
+                        stage
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn all_rules_respect_eligibility() {
+        let instance = restricted_instance();
+        let schedule: Vec<u32> = (0..instance.jobs).rev().collect();
+
+        for rule in RULES {
+            let mut makespan = Makespan::new(&instance, rule);
+            let (_, machine_completions) = makespan.makespan(&schedule);
+            assert_eligible(&instance, &machine_completions);
+        }
+
+        let (_, machine_completions) =
+            MDDR::mddr(&mut Makespan::new(&instance, DecodingType::Fifo));
+        assert_eligible(&instance, &machine_completions);
+    }
+}
@@ -0,0 +1,374 @@
+use std::fmt;
+
+use super::instance::Instance;
+
+/*
+Feasibility check of a schedule, given as machine_completions[stage][machine][machine_run](job, completion_time)
+as written in solution files. Start times are not part of a solution, so each job is assumed to start its
+setup as late as possible, i.e. at completion - processing time - setup time on its machine.
+
+A schedule is feasible if
+    - every job is processed exactly once in each stage it visits (non-zero processing time), on an eligible machine
+    - no job is processed in a stage it skips
+    - the setup of a job does not start before the job is released or has completed its previous stage
+    - the setup of a job does not start before the previous job on the machine has completed
+    - the reported makespan is the largest completion time
+Setups can not be done before the job arrives at the machine, as in the decoder (see common::makespan).
+All violations are reported, not only the first one.
+*/
+
+#[derive(Debug, PartialEq)]
+pub enum Violation {
+    StageCount {
+        expected: usize,
+        found: usize,
+    },
+    MachineCount {
+        stage: usize,
+        expected: usize,
+        found: usize,
+    },
+    UnknownJob {
+        job: u32,
+        stage: usize,
+        machine: usize,
+    },
+    // The job has zero processing time in the stage, but is scheduled on a machine
+    SkippedStage {
+        job: u32,
+        stage: usize,
+    },
+    MissingStage {
+        job: u32,
+        stage: usize,
+    },
+    RepeatedStage {
+        job: u32,
+        stage: usize,
+    },
+    Ineligible {
+        job: u32,
+        stage: usize,
+        machine: usize,
+    },
+    // Setup starts before the job is released or completed in its previous stage
+    Precedence {
+        job: u32,
+        stage: usize,
+        start: i64,
+        ready: u32,
+    },
+    // Setup starts before the previous job on the machine is completed (or before time 0)
+    Overlap {
+        job: u32,
+        stage: usize,
+        machine: usize,
+        start: i64,
+        machine_ready: u32,
+    },
+    MakespanMismatch {
+        reported: u32,
+        actual: u32,
+    },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Violation::StageCount { expected, found } => {
+                write!(f, "schedule has {} stages, expected {}", found, expected)
+            }
+            Violation::MachineCount {
+                stage,
+                expected,
+                found,
+            } => write!(
+                f,
+                "stage {} has {} machines, expected {}",
+                stage, found, expected
+            ),
+            Violation::UnknownJob {
+                job,
+                stage,
+                machine,
+            } => write!(
+                f,
+                "unknown job {} on machine {} in stage {}",
+                job, machine, stage
+            ),
+            Violation::SkippedStage { job, stage } => {
+                write!(
+                    f,
+                    "job {} is scheduled in stage {}, which it skips",
+                    job, stage
+                )
+            }
+            Violation::MissingStage { job, stage } => {
+                write!(f, "job {} is not scheduled in stage {}", job, stage)
+            }
+            Violation::RepeatedStage { job, stage } => {
+                write!(
+                    f,
+                    "job {} is scheduled more than once in stage {}",
+                    job, stage
+                )
+            }
+            Violation::Ineligible {
+                job,
+                stage,
+                machine,
+            } => write!(
+                f,
+                "job {} is not eligible on machine {} in stage {}",
+                job, machine, stage
+            ),
+            Violation::Precedence {
+                job,
+                stage,
+                start,
+                ready,
+            } => write!(
+                f,
+                "job {} starts in stage {} at {}, but is not ready before {}",
+                job, stage, start, ready
+            ),
+            Violation::Overlap {
+                job,
+                stage,
+                machine,
+                start,
+                machine_ready,
+            } => write!(
+                f,
+                "job {} starts on machine {} in stage {} at {}, but the machine is busy until {}",
+                job, machine, stage, start, machine_ready
+            ),
+            Violation::MakespanMismatch { reported, actual } => {
+                write!(f, "reported makespan {} is not {}", reported, actual)
+            }
+        }
+    }
+}
+
+// Returns all violations of the schedule, an empty list if it is feasible
+pub fn check(
+    instance: &Instance,
+    machine_completions: &[Vec<Vec<(u32, u32)>>],
+    makespan: u32,
+) -> Vec<Violation> {
+    let mut violations = Vec::new();
+    let n_jobs = instance.jobs as usize;
+    let n_stages = instance.stages as usize;
+
+    if machine_completions.len() != n_stages {
+        violations.push(Violation::StageCount {
+            expected: n_stages,
+            found: machine_completions.len(),
+        });
+        return violations;
+    }
+
+    // scheduled[job][stage](start, completion_time) of every time the job is processed in the stage
+    let mut scheduled: Vec<Vec<Vec<(i64, u32)>>> = vec![vec![Vec::new(); n_stages]; n_jobs];
+    let mut actual_makespan = 0;
+
+    for (stage, machines) in machine_completions.iter().enumerate() {
+        let n_machines = instance.machines[stage] as usize;
+        if machines.len() != n_machines {
+            violations.push(Violation::MachineCount {
+                stage,
+                expected: n_machines,
+                found: machines.len(),
+            });
+            continue;
+        }
+
+        for (machine, machine_run) in machines.iter().enumerate() {
+            let mut prev: Option<(u32, u32)> = None;
+
+            for &(job, completion) in machine_run.iter() {
+                if job as usize >= n_jobs {
+                    violations.push(Violation::UnknownJob {
+                        job,
+                        stage,
+                        machine,
+                    });
+                    continue;
+                }
+                if instance.processing_times[job as usize][stage] == 0 {
+                    violations.push(Violation::SkippedStage { job, stage });
+                    continue;
+                }
+                if !instance.is_eligible(job, stage as u32, machine as u32) {
+                    violations.push(Violation::Ineligible {
+                        job,
+                        stage,
+                        machine,
+                    });
+                }
+
+                // setup_times[stage][job][job] is the initial setup of the first job on a machine
+                let (prev_job, machine_ready) = prev.unwrap_or((job, 0));
+                let busy = instance.processing_time(job, stage as u32, machine as u32)
+                    + instance.setup_time(stage as u32, machine as u32, prev_job, job);
+                let start = completion as i64 - busy as i64;
+
+                if start < machine_ready as i64 {
+                    violations.push(Violation::Overlap {
+                        job,
+                        stage,
+                        machine,
+                        start,
+                        machine_ready,
+                    });
+                }
+
+                scheduled[job as usize][stage].push((start, completion));
+                actual_makespan = actual_makespan.max(completion);
+                prev = Some((job, completion));
+            }
+        }
+    }
+
+    for (job, stages) in scheduled.iter().enumerate() {
+        let job = job as u32;
+        let mut ready = instance.release_date(job);
+
+        for (stage, times) in stages.iter().enumerate() {
+            if instance.processing_times[job as usize][stage] == 0 {
+                continue;
+            }
+            match times.as_slice() {
+                [] => violations.push(Violation::MissingStage { job, stage }),
+                &[(start, completion)] => {
+                    if start < ready as i64 {
+                        violations.push(Violation::Precedence {
+                            job,
+                            stage,
+                            start,
+                            ready,
+                        });
+                    }
+                    ready = completion;
+                }
+                _ => violations.push(Violation::RepeatedStage { job, stage }),
+            }
+        }
+    }
+
+    if makespan != actual_makespan {
+        violations.push(Violation::MakespanMismatch {
+            reported: makespan,
+            actual: actual_makespan,
+        });
+    }
+
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        common::{
+            construction::{mddr::MDDR, neh::NEH},
+            decoding::DecodingType,
+            instance::parse,
+            makespan::Makespan,
+        },
+        genetic_algorithm::tests::tests::test_instance,
+    };
+
+    use super::{check, Violation};
+
+    #[test]
+    fn decoded_schedules_are_feasible() {
+        // All jobs visit all stages in n20m2-43, and some jobs skip stages in n50m4-10
+        for path in [
+            "./instances/ruiz/json/n20m2-43.json",
+            "./instances/ruiz/json/n50m4-10.json",
+        ] {
+            let instance = parse(path).unwrap();
+            let (permutation, _) = NEH::neh(&mut Makespan::new(&instance, DecodingType::Fifo));
+
+            for rule in [
+                DecodingType::Fifo,
+                DecodingType::FirstComplete,
+                DecodingType::ShortestSetup,
+                DecodingType::LeastLoaded,
+                DecodingType::PermutationOrder,
+            ] {
+                let (mks, machine_completions) =
+                    Makespan::new(&instance, rule).makespan(&permutation);
+                assert_eq!(
+                    check(&instance, &machine_completions, mks),
+                    vec![],
+                    "{} {:?}",
+                    path,
+                    rule
+                );
+            }
+
+            // The incremental evaluation gives the same makespan as the full decoding
+            let mut makespan = Makespan::new_incremental(&instance, DecodingType::Fifo);
+            assert_eq!(
+                makespan.evaluate(&permutation),
+                makespan.makespan(&permutation).0
+            );
+        }
+
+        // The dispatching rule of MDDR puts no job on a machine of a stage it skips
+        let instance = parse("./instances/ruiz/json/n50m4-10.json").unwrap();
+        let (mks, machine_completions) =
+            MDDR::mddr(&mut Makespan::new(&instance, DecodingType::Fifo));
+        assert_eq!(check(&instance, &machine_completions, mks), vec![]);
+    }
+
+    #[test]
+    fn reports_all_violations() {
+        let instance = test_instance();
+        let (mks, mut machine_completions) =
+            Makespan::new(&instance, DecodingType::Fifo).makespan(&vec![0, 1, 2, 3, 4]);
+
+        // Remove a job from the last stage, and process the first job of the first stage twice
+        let removed = machine_completions[1][0].remove(0);
+        let first = machine_completions[0][0][0];
+        machine_completions[0][1].insert(0, first);
+
+        let violations = check(&instance, &machine_completions, mks + 1);
+
+        assert!(violations.contains(&Violation::MissingStage {
+            job: removed.0,
+            stage: 1
+        }));
+        assert!(violations.contains(&Violation::RepeatedStage {
+            job: first.0,
+            stage: 0
+        }));
+        assert!(violations
+            .iter()
+            .any(|v| matches!(v, Violation::MakespanMismatch { .. })));
+    }
+
+    #[test]
+    fn overlap_and_precedence() {
+        let instance = test_instance();
+        let (mks, mut machine_completions) =
+            Makespan::new(&instance, DecodingType::Fifo).makespan(&vec![0, 1, 2, 3, 4]);
+
+        // Completing the second job on a machine earlier makes it overlap with the first one
+        let (job, completion) = machine_completions[0][0][1];
+        machine_completions[0][0][1] = (job, completion - 1);
+        // Completing a job in the last stage earlier makes it start before it has completed the first stage
+        let (job, completion) = machine_completions[1][0][0];
+        machine_completions[1][0][0] = (job, completion - 1);
+
+        let violations = check(&instance, &machine_completions, mks);
+
+        assert!(violations
+            .iter()
+            .any(|v| matches!(v, Violation::Overlap { stage: 0, .. })));
+        assert!(violations
+            .iter()
+            .any(|v| matches!(v, Violation::Precedence { stage: 1, .. })));
+    }
+}
@@ -1,25 +1,150 @@
-use serde_derive::Deserialize;
+use serde_derive::{Deserialize, Serialize};
 
 /*
 machines[stage]
 processing_times[job][stage]
 setup_times:[stage][previous_job][current_job]
 Note: When previous job and current is the same, it is the inital setup time
+
+Optional fields (may be left out of the JSON file):
+release_dates[job]: earliest time the job can start in the first stage (default 0)
+due_dates[job]: time the job should be completed by (default 0)
+weights[job]: importance of the job in weighted objectives (default 1)
+eligibility[job][stage][machine]: whether the job can be processed on the machine (default all eligible)
+
+Unrelated machines (detected by the parser, see parser.rs):
+machine_processing_times[job][stage][machine]: processing time of the job on each machine
+machine_setup_times[stage][machine][previous_job][current_job]: setup times on each machine
+processing_times and setup_times then hold the shortest time on any machine, which is used by heuristics
+that do not consider machines (e.g. the NEH ordering). A job skips a stage if it has zero processing
+time on all machines, and is not eligible on a machine with zero processing time in a stage it visits.
 */
 
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone, Default)]
 pub struct Instance {
     pub jobs: u32,
     pub stages: u32,
     pub machines: Vec<u32>,
     pub processing_times: Vec<Vec<u32>>,
     pub setup_times: Vec<Vec<Vec<u32>>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub release_dates: Option<Vec<u32>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub due_dates: Option<Vec<u32>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weights: Option<Vec<u32>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub eligibility: Option<Vec<Vec<Vec<bool>>>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub machine_processing_times: Option<Vec<Vec<Vec<u32>>>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub machine_setup_times: Option<Vec<Vec<Vec<Vec<u32>>>>>,
+}
+
+impl Instance {
+    pub fn release_date(&self, job: u32) -> u32 {
+        self.release_dates
+            .as_ref()
+            .map_or(0, |release_dates| release_dates[job as usize])
+    }
+
+    pub fn due_date(&self, job: u32) -> u32 {
+        self.due_dates
+            .as_ref()
+            .map_or(0, |due_dates| due_dates[job as usize])
+    }
+
+    pub fn weight(&self, job: u32) -> u32 {
+        self.weights
+            .as_ref()
+            .map_or(1, |weights| weights[job as usize])
+    }
+
+    // Processing time of the job on the given machine in the stage
+    pub fn processing_time(&self, job: u32, stage: u32, machine: u32) -> u32 {
+        match self.machine_processing_times.as_ref() {
+            Some(times) => times[job as usize][stage as usize][machine as usize],
+            None => self.processing_times[job as usize][stage as usize],
+        }
+    }
+
+    // Setup time between previous_job and job on the given machine in the stage
+    pub fn setup_time(&self, stage: u32, machine: u32, previous_job: u32, job: u32) -> u32 {
+        match self.machine_setup_times.as_ref() {
+            Some(times) => {
+                times[stage as usize][machine as usize][previous_job as usize][job as usize]
+            }
+            None => self.setup_times[stage as usize][previous_job as usize][job as usize],
+        }
+    }
+
+    // Whether the job can be processed on the machine. With unrelated machines, a machine with zero processing
+    // time does not process the job
+    pub fn is_eligible(&self, job: u32, stage: u32, machine: u32) -> bool {
+        let (job, stage, machine) = (job as usize, stage as usize, machine as usize);
+        self.eligibility
+            .as_ref()
+            .is_none_or(|eligibility| eligibility[job][stage][machine])
+            && self
+                .machine_processing_times
+                .as_ref()
+                .is_none_or(|times| times[job][stage][machine] != 0)
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::common::instance::validation::InstanceError;
+
+    use super::Instance;
+
     #[test]
     fn testing() {
         print!("yes")
     }
+
+    #[test]
+    fn eligibility_validation() {
+        let mut instance = Instance {
+            jobs: 2,
+            stages: 2,
+            machines: vec![2, 1],
+            processing_times: vec![vec![5, 0], vec![5, 5]],
+            setup_times: vec![vec![vec![0; 2]; 2]; 2],
+            ..Default::default()
+        };
+        assert!(instance.validate().is_ok());
+        assert!(instance.is_eligible(0, 0, 1));
+
+        // Job 0 skips stage 1, so it does not need an eligible machine there
+        instance.eligibility = Some(vec![
+            vec![vec![false, true], vec![false]],
+            vec![vec![true, true], vec![true]],
+        ]);
+        assert!(instance.validate().is_ok());
+        assert!(!instance.is_eligible(0, 0, 0));
+
+        // Job 1 can not be processed in stage 1
+        instance.eligibility = Some(vec![
+            vec![vec![false, true], vec![false]],
+            vec![vec![true, true], vec![false]],
+        ]);
+        assert!(matches!(
+            instance.validate(),
+            Err(InstanceError::NoEligibleMachine { job: 1, stage: 1 })
+        ));
+
+        // Wrong number of machines in stage 0
+        instance.eligibility = Some(vec![
+            vec![vec![true], vec![true]],
+            vec![vec![true], vec![true]],
+        ]);
+        assert!(matches!(
+            instance.validate(),
+            Err(InstanceError::ShapeMismatch {
+                field: "eligibility",
+                ..
+            })
+        ));
+    }
 }
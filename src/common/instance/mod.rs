@@ -1,7 +1,11 @@
+pub mod feasibility;
 pub mod instance;
 pub mod parser;
+pub mod ruiz;
 pub mod solution;
+pub mod validation;
 
 pub use instance::Instance;
 pub use parser::parse;
 pub use solution::Solution;
+pub use validation::InstanceError;
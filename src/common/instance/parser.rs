@@ -2,12 +2,13 @@
 https://docs.serde.rs/serde_json/de/fn.from_reader.html
 */
 
-use std::error::Error;
 use std::fs::File;
-use std::io::BufReader;
+use std::io::{BufReader, BufWriter};
 use std::path::Path;
 
-use super::instance::Instance;
+use serde_json::Value;
+
+use super::{instance::Instance, ruiz, validation::InstanceError};
 
 /* Example of use:
 let instance = parser::parse("n20m2-1.json").unwrap();
@@ -19,16 +20,245 @@ path: "instances\\ruiz\\json\\FILENAME.json"
 machines[stage]
 production_times[product][stage]
 setup_times:[machine][previous_job][current_job]
+
+Instances with unrelated machines give production_times[product][stage][machine] and/or
+setup_times[stage][machine][previous_job][current_job] instead
+
+Files that do not end in .json are read as benchmark text files, see ruiz.rs
 */
 
-pub fn parse<P: AsRef<Path>>(path: P) -> Result<Instance, Box<dyn Error>> {
+pub fn parse<P: AsRef<Path>>(path: P) -> Result<Instance, InstanceError> {
+    if path.as_ref().extension().is_some_and(|ext| ext != "json") {
+        return ruiz::read_text(path);
+    }
+
     // Open file in read only mode with buffer.
     let file = File::open(path)?;
     let reader = BufReader::new(file);
 
-    // Read content of JSON contents of the file as an instance of Instance.
-    let i = serde_json::from_reader(reader)?;
+    // Read content of JSON contents of the file, and move machine specific times to their own fields
+    let mut value: Value = serde_json::from_reader(reader)?;
+    unrelated_machines(&mut value)?;
+
+    let i: Instance = serde_json::from_value(value)?;
+
+    // Make sure the instance can be decoded
+    i.validate()?;
 
     // return instance
     Ok(i)
 }
+
+// Writes the instance as JSON, in the format read by parse
+pub fn write_json<P: AsRef<Path>>(instance: &Instance, path: P) -> Result<(), InstanceError> {
+    let file = File::create(path)?;
+    serde_json::to_writer(BufWriter::new(file), instance)?;
+
+    Ok(())
+}
+
+// Detects the unrelated machines format by the depth of the time arrays. Machine specific times are moved to
+// machine_processing_times and machine_setup_times, and replaced by the shortest time on any machine
+fn unrelated_machines(value: &mut Value) -> Result<(), serde_json::Error> {
+    if value["processing_times"][0][0].is_array() {
+        let times: Vec<Vec<Vec<u32>>> = serde_json::from_value(value["processing_times"].take())?;
+
+        // Zero on all machines means the stage is skipped, so zeros are only used if no machine processes the job
+        let shortest: Vec<Vec<u32>> = times
+            .iter()
+            .map(|stages| {
+                stages
+                    .iter()
+                    .map(|machines| {
+                        machines
+                            .iter()
+                            .filter(|&&time| time != 0)
+                            .min()
+                            .map_or(0, |&time| time)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        value["processing_times"] = serde_json::to_value(shortest)?;
+        value["machine_processing_times"] = serde_json::to_value(times)?;
+    }
+
+    if value["setup_times"][0][0][0].is_array() {
+        let times: Vec<Vec<Vec<Vec<u32>>>> = serde_json::from_value(value["setup_times"].take())?;
+
+        let shortest: Vec<Vec<Vec<u32>>> = times
+            .iter()
+            .map(|machines| {
+                // The shape is validated after parsing, so missing entries are skipped here
+                let n_jobs = machines.first().map_or(0, |setups| setups.len());
+                (0..n_jobs)
+                    .map(|prev| {
+                        (0..n_jobs)
+                            .filter_map(|job| {
+                                machines
+                                    .iter()
+                                    .filter_map(|m| m.get(prev)?.get(job))
+                                    .min()
+                                    .copied()
+                            })
+                            .collect()
+                    })
+                    .collect()
+            })
+            .collect();
+
+        value["setup_times"] = serde_json::to_value(shortest)?;
+        value["machine_setup_times"] = serde_json::to_value(times)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::common::{
+        decoding::DecodingType,
+        instance::{
+            feasibility::{check, Violation},
+            validation::InstanceError,
+        },
+        makespan::Makespan,
+    };
+
+    use super::{parse, write_json};
+
+    #[test]
+    fn parse_unrelated_machines() {
+        let instance = parse("./instances/unrelated/test.json").unwrap();
+
+        // Shortest non-zero time on any machine, zero if the stage is skipped
+        assert_eq!(
+            instance.processing_times,
+            vec![vec![10, 20], vec![15, 0], vec![25, 10]]
+        );
+        assert_eq!(instance.setup_times[0][0], vec![1, 2, 3]);
+        assert_eq!(instance.processing_time(0, 0, 1), 40);
+        assert_eq!(instance.setup_time(0, 1, 2, 0), 3);
+    }
+
+    #[test]
+    fn decode_unrelated_machines() {
+        let instance = parse("./instances/unrelated/test.json").unwrap();
+        let mut makespan = Makespan::new(&instance, DecodingType::Fifo);
+        let (mks, machine_completions) = makespan.makespan(&vec![0, 1, 2]);
+
+        // Job 0 is quicker on machine 0, job 1 on machine 1
+        assert_eq!(machine_completions[0][0][0], (0, 1 + 10));
+        assert_eq!(machine_completions[0][1][0], (1, 5 + 15));
+        // Job 2 finishes at 11 + 3 + 25 = 39 on machine 0, and 20 + 4 + 25 = 49 on machine 1
+        assert_eq!(machine_completions[0][0][1], (2, 39));
+        // Last stage: job 0 from 11 to 11 + 2 + 20 = 33, job 2 from 39 to 39 + 2 + 10 = 51
+        assert_eq!(mks, 51);
+    }
+
+    #[test]
+    fn zero_time_machines_are_ineligible() {
+        let mut instance = parse("./instances/unrelated/test.json").unwrap();
+        // Machine 1 does not process job 1 in stage 0
+        instance.machine_processing_times.as_mut().unwrap()[1][0] = vec![30, 0];
+        instance.processing_times[1][0] = 30;
+        assert!(instance.validate().is_ok());
+        assert!(instance.is_eligible(1, 0, 0));
+        assert!(!instance.is_eligible(1, 0, 1));
+
+        for rule in [
+            DecodingType::Fifo,
+            DecodingType::FirstComplete,
+            DecodingType::ShortestSetup,
+            DecodingType::LeastLoaded,
+            DecodingType::PermutationOrder,
+        ] {
+            let (mks, machine_completions) =
+                Makespan::new(&instance, rule).makespan(&vec![1, 0, 2]);
+            assert!(machine_completions[0][1].iter().all(|&(job, _)| job != 1));
+            assert_eq!(check(&instance, &machine_completions, mks), vec![]);
+        }
+
+        // Job 1 processed on machine 1 in stage 0 is reported by the feasibility check
+        let (mks, mut machine_completions) =
+            Makespan::new(&instance, DecodingType::Fifo).makespan(&vec![1, 0, 2]);
+        let position = machine_completions[0][0]
+            .iter()
+            .position(|&(job, _)| job == 1)
+            .unwrap();
+        let moved = machine_completions[0][0].remove(position);
+        machine_completions[0][1].insert(0, moved);
+        assert!(
+            check(&instance, &machine_completions, mks).contains(&Violation::Ineligible {
+                job: 1,
+                stage: 0,
+                machine: 1
+            })
+        );
+
+        // Job 1 is not eligible on any machine in stage 0 if machine 0 is excluded
+        instance.eligibility = Some(vec![
+            vec![vec![true, true], vec![true]],
+            vec![vec![false, true], vec![true]],
+            vec![vec![true, true], vec![true]],
+        ]);
+        assert!(matches!(
+            instance.validate(),
+            Err(InstanceError::NoEligibleMachine { job: 1, stage: 0 })
+        ));
+    }
+
+    #[test]
+    fn identical_machines_in_unrelated_format() {
+        let mut instance = parse("./instances/ruiz/json/n20m4-01.json").unwrap();
+        let schedule: Vec<u32> = (0..instance.jobs).collect();
+        let (expected, _) = Makespan::new(&instance, DecodingType::Fifo).makespan(&schedule);
+
+        // Same times on all machines gives the same schedule
+        instance.machine_processing_times = Some(
+            instance
+                .processing_times
+                .iter()
+                .map(|stages| {
+                    stages
+                        .iter()
+                        .zip(instance.machines.iter())
+                        .map(|(&time, &machines)| vec![time; machines as usize])
+                        .collect()
+                })
+                .collect(),
+        );
+        instance.machine_setup_times = Some(
+            instance
+                .setup_times
+                .iter()
+                .zip(instance.machines.iter())
+                .map(|(setups, &machines)| vec![setups.clone(); machines as usize])
+                .collect(),
+        );
+        assert!(instance.validate().is_ok());
+
+        let (mks, _) = Makespan::new(&instance, DecodingType::Fifo).makespan(&schedule);
+        assert_eq!(mks, expected);
+    }
+
+    #[test]
+    fn write_and_parse_json() {
+        let instance = parse("./instances/unrelated/test.json").unwrap();
+
+        let file = std::env::temp_dir().join("tio4905_write_and_parse_json.json");
+        write_json(&instance, &file).unwrap();
+        let written = parse(&file).unwrap();
+        std::fs::remove_file(&file).unwrap();
+
+        assert_eq!(written.processing_times, instance.processing_times);
+        assert_eq!(written.setup_times, instance.setup_times);
+        assert_eq!(
+            written.machine_processing_times,
+            instance.machine_processing_times
+        );
+        assert_eq!(written.machine_setup_times, instance.machine_setup_times);
+        assert_eq!(written.eligibility, instance.eligibility);
+    }
+}
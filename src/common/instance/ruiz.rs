@@ -0,0 +1,293 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use calamine::{open_workbook_auto, Data, Reader};
+
+use super::{instance::Instance, parser, validation::InstanceError};
+
+/*
+Reader for the original benchmark files of Ruiz and Maroto, in the n=20, n=50 and n=120 folders,
+and for the same instances as plain text. Both give rows of numbers, which are read the same way.
+
+An instance is laid out as (empty rows are ignored, and only the first values of each row are read):
+jobs
+stages
+machines[0] machines[1] ... machines[stages - 1]
+processing_times[job][stage]       (one row per job)
+setup_times[stage][prev_job][job]  (one row per previous job, stages * jobs rows)
+
+In the Excel files the first two rows are padded with zeros. The Taillard-style text layout with
+"jobs stages" on the first row is also accepted. A workbook holds many instances on the first sheet,
+one after the other, e.g. 80 instances with 2 stages followed by 80 with 4 stages in "n=20, m=2,4.xls".
+
+Example of use:
+let instances = ruiz::read_workbook("instances/ruiz/n=20/m=8/n=20, m=8.xls").unwrap();
+ruiz::workbook_to_json("instances/ruiz/n=20/m=8/n=20, m=8.xls", "instances/ruiz/json").unwrap();
+*/
+
+// Reads a single instance from a text file
+pub fn read_text<P: AsRef<Path>>(path: P) -> Result<Instance, InstanceError> {
+    let content = fs::read_to_string(path)?;
+
+    let mut rows = Vec::new();
+    for (line, text) in content.lines().enumerate() {
+        let row =
+            text.split(|c: char| c.is_whitespace() || c == ',' || c == ';')
+                .filter(|value| !value.is_empty())
+                .map(|value| {
+                    value.parse::<f64>().ok().and_then(to_time).ok_or_else(|| {
+                        format_error(line + 1, format!("{} is not a valid time", value))
+                    })
+                })
+                .collect::<Result<Vec<u32>, InstanceError>>()?;
+
+        if !row.is_empty() {
+            rows.push((line + 1, row));
+        }
+    }
+
+    let (instance, next) = from_rows(&rows, 0)?;
+    if let Some((line, _)) = rows.get(next) {
+        return Err(format_error(
+            *line,
+            "unexpected values after the instance".into(),
+        ));
+    }
+
+    instance.validate()?;
+    Ok(instance)
+}
+
+// Reads all instances on the first sheet of an Excel (or OpenDocument) workbook, in the order they appear
+pub fn read_workbook<P: AsRef<Path>>(path: P) -> Result<Vec<Instance>, InstanceError> {
+    let mut workbook = open_workbook_auto(path)?;
+    let sheet = match workbook.worksheet_range_at(0) {
+        Some(sheet) => sheet?,
+        None => return Ok(Vec::new()),
+    };
+    let first_row = sheet.start().map_or(0, |(row, _)| row as usize);
+
+    let mut rows = Vec::new();
+    for (index, cells) in sheet.rows().enumerate() {
+        let row_number = first_row + index + 1;
+
+        // Trailing empty cells are not part of the row
+        let length = cells
+            .iter()
+            .rposition(|cell| *cell != Data::Empty)
+            .map_or(0, |last| last + 1);
+
+        let row = cells[..length]
+            .iter()
+            .map(|cell| {
+                let time = match cell {
+                    Data::Int(value) => to_time(*value as f64),
+                    Data::Float(value) => to_time(*value),
+                    Data::Empty => Some(0),
+                    _ => None,
+                };
+                time.ok_or_else(|| {
+                    format_error(row_number, format!("{} is not a valid time", cell))
+                })
+            })
+            .collect::<Result<Vec<u32>, InstanceError>>()?;
+
+        if !row.is_empty() {
+            rows.push((row_number, row));
+        }
+    }
+
+    let mut instances = Vec::new();
+    let mut next = 0;
+    while next < rows.len() {
+        let (instance, end) = from_rows(&rows, next)?;
+        instance.validate()?;
+        instances.push(instance);
+        next = end;
+    }
+
+    Ok(instances)
+}
+
+// Names instances as in instances/ruiz/json, i.e. n20m2-01, numbered by their order among
+// instances with the same number of jobs and stages
+pub fn instance_names(instances: &[Instance]) -> Vec<String> {
+    let mut counts: HashMap<(u32, u32), u32> = HashMap::new();
+
+    instances
+        .iter()
+        .map(|instance| {
+            let count = counts.entry((instance.jobs, instance.stages)).or_insert(0);
+            *count += 1;
+            format!("n{}m{}-{:02}", instance.jobs, instance.stages, count)
+        })
+        .collect()
+}
+
+// Writes all instances in a workbook to JSON files in the given folder, returning the written files
+pub fn workbook_to_json<P: AsRef<Path>, Q: AsRef<Path>>(
+    workbook: P,
+    folder: Q,
+) -> Result<Vec<PathBuf>, InstanceError> {
+    let instances = read_workbook(workbook)?;
+    fs::create_dir_all(&folder)?;
+
+    let mut files = Vec::new();
+    for (instance, name) in instances.iter().zip(instance_names(&instances)) {
+        let file = folder.as_ref().join(format!("{}.json", name));
+        parser::write_json(instance, &file)?;
+        files.push(file);
+    }
+
+    Ok(files)
+}
+
+// Times are whole numbers, but Excel stores all numbers as floats
+fn to_time(value: f64) -> Option<u32> {
+    match value >= 0.0 && value.fract() == 0.0 && value <= u32::MAX as f64 {
+        true => Some(value as u32),
+        false => None,
+    }
+}
+
+fn format_error(row: usize, reason: String) -> InstanceError {
+    InstanceError::Format { row, reason }
+}
+
+// Reads the instance starting at rows[start], and returns it with the index of the row after it.
+// rows are (row_number, values), where row_number is used in error messages
+fn from_rows(rows: &[(usize, Vec<u32>)], start: usize) -> Result<(Instance, usize), InstanceError> {
+    let mut next = start;
+
+    // Returns the first `length` values of the next row
+    let mut take = |length: usize, what: &str| -> Result<Vec<u32>, InstanceError> {
+        let (row_number, row) = match rows.get(next) {
+            Some(row) => row,
+            None => {
+                let last = rows.last().map_or(0, |(row_number, _)| *row_number);
+                return Err(format_error(last + 1, format!("missing {}", what)));
+            }
+        };
+        next += 1;
+
+        match row.len() >= length {
+            true => Ok(row[..length].to_vec()),
+            false => Err(format_error(
+                *row_number,
+                format!("{} has {} values, expected {}", what, row.len(), length),
+            )),
+        }
+    };
+
+    let jobs = take(1, "number of jobs")?[0];
+
+    // Taillard-style "jobs stages" on the first row, or jobs and stages on separate rows
+    let stages = match rows[start].1.get(1) {
+        Some(&stages) if stages != 0 => stages,
+        _ => take(1, "number of stages")?[0],
+    };
+
+    if jobs == 0 {
+        return Err(InstanceError::NoJobs);
+    }
+    if stages == 0 {
+        return Err(InstanceError::NoStages);
+    }
+
+    let machines = take(stages as usize, "machines")?;
+
+    let processing_times = (0..jobs)
+        .map(|_| take(stages as usize, "processing times"))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let setup_times = (0..stages)
+        .map(|_| {
+            (0..jobs)
+                .map(|_| take(jobs as usize, "setup times"))
+                .collect::<Result<Vec<_>, _>>()
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let instance = Instance {
+        jobs,
+        stages,
+        machines,
+        processing_times,
+        setup_times,
+        ..Default::default()
+    };
+
+    Ok((instance, next))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use crate::common::instance::{parse, InstanceError};
+
+    use super::{instance_names, read_text, read_workbook};
+
+    #[test]
+    fn workbook_matches_json() {
+        let instances = read_workbook("./instances/ruiz/n=20/m=8/n=20, m=8.xls").unwrap();
+        assert_eq!(instances.len(), 80);
+
+        let names = instance_names(&instances);
+        assert_eq!(names[0], "n20m8-01");
+        assert_eq!(names[79], "n20m8-80");
+
+        for index in [0, 41, 79] {
+            let file = format!("./instances/ruiz/json/{}.json", names[index]);
+            let expected = parse(file).unwrap();
+
+            assert_eq!(instances[index].machines, expected.machines);
+            assert_eq!(instances[index].processing_times, expected.processing_times);
+            assert_eq!(instances[index].setup_times, expected.setup_times);
+        }
+    }
+
+    #[test]
+    fn workbook_with_two_stage_counts() {
+        let instances = read_workbook("./instances/ruiz/n=20/m=2,4/n=20, m=2,4.xls").unwrap();
+        let names = instance_names(&instances);
+
+        assert_eq!(instances.len(), 160);
+        assert_eq!(names[80], "n20m4-01");
+    }
+
+    #[test]
+    fn text_round_trip() {
+        let expected = parse("./instances/ruiz/json/n20m2-01.json").unwrap();
+
+        // Write in the Taillard-style layout and read back
+        let mut text = format!("{} {}\n", expected.jobs, expected.stages);
+        let rows = std::iter::once(&expected.machines)
+            .chain(expected.processing_times.iter())
+            .chain(expected.setup_times.iter().flatten());
+        for row in rows {
+            let values: Vec<String> = row.iter().map(|v| v.to_string()).collect();
+            text.push_str(&values.join(" "));
+            text.push('\n');
+        }
+
+        let file = std::env::temp_dir().join("tio4905_text_round_trip.txt");
+        fs::write(&file, &text).unwrap();
+        let instance = read_text(&file).unwrap();
+
+        assert_eq!(instance.machines, expected.machines);
+        assert_eq!(instance.processing_times, expected.processing_times);
+        assert_eq!(instance.setup_times, expected.setup_times);
+
+        // Missing the last setup row
+        let truncated = &text[..text.trim_end().rfind('\n').unwrap()];
+        fs::write(&file, truncated).unwrap();
+        assert!(matches!(
+            read_text(&file),
+            Err(InstanceError::Format { .. })
+        ));
+
+        fs::remove_file(&file).unwrap();
+    }
+}
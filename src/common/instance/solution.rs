@@ -1,15 +1,62 @@
+use super::feasibility::{self, Violation};
 use super::instance::Instance;
+use super::validation::InstanceError;
 use serde_derive::{Deserialize, Serialize};
 use std::fs::{self, File};
-use std::io::ErrorKind;
+use std::io::{BufReader, ErrorKind};
+use std::path::Path;
 
-#[derive(Serialize, Deserialize)]
+/*
+The timeline of a solution is derived from machine_completions[stage][machine][machine_run](job, completion_time).
+As in the decoder, a job is set up right before it is processed, so
+processing_start = completion - processing time and setup_start = processing_start - setup time.
+A job skipping a stage has an operation without a machine, that starts and completes when the job is ready.
+
+Older solution files without the timeline can still be read, the timeline fields are then empty.
+*/
+
+#[derive(Serialize, Deserialize, Clone)]
 pub struct Solution {
     jobs: u32,
     stages: u32,
     machines: Vec<u32>,
     makespan: u32,
     machine_completions: Vec<Vec<Vec<(u32, u32)>>>,
+    // eligibility[job][stage][machine], only written for instances with eligibility constraints
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    eligibility: Option<Vec<Vec<Vec<bool>>>>,
+    // All operations, ordered by stage, machine and machine run, with skipped stages last in each stage
+    #[serde(default)]
+    operations: Vec<Operation>,
+    // machine_usage[stage][machine]
+    #[serde(default)]
+    machine_usage: Vec<Vec<MachineUsage>>,
+    #[serde(default)]
+    total_setup_time: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Operation {
+    pub job: u32,
+    pub stage: u32,
+    // None if the job skips the stage
+    pub machine: Option<u32>,
+    pub setup_start: u32,
+    pub processing_start: u32,
+    pub completion: u32,
+    // Time the machine was idle between the previous job (or time 0) and the start of the setup
+    pub idle: u32,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct MachineUsage {
+    pub jobs: u32,
+    pub setup_time: u32,
+    pub processing_time: u32,
+    // Time the machine is neither setting up nor processing before the makespan
+    pub idle_time: u32,
+    // Share of the makespan the machine is setting up or processing
+    pub utilization: f64,
 }
 
 impl Solution {
@@ -18,12 +65,23 @@ impl Solution {
         makespan: u32,
         instance: &Instance,
     ) -> Solution {
+        let operations = timeline(&machine_completions, instance);
+        let machine_usage = machine_usage(&operations, makespan, instance);
+        let total_setup_time = operations
+            .iter()
+            .map(|o| o.processing_start - o.setup_start)
+            .sum();
+
         Solution {
             jobs: instance.jobs,
             stages: instance.stages,
             machines: instance.machines.to_vec(),
             makespan: makespan,
             machine_completions,
+            eligibility: instance.eligibility.clone(),
+            operations,
+            machine_usage,
+            total_setup_time,
         }
     }
 
@@ -44,4 +102,207 @@ impl Solution {
         let s = serde_json::to_string(self).unwrap();
         fs::write(&path, s).expect("Could not write solution to file");
     }
+
+    // Reads a solution file, e.g. one written by write or by another tool in the same format
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Solution, InstanceError> {
+        let reader = BufReader::new(File::open(path)?);
+        Ok(serde_json::from_reader(reader)?)
+    }
+
+    // Returns all violations of the solution as a schedule of the instance, see feasibility.rs
+    pub fn check(&self, instance: &Instance) -> Vec<Violation> {
+        feasibility::check(instance, &self.machine_completions, self.makespan)
+    }
+}
+
+#[allow(dead_code)]
+impl Solution {
+    pub fn makespan(&self) -> u32 {
+        self.makespan
+    }
+
+    pub fn operations(&self) -> &[Operation] {
+        &self.operations
+    }
+
+    pub fn machine_usage(&self) -> &[Vec<MachineUsage>] {
+        &self.machine_usage
+    }
+
+    pub fn total_setup_time(&self) -> u32 {
+        self.total_setup_time
+    }
+}
+
+// Returns the operations of all jobs in all stages, see the top of the file
+fn timeline(machine_completions: &[Vec<Vec<(u32, u32)>>], instance: &Instance) -> Vec<Operation> {
+    let mut operations = Vec::new();
+    // Time each job is ready for the next stage it visits
+    let mut ready: Vec<u32> = (0..instance.jobs)
+        .map(|j| instance.release_date(j))
+        .collect();
+
+    for (stage, machines) in machine_completions.iter().enumerate() {
+        let mut completed = ready.clone();
+
+        for (machine, machine_run) in machines.iter().enumerate() {
+            let (stage, machine) = (stage as u32, machine as u32);
+            let mut prev: Option<(u32, u32)> = None;
+
+            for &(job, completion) in machine_run.iter() {
+                // setup_times[stage][job][job] is the initial setup of the first job on a machine
+                let (prev_job, machine_ready) = prev.unwrap_or((job, 0));
+                let processing_start =
+                    completion.saturating_sub(instance.processing_time(job, stage, machine));
+                let setup_start = processing_start
+                    .saturating_sub(instance.setup_time(stage, machine, prev_job, job));
+
+                operations.push(Operation {
+                    job,
+                    stage,
+                    machine: Some(machine),
+                    setup_start,
+                    processing_start,
+                    completion,
+                    idle: setup_start.saturating_sub(machine_ready),
+                });
+                completed[job as usize] = completion;
+                prev = Some((job, completion));
+            }
+        }
+
+        for job in 0..instance.jobs {
+            if instance.processing_times[job as usize][stage] == 0 {
+                let time = ready[job as usize];
+                operations.push(Operation {
+                    job,
+                    stage: stage as u32,
+                    machine: None,
+                    setup_start: time,
+                    processing_start: time,
+                    completion: time,
+                    idle: 0,
+                });
+            }
+        }
+        ready = completed;
+    }
+
+    operations
+}
+
+// Returns the usage of each machine in each stage over the makespan
+fn machine_usage(
+    operations: &[Operation],
+    makespan: u32,
+    instance: &Instance,
+) -> Vec<Vec<MachineUsage>> {
+    let mut usage: Vec<Vec<MachineUsage>> = instance
+        .machines
+        .iter()
+        .map(|&machines| {
+            vec![
+                MachineUsage {
+                    jobs: 0,
+                    setup_time: 0,
+                    processing_time: 0,
+                    idle_time: 0,
+                    utilization: 0.0,
+                };
+                machines as usize
+            ]
+        })
+        .collect();
+
+    for operation in operations.iter() {
+        if let Some(machine) = operation.machine {
+            let u = &mut usage[operation.stage as usize][machine as usize];
+            u.jobs += 1;
+            u.setup_time += operation.processing_start - operation.setup_start;
+            u.processing_time += operation.completion - operation.processing_start;
+        }
+    }
+
+    for u in usage.iter_mut().flatten() {
+        let busy = u.setup_time + u.processing_time;
+        u.idle_time = makespan.saturating_sub(busy);
+        u.utilization = match makespan {
+            0 => 0.0,
+            _ => busy as f64 / makespan as f64,
+        };
+    }
+
+    usage
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        common::{decoding::DecodingType, makespan::Makespan},
+        genetic_algorithm::tests::tests::test_instance,
+    };
+
+    use super::{Operation, Solution};
+
+    #[test]
+    fn timeline() {
+        let instance = test_instance();
+        let (mks, machine_completions) =
+            Makespan::new(&instance, DecodingType::Fifo).makespan(&vec![0, 1, 2, 3, 4]);
+        let solution = Solution::new(machine_completions, mks, &instance);
+
+        // Job 0 is first on machine 0 in stage 0, with initial setup 4 and processing time 71
+        assert_eq!(
+            solution.operations()[0],
+            Operation {
+                job: 0,
+                stage: 0,
+                machine: Some(0),
+                setup_start: 0,
+                processing_start: 4,
+                completion: 75,
+                idle: 0,
+            }
+        );
+        // Job 4 follows job 0 on machine 0, with setup 5 and processing time 29
+        assert_eq!(
+            solution.operations()[1],
+            Operation {
+                job: 4,
+                stage: 0,
+                machine: Some(0),
+                setup_start: 75,
+                processing_start: 80,
+                completion: 109,
+                idle: 0,
+            }
+        );
+
+        // Job 2 skips stage 0, and is ready at its release date
+        let skipped = solution
+            .operations()
+            .iter()
+            .find(|o| o.stage == 0 && o.machine.is_none())
+            .unwrap();
+        assert_eq!((skipped.job, skipped.completion), (2, 0));
+
+        // Every job has an operation in every stage
+        assert_eq!(solution.operations().len(), 5 * 2);
+
+        let usage = &solution.machine_usage()[0][0];
+        assert_eq!(
+            (usage.jobs, usage.setup_time, usage.processing_time),
+            (2, 9, 100)
+        );
+        assert_eq!(usage.idle_time, mks - 109);
+        assert_eq!(
+            solution.total_setup_time(),
+            solution
+                .machine_usage()
+                .iter()
+                .flatten()
+                .map(|u| u.setup_time)
+                .sum::<u32>()
+        );
+    }
 }
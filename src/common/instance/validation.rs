@@ -0,0 +1,280 @@
+use std::{error::Error, fmt, io};
+
+use super::instance::Instance;
+
+/*
+Validation of an instance, so that malformed instance files are reported when they are parsed
+instead of panicking with an index error when a schedule is decoded.
+
+Shape errors give the field and the indices of the offending list, e.g. setup_times[2][5] having
+19 entries when the instance has 20 jobs gives
+ShapeMismatch { field: "setup_times", index: [2, 5], expected: 20, found: 19 }
+
+A job with zero processing time in all stages is not an error, as the Ruiz benchmark instances
+contain such jobs (every stage is skipped with some probability). The decoder lets them pass through.
+*/
+
+#[derive(Debug)]
+pub enum InstanceError {
+    // The instance file could not be read
+    Io(io::Error),
+    // The instance file is not valid JSON, or is missing a required field
+    Json(serde_json::Error),
+    // The benchmark workbook could not be opened or read
+    Workbook(calamine::Error),
+    // A row of a benchmark workbook or text file is missing or can not be read (row is 1-based)
+    Format {
+        row: usize,
+        reason: String,
+    },
+    NoJobs,
+    NoStages,
+    // A list has the wrong number of entries
+    ShapeMismatch {
+        field: &'static str,
+        index: Vec<usize>,
+        expected: usize,
+        found: usize,
+    },
+    ZeroMachines {
+        stage: usize,
+    },
+    // The job visits the stage, but is not eligible on any of its machines
+    NoEligibleMachine {
+        job: usize,
+        stage: usize,
+    },
+}
+
+impl fmt::Display for InstanceError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InstanceError::Io(e) => write!(f, "could not read instance: {}", e),
+            InstanceError::Json(e) => write!(f, "could not parse instance: {}", e),
+            InstanceError::Workbook(e) => write!(f, "could not read workbook: {}", e),
+            InstanceError::Format { row, reason } => write!(f, "row {}: {}", row, reason),
+            InstanceError::NoJobs => write!(f, "instance has no jobs"),
+            InstanceError::NoStages => write!(f, "instance has no stages"),
+            InstanceError::ShapeMismatch {
+                field,
+                index,
+                expected,
+                found,
+            } => {
+                write!(f, "{}", field)?;
+                for i in index.iter() {
+                    write!(f, "[{}]", i)?;
+                }
+                write!(f, " has {} entries, expected {}", found, expected)
+            }
+            InstanceError::ZeroMachines { stage } => write!(f, "stage {} has no machines", stage),
+            InstanceError::NoEligibleMachine { job, stage } => {
+                write!(f, "job {} has no eligible machine in stage {}", job, stage)
+            }
+        }
+    }
+}
+
+impl Error for InstanceError {}
+
+impl From<io::Error> for InstanceError {
+    fn from(e: io::Error) -> Self {
+        InstanceError::Io(e)
+    }
+}
+
+impl From<calamine::Error> for InstanceError {
+    fn from(e: calamine::Error) -> Self {
+        InstanceError::Workbook(e)
+    }
+}
+
+impl From<serde_json::Error> for InstanceError {
+    fn from(e: serde_json::Error) -> Self {
+        InstanceError::Json(e)
+    }
+}
+
+// Returns an error if the list does not have the expected number of entries
+fn check_len<T>(
+    list: &[T],
+    expected: usize,
+    field: &'static str,
+    index: &[usize],
+) -> Result<(), InstanceError> {
+    match list.len() == expected {
+        true => Ok(()),
+        false => Err(InstanceError::ShapeMismatch {
+            field,
+            index: index.to_vec(),
+            expected,
+            found: list.len(),
+        }),
+    }
+}
+
+impl Instance {
+    // Checks that all lists have the shape given by the number of jobs, stages and machines,
+    // that every stage has machines, and that every job can be processed in the stages it visits
+    pub fn validate(&self) -> Result<(), InstanceError> {
+        let n_jobs = self.jobs as usize;
+        let n_stages = self.stages as usize;
+
+        if n_jobs == 0 {
+            return Err(InstanceError::NoJobs);
+        }
+        if n_stages == 0 {
+            return Err(InstanceError::NoStages);
+        }
+
+        check_len(&self.machines, n_stages, "machines", &[])?;
+        if let Some(stage) = self.machines.iter().position(|&m| m == 0) {
+            return Err(InstanceError::ZeroMachines { stage });
+        }
+
+        check_len(&self.processing_times, n_jobs, "processing_times", &[])?;
+        for (job, stages) in self.processing_times.iter().enumerate() {
+            check_len(stages, n_stages, "processing_times", &[job])?;
+        }
+
+        check_len(&self.setup_times, n_stages, "setup_times", &[])?;
+        for (stage, setups) in self.setup_times.iter().enumerate() {
+            check_len(setups, n_jobs, "setup_times", &[stage])?;
+            for (prev_job, row) in setups.iter().enumerate() {
+                check_len(row, n_jobs, "setup_times", &[stage, prev_job])?;
+            }
+        }
+
+        for (field, values) in [
+            ("release_dates", &self.release_dates),
+            ("due_dates", &self.due_dates),
+            ("weights", &self.weights),
+        ] {
+            if let Some(values) = values {
+                check_len(values, n_jobs, field, &[])?;
+            }
+        }
+
+        if let Some(times) = self.machine_processing_times.as_ref() {
+            check_len(times, n_jobs, "machine_processing_times", &[])?;
+            for (job, stages) in times.iter().enumerate() {
+                check_len(stages, n_stages, "machine_processing_times", &[job])?;
+                for (stage, machines) in stages.iter().enumerate() {
+                    let n_machines = self.machines[stage] as usize;
+                    check_len(
+                        machines,
+                        n_machines,
+                        "machine_processing_times",
+                        &[job, stage],
+                    )?;
+                }
+            }
+        }
+
+        if let Some(times) = self.machine_setup_times.as_ref() {
+            check_len(times, n_stages, "machine_setup_times", &[])?;
+            for (stage, machines) in times.iter().enumerate() {
+                let n_machines = self.machines[stage] as usize;
+                check_len(machines, n_machines, "machine_setup_times", &[stage])?;
+                for (machine, setups) in machines.iter().enumerate() {
+                    check_len(setups, n_jobs, "machine_setup_times", &[stage, machine])?;
+                    for (prev_job, row) in setups.iter().enumerate() {
+                        let index = [stage, machine, prev_job];
+                        check_len(row, n_jobs, "machine_setup_times", &index)?;
+                    }
+                }
+            }
+        }
+
+        if let Some(eligibility) = self.eligibility.as_ref() {
+            check_len(eligibility, n_jobs, "eligibility", &[])?;
+            for (job, stages) in eligibility.iter().enumerate() {
+                check_len(stages, n_stages, "eligibility", &[job])?;
+                for (stage, machines) in stages.iter().enumerate() {
+                    let n_machines = self.machines[stage] as usize;
+                    check_len(machines, n_machines, "eligibility", &[job, stage])?;
+                }
+            }
+        }
+
+        // A machine is not eligible if the job has zero processing time on it (see Instance::is_eligible)
+        for (job, stages) in self.processing_times.iter().enumerate() {
+            for (stage, &time) in stages.iter().enumerate() {
+                let eligible = (0..self.machines[stage])
+                    .any(|machine| self.is_eligible(job as u32, stage as u32, machine));
+                if time != 0 && !eligible {
+                    return Err(InstanceError::NoEligibleMachine { job, stage });
+                }
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{common::instance::Instance, genetic_algorithm::tests::tests::test_instance};
+
+    use super::InstanceError;
+
+    #[test]
+    fn valid_instance() {
+        assert!(test_instance().validate().is_ok());
+    }
+
+    #[test]
+    fn shape_mismatch() {
+        let mut instance = test_instance();
+        instance.setup_times[1][3].pop();
+
+        match instance.validate() {
+            Err(InstanceError::ShapeMismatch {
+                field,
+                index,
+                expected,
+                found,
+            }) => {
+                assert_eq!(field, "setup_times");
+                assert_eq!(index, vec![1, 3]);
+                assert_eq!((expected, found), (5, 4));
+            }
+            other => panic!("unexpected result {:?}", other),
+        }
+
+        let instance = Instance {
+            machines: vec![2],
+            ..test_instance()
+        };
+        assert!(matches!(
+            instance.validate(),
+            Err(InstanceError::ShapeMismatch {
+                field: "machines",
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn zero_machines_and_skipping_jobs() {
+        let instance = Instance {
+            machines: vec![2, 0],
+            ..test_instance()
+        };
+        assert!(matches!(
+            instance.validate(),
+            Err(InstanceError::ZeroMachines { stage: 1 })
+        ));
+
+        // Skipping all stages is allowed
+        let mut instance = test_instance();
+        instance.processing_times[3] = vec![0, 0];
+        assert!(instance.validate().is_ok());
+    }
+
+    #[test]
+    fn missing_file() {
+        let error = crate::common::instance::parse("./instances/missing.json").unwrap_err();
+        assert!(matches!(error, InstanceError::Io(_)));
+    }
+}
@@ -1,43 +1,207 @@
 use core::cmp::max;
 
-use super::instance::Instance;
+use crate::genetic_algorithm::params;
 
-const PROCESS_FIFO: bool = true;
-const PROCESS_FIRSTCOMPLETE: bool = false;
+use super::{
+    decoding::{self, DecodingType},
+    instance::Instance,
+    objective::{ObjectiveType, Schedule},
+};
 
 pub struct Makespan {
     pub count: u32,
     pub instance: Instance,
+    // Rule used to dispatch jobs to machines in each stage
+    pub rule: DecodingType,
+    // Objective value returned by `evaluate` (the makespan by default)
+    pub objective: ObjectiveType,
+    // Objectives and their weights summed by the weighted objective
+    pub objective_weights: Vec<(ObjectiveType, u32)>,
+    // Decoded state of the last permutation passed to `evaluate`, if incremental evaluation is enabled
+    cache: Option<PrefixCache>,
+}
+
+// Per-stage state of a FIFO decode, kept so that a permutation sharing a prefix with the
+// previously evaluated one only needs its suffix decoded
+struct PrefixCache {
+    // dispatched[stage][i](job_number, ready_time) in the order the jobs were scheduled in the stage
+    dispatched: Vec<Vec<(u32, u32)>>,
+    // assigned[stage][i] is the machine the i-th dispatched job was put on (u32::MAX if the stage is skipped)
+    assigned: Vec<Vec<u32>>,
+    // job_completions[stage][job](job_number, completion_time)
+    job_completions: Vec<Vec<(u32, u32)>>,
+    // machine_completions[stage][machine][machine_run](job_number, completion_time)
+    machine_completions: Vec<Vec<Vec<(u32, u32)>>>,
+}
+
+impl PrefixCache {
+    fn new(instance: &Instance) -> PrefixCache {
+        let n_stages = instance.stages as usize;
+
+        PrefixCache {
+            dispatched: vec![Vec::new(); n_stages],
+            assigned: vec![Vec::new(); n_stages],
+            job_completions: vec![Vec::new(); n_stages],
+            machine_completions: instance
+                .machines
+                .iter()
+                .map(|&machines| vec![Vec::new(); machines as usize])
+                .collect(),
+        }
+    }
+
+    // Undo everything scheduled in the stage after the first `len` dispatched jobs
+    fn truncate(&mut self, stage: usize, len: usize) {
+        for &machine in self.assigned[stage][len..].iter() {
+            if machine != u32::MAX {
+                self.machine_completions[stage][machine as usize].pop();
+            }
+        }
+        self.dispatched[stage].truncate(len);
+        self.assigned[stage].truncate(len);
+        self.job_completions[stage].truncate(len);
+    }
 }
 
 /* Pseudo code
 makespan(initial_job_order, instance) -> makespan
     let job_completions[stage](job_number, job_completion_time)
     let machine_completions[stage][machine][machine_run](job_number, completion_time)
-    let mut job_order = inital_job_order
+    let mut job_order = inital_job_order (ready at the release date of each job)
     for stage in instance.stages:
-        for job in job_order:
-            if PROCESS_FIFO or stage == 0 {
-                find the machine that processes the job the fastest
-                Schedule the job for that machine
-            }
-            elif PROCESS_FIRSTCOMPLETE {
-                find the next job that can finish the fastest, and the corresponding machine
-                schedule that job on the corresponding machine machine
-            }
+        schedule the jobs of the stage by the decoding rule, e.g.
+            Fifo: for job in job_order, schedule the job on the machine that processes it the fastest
+            FirstComplete: schedule the job that can finish the fastest on the corresponding machine
+            (see common::decoding for all rules)
         let job_order = The completion order of products in the current stage
     return the largest completion time from the last stage
 */
 impl Makespan {
-    pub fn new(instance: &Instance) -> Makespan {
+    #[allow(dead_code)]
+    pub fn new(instance: &Instance, rule: DecodingType) -> Makespan {
+        Makespan {
+            count: 0,
+            instance: instance.clone(),
+            rule,
+            objective: ObjectiveType::Makespan,
+            objective_weights: params::OBJECTIVE_WEIGHTS.to_vec(),
+            cache: None,
+        }
+    }
+
+    // Makespan struct where `evaluate` only re-decodes the part of a permutation that differs from the previous one
+    pub fn new_incremental(instance: &Instance, rule: DecodingType) -> Makespan {
         Makespan {
             count: 0,
             instance: instance.clone(),
+            rule,
+            objective: ObjectiveType::Makespan,
+            objective_weights: params::OBJECTIVE_WEIGHTS.to_vec(),
+            cache: Some(PrefixCache::new(instance)),
         }
     }
 
+    /* Incremental evaluation
+    In FIFO decoding, the jobs of a stage are dispatched in a fixed order (the permutation in stage 0,
+    ready times from the previous stage otherwise, or the permutation in every stage with the
+    PermutationOrder rule), and the machine states after dispatching a job only
+    depend on the jobs dispatched before it. Two permutations that share a prefix therefore share the
+    decoded state of every stage up until the first position where their dispatch orders differ.
+    When inserting a job in all positions of a permutation, consecutive candidates only differ from the
+    insertion point and onwards, so only the suffix is decoded again. The result is identical to a full decode.
+    */
+    // returns the objective value of the schedule, reusing the decoded prefix of the last evaluated schedule if possible
+    pub fn evaluate(&mut self, initial_job_order: &Vec<u32>) -> u32 {
+        let cache = match self.cache.as_mut() {
+            Some(cache) if self.rule.fixed_dispatch_order() => cache,
+            _ => {
+                let (job_completions, machine_completions) = self.decode(initial_job_order);
+                let schedule = Schedule {
+                    job_completions: &job_completions,
+                    machine_completions: &machine_completions,
+                };
+                return self
+                    .objective
+                    .evaluate(&schedule, &self.instance, &self.objective_weights);
+            }
+        };
+        let instance: &Instance = &self.instance;
+
+        let mut ready: Vec<(u32, u32)> = initial_job_order
+            .iter()
+            .map(|&job| (job, instance.release_date(job)))
+            .collect();
+
+        for stage in 0..instance.stages {
+            let s = stage as usize;
+
+            // Jobs are dispatched in order of ready times after the first stage, unless the permutation is kept
+            if stage != 0 {
+                ready = match self.rule {
+                    DecodingType::PermutationOrder => decoding::permutation_order(
+                        initial_job_order,
+                        &cache.job_completions[s - 1],
+                        instance,
+                    ),
+                    _ => {
+                        let mut ready = cache.job_completions[s - 1].clone();
+                        ready.sort_by_key(|&(_, time)| time);
+                        ready
+                    }
+                };
+            }
+
+            // Keep the part of the stage that is dispatched in the same order as last time
+            let shared = cache.dispatched[s]
+                .iter()
+                .zip(ready.iter())
+                .take_while(|(cached, new)| cached == new)
+                .count();
+            cache.truncate(s, shared);
+
+            for (job, prev_stage_completion_time) in ready[shared..].iter() {
+                let machine = Self::fifo(
+                    job,
+                    &stage,
+                    prev_stage_completion_time,
+                    &mut cache.job_completions,
+                    &mut cache.machine_completions,
+                    instance,
+                );
+                cache.assigned[s].push(machine);
+            }
+            cache.dispatched[s] = std::mem::take(&mut ready);
+        }
+        self.count += 1;
+
+        let schedule = Schedule {
+            job_completions: &cache.job_completions,
+            machine_completions: &cache.machine_completions,
+        };
+        self.objective
+            .evaluate(&schedule, instance, &self.objective_weights)
+    }
+
     // returns the makespan of the schedule and the machine completions
     pub fn makespan(&mut self, initial_job_order: &Vec<u32>) -> (u32, Vec<Vec<Vec<(u32, u32)>>>) {
+        let (job_completions, machine_completions) = self.decode(initial_job_order);
+
+        (
+            *job_completions[job_completions.len() - 1]
+                .iter()
+                .map(|(_job, time)| time)
+                .max()
+                .unwrap(),
+            machine_completions,
+        )
+    }
+
+    // returns the job completions and machine completions of the schedule
+    #[allow(clippy::type_complexity)]
+    fn decode(
+        &mut self,
+        initial_job_order: &Vec<u32>,
+    ) -> (Vec<Vec<(u32, u32)>>, Vec<Vec<Vec<(u32, u32)>>>) {
         let instance: &Instance = &self.instance;
         let n_jobs: usize = initial_job_order.len();
         let n_stages: usize = instance.stages as usize;
@@ -59,50 +223,28 @@ impl Makespan {
         // Note: this is simply the job_completion for the previous stage, but it needs to be artificially made the first time
         let mut prev_stage_completions: Vec<(u32, u32)> = Vec::with_capacity(n_jobs);
         for job in 0..n_jobs {
-            prev_stage_completions.push((initial_job_order[job], 0));
+            let job = initial_job_order[job];
+            prev_stage_completions.push((job, instance.release_date(job)));
         }
 
         for stage in 0..instance.stages {
-            if PROCESS_FIFO || stage == 0 {
-                // If FIFO and not in stage 0, we need to sort prev_stage_completions according to ready times of each job
-                if stage != 0 {
-                    prev_stage_completions.sort_by_key(|&(_, time)| time);
-                }
-
-                for (job, prev_stage_completion_time) in prev_stage_completions.iter() {
-                    Self::fifo(
-                        &job,
-                        &stage,
-                        &prev_stage_completion_time,
-                        &mut job_completions,
-                        &mut machine_completions,
-                        &instance,
-                    )
-                }
-            } else if PROCESS_FIRSTCOMPLETE {
-                for _ in 0..n_jobs {
-                    Self::earliest_completion_time(
-                        &stage,
-                        &mut prev_stage_completions,
-                        &mut job_completions,
-                        &mut machine_completions,
-                        &instance,
-                    )
-                }
-            }
+            decoding::schedule_stage(
+                self.rule,
+                &stage,
+                initial_job_order,
+                prev_stage_completions,
+                &mut job_completions,
+                &mut machine_completions,
+                instance,
+            );
             prev_stage_completions = job_completions[stage as usize].clone();
         }
         self.count += 1;
-        return (
-            *job_completions[job_completions.len() - 1]
-                .iter()
-                .map(|(_job, time)| time)
-                .max()
-                .unwrap(),
-            machine_completions,
-        );
+
+        (job_completions, machine_completions)
     }
 
+    // Schedules the job on the machine that completes it the quickest, and returns that machine (u32::MAX if the stage is skipped)
     pub fn fifo(
         job: &u32,
         stage: &u32,
@@ -110,7 +252,7 @@ impl Makespan {
         job_completions: &mut Vec<Vec<(u32, u32)>>,
         machine_completions: &mut Vec<Vec<Vec<(u32, u32)>>>,
         instance: &Instance,
-    ) {
+    ) -> u32 {
         let time;
         let machine;
         if instance.processing_times[*job as usize][*stage as usize] != 0 {
@@ -123,10 +265,8 @@ impl Makespan {
                 &instance,
             );
         } else {
-            (_, time) = *job_completions[*stage as usize]
-                .iter()
-                .last()
-                .unwrap_or(&(0u32, *prev_stage_completion_time));
+            // A job skipping the stage leaves it when it is ready, after its previous stage or release date
+            time = *prev_stage_completion_time;
             machine = u32::MAX;
         }
         // Update datastructures to keep track of production schedule
@@ -134,6 +274,7 @@ impl Makespan {
         if machine != u32::MAX {
             machine_completions[*stage as usize][machine as usize].push((*job, time));
         }
+        machine
     }
 
     // Function for choosing the machine in a given stage that completes the job the quickest
@@ -148,6 +289,12 @@ impl Makespan {
         let mut time_machine: (u32, u32) = (u32::MAX, 0);
         // Loop through all machines in the stage
         for machine in 0..(machine_completions[*stage as usize].len() as usize) {
+            // Skip machines the job can not be processed on (a job skipping the stage can use any machine)
+            if instance.processing_times[*job as usize][*stage as usize] != 0
+                && !instance.is_eligible(*job, *stage, machine as u32)
+            {
+                continue;
+            }
             // Get the number of the last job to complete on the machine and the time the machine is ready for the next job
             let (prev_job, machine_ready_time): (u32, u32) =
                 match machine_completions[*stage as usize][machine].iter().last() {
@@ -155,12 +302,14 @@ impl Makespan {
                     // If the machine has not processed any jobs yet, return (job, 0) because setup_times[machine][job][job] gives the initial setup time
                     None => (*job, 0),
                 };
-            // Compute the completion time
+            // Compute the completion time (processing and setup times depend on the machine if they are unrelated)
             let completion_time = max(machine_ready_time, *prev_stage_completion_time)
-                + instance.processing_times[*job as usize][*stage as usize]
                 + match instance.processing_times[*job as usize][*stage as usize] {
                     0 => 0,
-                    _ => instance.setup_times[*stage as usize][prev_job as usize][*job as usize],
+                    _ => {
+                        instance.processing_time(*job, *stage, machine as u32)
+                            + instance.setup_time(*stage, machine as u32, prev_job, *job)
+                    }
                 };
             // If the time of completion is less than the current best found, update the machine to use and the completion time
             if time_machine.0 > completion_time {
@@ -188,7 +337,9 @@ impl Makespan {
             );
         // Update datastuctures with the new scheduled job
         job_completions[*stage as usize].push((job, time));
-        machine_completions[*stage as usize][machine as usize].push((job, time));
+        if machine != u32::MAX {
+            machine_completions[*stage as usize][machine as usize].push((job, time));
+        }
         // Find the index of the job from the vector of jobs outstanding
         let remove_at_index: usize = jobs_outstanding
             .iter()
@@ -198,7 +349,7 @@ impl Makespan {
         jobs_outstanding.remove(remove_at_index);
     }
 
-    // Determines the job that can be completed the quickest and the corresponding machine, as well as the time it starts and finishes
+    // Determines the job that can be completed the quickest and the corresponding machine (u32::MAX if the job skips the stage), as well as the time it starts and finishes
     fn choose_fastest_job_and_machine(
         stage: &u32,
         jobs_outstanding: &Vec<(u32, u32)>,
@@ -208,14 +359,17 @@ impl Makespan {
         let mut time_job_machine_start = (u32::MAX, 0, 0, u32::MAX);
         // Loop through all jobs left to be scheduled
         for (job, prev_completion_time) in jobs_outstanding.iter() {
-            // Find the machine that can finish the job first
-            let current = Self::choose_machine_for_job(
-                &job,
-                &stage,
-                &prev_completion_time,
-                &machine_completions,
-                &instance,
-            );
+            // Find the machine that can finish the job first, a job skipping the stage leaves it when it is ready
+            let current = match instance.processing_times[*job as usize][*stage as usize] {
+                0 => (*prev_completion_time, u32::MAX),
+                _ => Self::choose_machine_for_job(
+                    &job,
+                    &stage,
+                    &prev_completion_time,
+                    &machine_completions,
+                    &instance,
+                ),
+            };
             // If the time to complete the job is shorter than the current best, set it to the current best
             if time_job_machine_start.0 > current.0 {
                 time_job_machine_start = (current.0, *job, current.1, *prev_completion_time);
@@ -230,15 +384,24 @@ mod makespan_tests {
     use std::time::Instant;
 
     use itertools::Itertools;
+    use rand::{prelude::StdRng, seq::SliceRandom, SeedableRng};
 
-    use crate::{common::instance::parse, genetic_algorithm::tests::tests::test_instance};
+    use crate::{
+        common::{
+            construction::neh::{insert_job, NEH},
+            decoding::DecodingType,
+            instance::{parse, Instance, Solution},
+            objective::ObjectiveType,
+        },
+        genetic_algorithm::tests::tests::test_instance,
+    };
 
     use super::Makespan;
 
     #[test]
     fn makespan_calculation() {
         let instance = test_instance();
-        let mut makespan = Makespan::new(&instance);
+        let mut makespan = Makespan::new(&instance, DecodingType::Fifo);
 
         let jobs1 = vec![0, 1, 2, 3, 4];
         let jobs2 = vec![1, 2, 3, 4, 0];
@@ -262,7 +425,7 @@ mod makespan_tests {
     #[test]
     fn makspan_test() {
         let instance = parse("./instances/ruiz/json/n20m2-01.json").unwrap();
-        let mut makespan = Makespan::new(&instance);
+        let mut makespan = Makespan::new(&instance, DecodingType::Fifo);
         let schedule: Vec<u32> = vec![
             0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19,
         ];
@@ -281,7 +444,7 @@ mod makespan_tests {
     #[test]
     fn makspan_test_unique() {
         let instance = parse("./instances/ruiz/json/n120m8-01.json").unwrap();
-        let mut makespan = Makespan::new(&instance);
+        let mut makespan = Makespan::new(&instance, DecodingType::Fifo);
         let schedule = (0..120).collect::<Vec<u32>>();
         let (_, machine_completions) = makespan.makespan(&schedule);
         for stage in 0..instance.stages {
@@ -300,11 +463,188 @@ mod makespan_tests {
     #[test]
     fn makespan_time() {
         let instance = parse("./instances/ruiz/json/n120m8-01.json").unwrap();
-        let mut makespan = Makespan::new(&instance);
+        let mut makespan = Makespan::new(&instance, DecodingType::Fifo);
         let schedule = (0..120).collect::<Vec<u32>>();
         let start = Instant::now();
         let (mks, _) = makespan.makespan(&schedule);
         let elapsed = start.elapsed().as_millis();
         println!("Found makespan {} in {} ms", mks, elapsed);
     }
+
+    // Evaluates all insertion positions of every job in a random permutation, both incrementally and from scratch
+    fn assert_incremental_equal(instance: &Instance) {
+        let mut full = Makespan::new(instance, DecodingType::Fifo);
+        let mut incremental = Makespan::new_incremental(instance, DecodingType::Fifo);
+        let mut rng = StdRng::seed_from_u64(123);
+
+        let mut jobs: Vec<u32> = (0..instance.jobs).collect();
+        jobs.shuffle(&mut rng);
+
+        for (i, job) in jobs.iter().enumerate() {
+            let partial = jobs[0..i].to_vec();
+            for index in 0..partial.len() + 1 {
+                let mut candidate = partial.clone();
+                candidate.insert(index, *job);
+
+                assert_eq!(
+                    incremental.evaluate(&candidate),
+                    full.makespan(&candidate).0
+                );
+            }
+        }
+        assert_eq!(incremental.count, full.count);
+    }
+
+    #[test]
+    fn incremental_equals_full_decode() {
+        assert_incremental_equal(&test_instance());
+        assert_incremental_equal(&parse("./instances/ruiz/json/n20m2-01.json").unwrap());
+        assert_incremental_equal(&parse("./instances/ruiz/json/n50m8-01.json").unwrap());
+    }
+
+    #[test]
+    fn incremental_neh() {
+        let instance = parse("./instances/ruiz/json/n120m8-01.json").unwrap();
+        let mut full = Makespan::new(&instance, DecodingType::Fifo);
+        let mut incremental = Makespan::new_incremental(&instance, DecodingType::Fifo);
+
+        let (_, full_mks) = NEH::neh(&mut full);
+        let (schedule, incremental_mks) = NEH::neh(&mut incremental);
+        assert_eq!(full_mks, incremental_mks);
+
+        // Inserting a job into the finished schedule gives the same result in both modes
+        let job = schedule[0];
+        let reduced = schedule[1..].to_vec();
+        assert_eq!(
+            insert_job(&mut full, &reduced, &job),
+            insert_job(&mut incremental, &reduced, &job)
+        );
+    }
+
+    #[test]
+    fn release_dates() {
+        let mut instance = test_instance();
+        let jobs = vec![0, 1, 2, 3, 4];
+
+        let (without_release, _) = Makespan::new(&instance, DecodingType::Fifo).makespan(&jobs);
+
+        // No job can start before time 1000, so the schedule is shifted in time
+        instance.release_dates = Some(vec![1000; instance.jobs as usize]);
+        let mut makespan = Makespan::new_incremental(&instance, DecodingType::Fifo);
+        let (with_release, machine_completions) = makespan.makespan(&jobs);
+
+        assert_eq!(with_release, without_release + 1000);
+        assert_eq!(makespan.evaluate(&jobs), with_release);
+        assert!(machine_completions[0]
+            .iter()
+            .flatten()
+            .all(|&(_, completion)| completion > 1000));
+    }
+
+    #[test]
+    fn skipped_stage_after_release() {
+        let mut instance = test_instance();
+        // Job 2 skips stage 0, and is released after the other jobs have completed stage 0
+        instance.release_dates = Some(vec![0, 0, 500, 0, 0]);
+        let jobs = vec![0, 1, 3, 4, 2];
+
+        for rule in [
+            DecodingType::Fifo,
+            DecodingType::FirstComplete,
+            DecodingType::ShortestSetup,
+            DecodingType::LeastLoaded,
+            DecodingType::PermutationOrder,
+        ] {
+            let mut makespan = Makespan::new(&instance, rule);
+            let (mks, machine_completions) = makespan.makespan(&jobs);
+
+            // Job 2 is only processed in stage 1, after its release date
+            let completion = machine_completions[1]
+                .iter()
+                .flatten()
+                .find(|&&(job, _)| job == 2)
+                .unwrap()
+                .1;
+            assert!(completion >= 500 + 49, "{:?}", rule);
+            assert_eq!(
+                Solution::new(machine_completions, mks, &instance).check(&instance),
+                vec![],
+                "{:?}",
+                rule
+            );
+
+            makespan.objective = ObjectiveType::TotalFlowTime;
+            assert!(makespan.evaluate(&jobs) >= completion - 500);
+        }
+    }
+
+    #[test]
+    fn skipped_stage_at_ready_time() {
+        // Some jobs skip stages in n50m4-10, see the top of common::decoding
+        let instance = parse("./instances/ruiz/json/n50m4-10.json").unwrap();
+        let schedule: Vec<u32> = (0..instance.jobs).collect();
+
+        for rule in [
+            DecodingType::Fifo,
+            DecodingType::FirstComplete,
+            DecodingType::ShortestSetup,
+            DecodingType::LeastLoaded,
+            DecodingType::PermutationOrder,
+        ] {
+            let (job_completions, machine_completions) =
+                Makespan::new(&instance, rule).decode(&schedule);
+            let completion = |stage: usize, job: u32| {
+                job_completions[stage]
+                    .iter()
+                    .find(|&&(j, _)| j == job)
+                    .unwrap()
+                    .1
+            };
+
+            for job in schedule.iter().copied() {
+                for (stage, machines) in machine_completions.iter().enumerate() {
+                    if instance.processing_times[job as usize][stage] != 0 {
+                        continue;
+                    }
+                    let ready = match stage {
+                        0 => instance.release_date(job),
+                        _ => completion(stage - 1, job),
+                    };
+                    assert_eq!(completion(stage, job), ready, "{:?}", rule);
+                    assert!(machines.iter().flatten().all(|&(j, _)| j != job));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn incremental_objectives() {
+        let mut instance = parse("./instances/ruiz/json/n20m2-01.json").unwrap();
+        instance.release_dates = Some((0..instance.jobs).map(|job| job * 10).collect());
+        instance.due_dates = Some((0..instance.jobs).map(|job| 300 + job * 50).collect());
+        instance.weights = Some((0..instance.jobs).map(|job| 1 + job % 3).collect());
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let mut schedule = (0..instance.jobs).collect_vec();
+
+        for objective in [
+            ObjectiveType::TotalFlowTime,
+            ObjectiveType::TotalCompletionTime,
+            ObjectiveType::TotalSetupTime,
+            ObjectiveType::Weighted,
+            ObjectiveType::TotalTardiness,
+            ObjectiveType::WeightedTardiness,
+            ObjectiveType::MaxLateness,
+        ] {
+            let mut full = Makespan::new(&instance, DecodingType::Fifo);
+            let mut incremental = Makespan::new_incremental(&instance, DecodingType::Fifo);
+            full.objective = objective;
+            incremental.objective = objective;
+
+            for _ in 0..20 {
+                schedule.shuffle(&mut rng);
+                assert_eq!(full.evaluate(&schedule), incremental.evaluate(&schedule));
+            }
+        }
+    }
 }
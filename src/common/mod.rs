@@ -1,6 +1,14 @@
 pub mod best_insertion;
+pub mod best_known;
+pub mod bounds;
 pub mod common_tests;
 pub mod construction;
+pub mod decoding;
 pub mod instance;
 pub mod makespan;
+pub mod neighborhood;
+pub mod objective;
+pub mod run;
+pub mod statistics;
+pub mod termination;
 pub mod utils;
@@ -0,0 +1,379 @@
+use rand::{
+    prelude::{SliceRandom, StdRng},
+    Rng,
+};
+use serde_derive::{Deserialize, Serialize};
+
+use super::{makespan::Makespan, termination::Progress};
+
+/*
+Neighborhoods of a job permutation, and local search in them. A neighborhood gives the moves of a permutation of
+n jobs by index, so that it can be explored in order, in random order or sampled:
+    Insertion: a job is removed and inserted at another position, (n - 1)^2 moves, as moving a job one position
+               back is the same as moving the job before it one position forward (see tabu_search::neighborhood)
+    Swap: two jobs are exchanged, n (n - 1) / 2 moves
+    AdjacentSwap: two neighbouring jobs are exchanged, n - 1 moves
+    BlockMove: a block of two or three consecutive jobs is moved to another position
+    Reversal: the order of the jobs between two positions is reversed, n (n - 1) / 2 moves
+
+A neighborhood is explored with
+    FirstImprovement: the moves are evaluated in random order, and the first improving move is made
+    BestImprovement: all moves are evaluated, and the best improving move is made
+and the exploration stops early when the termination criteria of the progress are met.
+*/
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum NTYPE {
+    Insertion,
+    Swap,
+    AdjacentSwap,
+    BlockMove,
+    Reversal,
+}
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum Exploration {
+    FirstImprovement,
+    BestImprovement,
+}
+
+pub trait Neighborhood {
+    // Number of moves of a permutation of n jobs
+    fn size(n: usize) -> usize;
+
+    // The permutation after the move with the index
+    fn neighbour(jobs: &[u32], index: usize) -> Vec<u32>;
+}
+
+pub struct Insertion;
+pub struct Swap;
+pub struct AdjacentSwap;
+pub struct BlockMove;
+pub struct Reversal;
+
+// Lengths of the blocks moved by BlockMove
+const BLOCK_LENGTHS: [usize; 2] = [2, 3];
+
+impl Neighborhood for Insertion {
+    fn size(n: usize) -> usize {
+        n.saturating_sub(1).pow(2)
+    }
+
+    fn neighbour(jobs: &[u32], index: usize) -> Vec<u32> {
+        let (from, to) = insertion(jobs.len(), index);
+
+        let mut neighbour = jobs.to_vec();
+        let job = neighbour.remove(from);
+        neighbour.insert(to, job);
+        neighbour
+    }
+}
+
+impl Neighborhood for Swap {
+    fn size(n: usize) -> usize {
+        n * n.saturating_sub(1) / 2
+    }
+
+    fn neighbour(jobs: &[u32], index: usize) -> Vec<u32> {
+        let (first, second) = pair(jobs.len(), index);
+
+        let mut neighbour = jobs.to_vec();
+        neighbour.swap(first, second);
+        neighbour
+    }
+}
+
+impl Neighborhood for AdjacentSwap {
+    fn size(n: usize) -> usize {
+        n.saturating_sub(1)
+    }
+
+    fn neighbour(jobs: &[u32], index: usize) -> Vec<u32> {
+        let mut neighbour = jobs.to_vec();
+        neighbour.swap(index, index + 1);
+        neighbour
+    }
+}
+
+impl Neighborhood for BlockMove {
+    fn size(n: usize) -> usize {
+        BLOCK_LENGTHS
+            .iter()
+            .map(|&length| other_positions((n + 1).saturating_sub(length)))
+            .sum()
+    }
+
+    fn neighbour(jobs: &[u32], index: usize) -> Vec<u32> {
+        let mut index = index;
+
+        for length in BLOCK_LENGTHS {
+            // A block is moved as a single job in the permutation without the block
+            let positions = (jobs.len() + 1).saturating_sub(length);
+            if index >= other_positions(positions) {
+                index -= other_positions(positions);
+                continue;
+            }
+
+            let (from, to) = other_position(positions, index);
+            let mut neighbour = jobs.to_vec();
+            let block: Vec<u32> = neighbour.drain(from..from + length).collect();
+            neighbour.splice(to..to, block);
+            return neighbour;
+        }
+
+        panic!("Move {} is not in the neighborhood", index)
+    }
+}
+
+impl Neighborhood for Reversal {
+    fn size(n: usize) -> usize {
+        Swap::size(n)
+    }
+
+    fn neighbour(jobs: &[u32], index: usize) -> Vec<u32> {
+        let (first, second) = pair(jobs.len(), index);
+
+        let mut neighbour = jobs.to_vec();
+        neighbour[first..=second].reverse();
+        neighbour
+    }
+}
+
+// Positions (from, to) with from != to and to != from - 1, with the index in the order (0, 1), (0, 2), ...,
+// (1, 2), (1, 3), ..., (2, 0), (2, 3), ...
+fn insertion(n: usize, index: usize) -> (usize, usize) {
+    // The first job can be moved to n - 1 positions, the others to n - 2
+    if index < n - 1 {
+        return (0, index + 1);
+    }
+    let from = 1 + (index - (n - 1)) / (n - 2);
+    let to = (index - (n - 1)) % (n - 2);
+
+    (from, if to + 1 >= from { to + 2 } else { to })
+}
+
+// Number of positions (from, to) with from != to
+fn other_positions(n: usize) -> usize {
+    n * n.saturating_sub(1)
+}
+
+// Positions (from, to) with from != to, with the index in the order (0, 1), (0, 2), ..., (1, 0), (1, 2), ...
+fn other_position(n: usize, index: usize) -> (usize, usize) {
+    let from = index / (n - 1);
+    let to = index % (n - 1);
+
+    (from, if to >= from { to + 1 } else { to })
+}
+
+// Positions (first, second) with first < second, with the index in the order (0, 1), (0, 2), ..., (1, 2), ...
+fn pair(n: usize, index: usize) -> (usize, usize) {
+    let mut index = index;
+    let mut first = 0;
+
+    while index >= n - 1 - first {
+        index -= n - 1 - first;
+        first += 1;
+    }
+
+    (first, first + 1 + index)
+}
+
+// The best or first improving neighbour of the schedule, or None if it is a local optimum of the neighborhood
+pub fn improve<N: Neighborhood>(
+    exploration: Exploration,
+    makespan: &mut Makespan,
+    schedule: &(Vec<u32>, u32),
+    progress: &Progress,
+    rng: &mut StdRng,
+) -> Option<(Vec<u32>, u32)> {
+    let mut order: Vec<usize> = (0..N::size(schedule.0.len())).collect();
+    if exploration == Exploration::FirstImprovement {
+        order.shuffle(rng);
+    }
+
+    let mut best: Option<(Vec<u32>, u32)> = None;
+    for index in order {
+        if progress.is_terminated(makespan.count) {
+            break;
+        }
+
+        let neighbour = N::neighbour(&schedule.0, index);
+        let value = makespan.evaluate(&neighbour);
+        if value < best.as_ref().map_or(schedule.1, |b| b.1) {
+            best = Some((neighbour, value));
+            if exploration == Exploration::FirstImprovement {
+                break;
+            }
+        }
+    }
+
+    best
+}
+
+// A random neighbour of the jobs, the jobs themselves if the neighborhood is empty
+pub fn random_neighbour<N: Neighborhood>(jobs: &[u32], rng: &mut StdRng) -> Vec<u32> {
+    match N::size(jobs.len()) {
+        0 => jobs.to_vec(),
+        size => N::neighbour(jobs, rng.gen_range(0..size)),
+    }
+}
+
+impl NTYPE {
+    // The best or first improving neighbour in the neighborhood, see improve
+    pub fn improve(
+        &self,
+        exploration: Exploration,
+        makespan: &mut Makespan,
+        schedule: &(Vec<u32>, u32),
+        progress: &Progress,
+        rng: &mut StdRng,
+    ) -> Option<(Vec<u32>, u32)> {
+        match self {
+            NTYPE::Insertion => {
+                improve::<Insertion>(exploration, makespan, schedule, progress, rng)
+            }
+            NTYPE::Swap => improve::<Swap>(exploration, makespan, schedule, progress, rng),
+            NTYPE::AdjacentSwap => {
+                improve::<AdjacentSwap>(exploration, makespan, schedule, progress, rng)
+            }
+            NTYPE::BlockMove => {
+                improve::<BlockMove>(exploration, makespan, schedule, progress, rng)
+            }
+            NTYPE::Reversal => improve::<Reversal>(exploration, makespan, schedule, progress, rng),
+        }
+    }
+
+    // A random neighbour of the jobs in the neighborhood, see random_neighbour
+    pub fn random_neighbour(&self, jobs: &[u32], rng: &mut StdRng) -> Vec<u32> {
+        match self {
+            NTYPE::Insertion => random_neighbour::<Insertion>(jobs, rng),
+            NTYPE::Swap => random_neighbour::<Swap>(jobs, rng),
+            NTYPE::AdjacentSwap => random_neighbour::<AdjacentSwap>(jobs, rng),
+            NTYPE::BlockMove => random_neighbour::<BlockMove>(jobs, rng),
+            NTYPE::Reversal => random_neighbour::<Reversal>(jobs, rng),
+        }
+    }
+
+    // Improves the schedule in the neighborhood until it is a local optimum
+    pub fn descent(
+        &self,
+        exploration: Exploration,
+        makespan: &mut Makespan,
+        schedule: (Vec<u32>, u32),
+        progress: &Progress,
+        rng: &mut StdRng,
+    ) -> (Vec<u32>, u32) {
+        let mut current = schedule;
+        while let Some(better) = self.improve(exploration, makespan, &current, progress, rng) {
+            current = better;
+        }
+        current
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use rand::{prelude::StdRng, SeedableRng};
+
+    use crate::common::{
+        decoding::DecodingType,
+        instance::parse,
+        makespan::Makespan,
+        termination::{Progress, Termination},
+    };
+
+    use super::{
+        AdjacentSwap, BlockMove, Exploration, Insertion, Neighborhood, Reversal, Swap, NTYPE,
+    };
+
+    // All neighbours of the permutation
+    fn neighbours<N: Neighborhood>(jobs: &[u32]) -> Vec<Vec<u32>> {
+        (0..N::size(jobs.len()))
+            .map(|index| N::neighbour(jobs, index))
+            .collect()
+    }
+
+    #[test]
+    fn neighborhood_moves() {
+        let jobs: Vec<u32> = (0..6).collect();
+
+        for (neighbours, size) in [
+            // Moving a job one position back is moving the job before it one position forward
+            (neighbours::<Insertion>(&jobs), 25),
+            (neighbours::<Swap>(&jobs), 15),
+            (neighbours::<AdjacentSwap>(&jobs), 5),
+            // Blocks of two jobs have 5 positions, and blocks of three 4
+            (neighbours::<BlockMove>(&jobs), 20 + 12),
+            (neighbours::<Reversal>(&jobs), 15),
+        ] {
+            assert_eq!(neighbours.len(), size);
+            for neighbour in neighbours.iter() {
+                assert_ne!(neighbour, &jobs);
+                let mut sorted = neighbour.clone();
+                sorted.sort();
+                assert_eq!(sorted, jobs);
+            }
+        }
+
+        // All insertions give different permutations
+        let mut insertions = neighbours::<Insertion>(&jobs);
+        insertions.sort();
+        insertions.dedup();
+        assert_eq!(insertions.len(), 25);
+
+        assert_eq!(Insertion::neighbour(&jobs, 0), vec![1, 0, 2, 3, 4, 5]);
+        assert_eq!(Insertion::neighbour(&jobs, 5), vec![0, 2, 1, 3, 4, 5]);
+        assert_eq!(Insertion::neighbour(&jobs, 9), vec![2, 0, 1, 3, 4, 5]);
+        assert_eq!(BlockMove::neighbour(&jobs, 0), vec![2, 0, 1, 3, 4, 5]);
+        assert_eq!(Reversal::neighbour(&jobs, 14), vec![0, 1, 2, 3, 5, 4]);
+        assert_eq!(Reversal::neighbour(&jobs, 4), vec![5, 4, 3, 2, 1, 0]);
+    }
+
+    #[test]
+    fn descent_to_local_optimum() {
+        let instance = parse("./instances/ruiz/json/n20m2-43.json").unwrap();
+        let mut makespan = Makespan::new_incremental(&instance, DecodingType::Fifo);
+        let mut rng = StdRng::seed_from_u64(123);
+        let progress = Progress::new(&Termination::default(), 0, Duration::ZERO);
+        let jobs: Vec<u32> = (0..instance.jobs).collect();
+        let start = (jobs.clone(), makespan.evaluate(&jobs));
+
+        for exploration in [Exploration::FirstImprovement, Exploration::BestImprovement] {
+            let optimum = NTYPE::Swap.descent(
+                exploration,
+                &mut makespan,
+                start.clone(),
+                &progress,
+                &mut rng,
+            );
+            assert!(optimum.1 < start.1);
+            assert_eq!(makespan.evaluate(&optimum.0), optimum.1);
+            assert!(NTYPE::Swap
+                .improve(exploration, &mut makespan, &optimum, &progress, &mut rng)
+                .is_none());
+        }
+
+        // Budget of the progress
+        let progress = Progress::new(
+            &Termination {
+                max_evaluations: Some(10),
+                ..Termination::default()
+            },
+            makespan.count,
+            Duration::ZERO,
+        );
+        let count = makespan.count;
+        NTYPE::Insertion.descent(
+            Exploration::BestImprovement,
+            &mut makespan,
+            start,
+            &progress,
+            &mut rng,
+        );
+        assert_eq!(makespan.count, count + 10);
+    }
+}
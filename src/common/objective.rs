@@ -0,0 +1,324 @@
+use clap::ArgEnum;
+use serde_derive::{Deserialize, Serialize};
+
+use super::instance::Instance;
+
+/*
+Objective values are computed from a decoded schedule and are always minimized.
+C_j is the completion time of job j in the last stage.
+
+Makespan: max C_j
+TotalFlowTime: sum (C_j - r_j)
+TotalCompletionTime: sum C_j
+TotalSetupTime: sum of all setup times on all machines, including the initial setups
+TotalTardiness: sum max(0, C_j - d_j)
+WeightedTardiness: sum w_j * max(0, C_j - d_j)
+MaxLateness: max (C_j - d_j), shifted by the latest due date (+ max d_j) as lateness can be negative
+Weighted: sum of the objectives in the objective weights, each multiplied by its weight. The weights are set by
+the objective_weights option (params::OBJECTIVE_WEIGHTS by default), and the sum saturates at u32::MAX
+*/
+
+#[allow(dead_code)]
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum ObjectiveType {
+    Makespan,
+    TotalFlowTime,
+    TotalCompletionTime,
+    TotalSetupTime,
+    TotalTardiness,
+    WeightedTardiness,
+    MaxLateness,
+    Weighted,
+}
+
+// A decoded schedule, as produced by the decoder in common::makespan
+pub struct Schedule<'a> {
+    // job_completions[stage][job](job_number, completion_time)
+    pub job_completions: &'a [Vec<(u32, u32)>],
+    // machine_completions[stage][machine][machine_run](job_number, completion_time)
+    pub machine_completions: &'a [Vec<Vec<(u32, u32)>>],
+}
+
+impl Schedule<'_> {
+    // Completions of all jobs in the last stage
+    pub fn last_stage(&self) -> &[(u32, u32)] {
+        &self.job_completions[self.job_completions.len() - 1]
+    }
+}
+
+pub trait Objective {
+    fn value(schedule: &Schedule, instance: &Instance) -> u32;
+}
+
+pub struct Makespan;
+pub struct TotalFlowTime;
+pub struct TotalCompletionTime;
+pub struct TotalSetupTime;
+pub struct TotalTardiness;
+pub struct WeightedTardiness;
+pub struct MaxLateness;
+pub struct Weighted;
+
+impl Objective for Makespan {
+    fn value(schedule: &Schedule, _instance: &Instance) -> u32 {
+        schedule
+            .last_stage()
+            .iter()
+            .map(|&(_, time)| time)
+            .max()
+            .unwrap()
+    }
+}
+
+impl Objective for TotalFlowTime {
+    fn value(schedule: &Schedule, instance: &Instance) -> u32 {
+        schedule
+            .last_stage()
+            .iter()
+            // The decoder completes no job before its release date, other schedules are cut off at zero
+            .map(|&(job, time)| time.saturating_sub(instance.release_date(job)))
+            .sum()
+    }
+}
+
+impl Objective for TotalCompletionTime {
+    fn value(schedule: &Schedule, _instance: &Instance) -> u32 {
+        schedule.last_stage().iter().map(|&(_, time)| time).sum()
+    }
+}
+
+impl Objective for TotalSetupTime {
+    fn value(schedule: &Schedule, instance: &Instance) -> u32 {
+        let mut total = 0;
+
+        for (stage, machines) in schedule.machine_completions.iter().enumerate() {
+            for (machine, machine_run) in machines.iter().enumerate() {
+                // setup_times[stage][job][job] is the initial setup of the first job on a machine
+                let mut prev_job = match machine_run.first() {
+                    Some(&(job, _)) => job,
+                    None => continue,
+                };
+
+                for &(job, _) in machine_run.iter() {
+                    total += instance.setup_time(stage as u32, machine as u32, prev_job, job);
+                    prev_job = job;
+                }
+            }
+        }
+        total
+    }
+}
+
+impl Objective for TotalTardiness {
+    fn value(schedule: &Schedule, instance: &Instance) -> u32 {
+        schedule
+            .last_stage()
+            .iter()
+            .map(|&(job, time)| time.saturating_sub(instance.due_date(job)))
+            .sum()
+    }
+}
+
+impl Objective for WeightedTardiness {
+    fn value(schedule: &Schedule, instance: &Instance) -> u32 {
+        schedule
+            .last_stage()
+            .iter()
+            .map(|&(job, time)| instance.weight(job) * time.saturating_sub(instance.due_date(job)))
+            .sum()
+    }
+}
+
+impl Objective for MaxLateness {
+    fn value(schedule: &Schedule, instance: &Instance) -> u32 {
+        let latest_due_date = (0..instance.jobs)
+            .map(|job| instance.due_date(job))
+            .max()
+            .unwrap_or(0);
+
+        schedule
+            .last_stage()
+            .iter()
+            .map(|&(job, time)| time + latest_due_date - instance.due_date(job))
+            .max()
+            .unwrap()
+    }
+}
+
+impl Weighted {
+    pub fn value(
+        schedule: &Schedule,
+        instance: &Instance,
+        weights: &[(ObjectiveType, u32)],
+    ) -> u32 {
+        weights
+            .iter()
+            // A weighted objective can not be part of itself
+            .filter(|(objective, _)| *objective != ObjectiveType::Weighted)
+            .map(|(objective, weight)| {
+                weight.saturating_mul(objective.evaluate(schedule, instance, &[]))
+            })
+            .fold(0, u32::saturating_add)
+    }
+}
+
+impl ObjectiveType {
+    // Objective value of the schedule, where the weights are only used by the weighted objective
+    pub fn evaluate(
+        &self,
+        schedule: &Schedule,
+        instance: &Instance,
+        weights: &[(ObjectiveType, u32)],
+    ) -> u32 {
+        match self {
+            ObjectiveType::Makespan => Makespan::value(schedule, instance),
+            ObjectiveType::TotalFlowTime => TotalFlowTime::value(schedule, instance),
+            ObjectiveType::TotalCompletionTime => TotalCompletionTime::value(schedule, instance),
+            ObjectiveType::TotalSetupTime => TotalSetupTime::value(schedule, instance),
+            ObjectiveType::TotalTardiness => TotalTardiness::value(schedule, instance),
+            ObjectiveType::WeightedTardiness => WeightedTardiness::value(schedule, instance),
+            ObjectiveType::MaxLateness => MaxLateness::value(schedule, instance),
+            ObjectiveType::Weighted => Weighted::value(schedule, instance, weights),
+        }
+    }
+}
+
+#[cfg(test)]
+mod objective_test {
+    use crate::common::instance::Instance;
+
+    use super::{ObjectiveType, Schedule};
+
+    fn instance() -> Instance {
+        Instance {
+            jobs: 3,
+            stages: 1,
+            machines: vec![2],
+            setup_times: vec![vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]]],
+            release_dates: Some(vec![0, 2, 4]),
+            due_dates: Some(vec![10, 20, 30]),
+            weights: Some(vec![1, 2, 3]),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn tardiness_objectives() {
+        let instance = instance();
+        let job_completions = vec![vec![(0, 15), (1, 12), (2, 40)]];
+        let schedule = Schedule {
+            job_completions: &job_completions,
+            machine_completions: &[],
+        };
+
+        assert_eq!(
+            ObjectiveType::Makespan.evaluate(&schedule, &instance, &[]),
+            40
+        );
+        assert_eq!(
+            ObjectiveType::TotalTardiness.evaluate(&schedule, &instance, &[]),
+            5 + 10
+        );
+        assert_eq!(
+            ObjectiveType::WeightedTardiness.evaluate(&schedule, &instance, &[]),
+            5 + 3 * 10
+        );
+        // Lateness is 5, -8 and 10, shifted by the latest due date 30
+        assert_eq!(
+            ObjectiveType::MaxLateness.evaluate(&schedule, &instance, &[]),
+            10 + 30
+        );
+    }
+
+    #[test]
+    fn flow_time_from_release() {
+        let instance = instance();
+        // Job 2 completes before its release date, which the decoder never does
+        let job_completions = vec![vec![(0, 15), (1, 12), (2, 3)]];
+        let schedule = Schedule {
+            job_completions: &job_completions,
+            machine_completions: &[],
+        };
+
+        assert_eq!(
+            ObjectiveType::TotalFlowTime.evaluate(&schedule, &instance, &[]),
+            15 + (12 - 2)
+        );
+    }
+
+    #[test]
+    fn no_tardiness_when_early() {
+        let instance = instance();
+        let job_completions = vec![vec![(0, 5), (1, 12), (2, 25)]];
+        let schedule = Schedule {
+            job_completions: &job_completions,
+            machine_completions: &[],
+        };
+
+        assert_eq!(
+            ObjectiveType::TotalTardiness.evaluate(&schedule, &instance, &[]),
+            0
+        );
+        // Largest lateness is -5 (job 0 and job 2)
+        assert_eq!(
+            ObjectiveType::MaxLateness.evaluate(&schedule, &instance, &[]),
+            30 - 5
+        );
+    }
+
+    #[test]
+    fn completion_and_setup_objectives() {
+        let instance = instance();
+        let job_completions = vec![vec![(0, 5), (2, 9), (1, 12)]];
+        // Job 0 and 1 on machine 0, job 2 on machine 1
+        let machine_completions = vec![vec![vec![(0, 5), (1, 12)], vec![(2, 9)]]];
+        let schedule = Schedule {
+            job_completions: &job_completions,
+            machine_completions: &machine_completions,
+        };
+
+        assert_eq!(
+            ObjectiveType::TotalCompletionTime.evaluate(&schedule, &instance, &[]),
+            5 + 9 + 12
+        );
+        assert_eq!(
+            ObjectiveType::TotalFlowTime.evaluate(&schedule, &instance, &[]),
+            5 + (9 - 4) + (12 - 2)
+        );
+        // Initial setups of job 0 and job 2, and setup from job 0 to job 1
+        assert_eq!(
+            ObjectiveType::TotalSetupTime.evaluate(&schedule, &instance, &[]),
+            1 + 9 + 2
+        );
+    }
+
+    #[test]
+    fn weighted_objective() {
+        let instance = instance();
+        let job_completions = vec![vec![(0, 15), (1, 12), (2, 40)]];
+        let schedule = Schedule {
+            job_completions: &job_completions,
+            machine_completions: &[],
+        };
+
+        let weights = [
+            (ObjectiveType::Makespan, 10),
+            (ObjectiveType::TotalTardiness, 2),
+            (ObjectiveType::Weighted, 100),
+        ];
+        assert_eq!(
+            ObjectiveType::Weighted.evaluate(&schedule, &instance, &weights),
+            10 * 40 + 2 * (5 + 10)
+        );
+
+        // The sum saturates instead of overflowing
+        let weights = [
+            (ObjectiveType::Makespan, u32::MAX / 2),
+            (ObjectiveType::TotalCompletionTime, 1),
+        ];
+        assert_eq!(
+            ObjectiveType::Weighted.evaluate(&schedule, &instance, &weights),
+            u32::MAX
+        );
+    }
+}
@@ -0,0 +1,151 @@
+use std::{borrow::Cow, path::PathBuf};
+
+use rand::{prelude::StdRng, SeedableRng};
+
+use crate::{
+    common::{
+        makespan::Makespan,
+        termination::{Progress, Termination},
+        utils,
+    },
+    genetic_algorithm::params,
+    iterated_greedy::options::Options as IgOptions,
+};
+
+/*
+Options and output shared by the local search algorithms run on a Makespan (SA, tabu search, VNS and ALNS).
+Each algorithm has its own options with a RunOptions, and is run by the Solver trait (see construction::solver)
+with the run options of the IG options and its own defaults, see run_solver.
+
+The improvement file of a run is written to <improvement_folder>/<algorithm>/all/<problem>.csv, see
+utils::write_makespan_improvement, with a row for the initial solution, every new best solution and the end:
+    seed, generation, objective value, evaluations, elapsed milliseconds
+*/
+
+#[derive(Clone, Debug)]
+pub struct RunOptions {
+    // Path to input file defining problem
+    pub problem_file: Cow<'static, PathBuf>,
+
+    // When to stop, see common::termination. What a generation is depends on the algorithm
+    pub termination: Termination,
+
+    // Seed of the random number generator
+    pub seed: u64,
+
+    // Write the improvement over the generations to file
+    pub write_improvement: bool,
+
+    // Folder of the improvement files, see utils::write_makespan_improvement
+    pub improvement_folder: PathBuf,
+}
+
+impl Default for RunOptions {
+    fn default() -> Self {
+        Self {
+            problem_file: Cow::Owned(PathBuf::from(params::PROBLEM_FILE)),
+            termination: Termination::default(),
+            seed: params::SEED,
+            write_improvement: params::WRITE_IMPROVEMENT,
+            improvement_folder: PathBuf::from(params::IMPROVEMENT_FOLDER),
+        }
+    }
+}
+
+impl From<IgOptions> for RunOptions {
+    fn from(options: IgOptions) -> Self {
+        Self {
+            problem_file: options.problem_file,
+            termination: options.termination,
+            seed: options.seed,
+            write_improvement: options.write_improvement,
+            improvement_folder: options.improvement_folder,
+        }
+    }
+}
+
+// Options of an algorithm with run options
+pub trait AlgorithmOptions: Default {
+    fn run_options(&mut self) -> &mut RunOptions;
+}
+
+// Algorithm improving the given schedule, or its own initial schedule, see e.g. tabu_search::tabu_search
+pub type Algorithm<O> =
+    fn(&mut Makespan, Option<(Vec<u32>, u32)>, Option<O>, &mut StdRng) -> (Vec<u32>, u32);
+
+// Runs the algorithm with the run options of the IG options and its own defaults otherwise, see Solver::run
+pub fn run_solver<O: AlgorithmOptions>(
+    makespan: &mut Makespan,
+    option: Option<IgOptions>,
+    algorithm: Algorithm<O>,
+) -> u32 {
+    let seed = option.as_ref().map_or(params::SEED, |o| o.seed);
+    let options = option.map(|o| {
+        let mut options = O::default();
+        *options.run_options() = RunOptions::from(o);
+        options
+    });
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    algorithm(makespan, None, options, &mut rng).1
+}
+
+// Improvement of the best solution over a run, kept if write_improvement is set
+pub struct Improvement {
+    records: Option<Vec<Vec<String>>>,
+}
+
+impl Improvement {
+    // Starts with the objective value of the initial solution
+    pub fn new(options: &RunOptions, value: u32, evaluations: u32) -> Improvement {
+        let records = options.write_improvement.then(|| {
+            vec![vec![
+                "0".to_string(),
+                value.to_string(),
+                evaluations.to_string(),
+                "0".to_string(),
+            ]]
+        });
+
+        Improvement { records }
+    }
+
+    // Adds a new best objective value
+    pub fn push(&mut self, progress: &Progress, value: u32, evaluations: u32) {
+        if let Some(records) = self.records.as_mut() {
+            records.push(vec![
+                progress.generation.to_string(),
+                value.to_string(),
+                evaluations.to_string(),
+                progress.elapsed().as_millis().to_string(),
+            ]);
+        }
+    }
+
+    // Adds the best objective value at the end of the run and writes the improvement file of the algorithm
+    pub fn write(
+        mut self,
+        algorithm: &str,
+        options: &RunOptions,
+        progress: &Progress,
+        value: u32,
+        evaluations: u32,
+    ) {
+        self.push(progress, value, evaluations);
+        let records = match self.records {
+            Some(records) => records,
+            None => return,
+        };
+
+        let mut filename = PathBuf::from(options.problem_file.file_name().unwrap());
+        filename.set_extension("csv");
+        let filename = PathBuf::from(algorithm).join("all").join(filename);
+        utils::write_makespan_improvement(
+            &options.improvement_folder,
+            filename,
+            options.seed,
+            &records,
+        )
+        .unwrap();
+    }
+}
@@ -0,0 +1,311 @@
+use std::{collections::HashMap, error::Error, fs, path::Path, time::Duration};
+
+use csv::{ReaderBuilder, Writer};
+use serde_derive::Serialize;
+
+use super::best_known;
+
+/*
+Statistics of replicated runs of an algorithm on an instance, as written to summary.csv and summary.json by
+solve and bench. The relative percentage deviation (RPD) of a value is 100 * (value - best known) / best known,
+where the best known values are read from a file with read_best_known. For the makespan, the gap of the min
+value to the lower bound of the instance (see common::bounds) is the same deviation from the lower bound, and an
+upper bound on the deviation from the optimal makespan.
+
+A run reaches the target if its objective value is at most the target. The time to target is the mean time of
+the runs that reach it, which is the time until the target was found when the target is also a termination
+criterion (see common::termination).
+*/
+
+// Objective value and run time of a single run
+#[derive(Clone, Copy, Debug)]
+pub struct Run {
+    pub value: u32,
+    pub elapsed: Duration,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Summary {
+    pub instance: String,
+    pub algorithm: String,
+    pub replications: usize,
+
+    // Objective values of the runs
+    pub mean: f64,
+    pub min: u32,
+    pub max: u32,
+    // Sample standard deviation, 0 for a single run
+    pub stddev: f64,
+
+    pub mean_time_ms: f64,
+
+    // RPD of the mean and min value, if the best known value of the instance is known
+    pub best_known: Option<u32>,
+    pub mean_rpd: Option<f64>,
+    pub min_rpd: Option<f64>,
+
+    // Lower bound of the instance and the deviation of the min value from it, for the makespan only
+    pub lower_bound: Option<u32>,
+    pub lower_bound_gap: Option<f64>,
+
+    // Number of runs that reached the target, and their mean time
+    pub target: Option<u32>,
+    pub hits: usize,
+    pub time_to_target_ms: Option<f64>,
+}
+
+pub const SUMMARY_HEADER: [&str; 16] = [
+    "instance",
+    "algorithm",
+    "replications",
+    "mean",
+    "min",
+    "max",
+    "stddev",
+    "mean_time_ms",
+    "best_known",
+    "mean_rpd",
+    "min_rpd",
+    "lower_bound",
+    "lower_bound_gap",
+    "target",
+    "hits",
+    "time_to_target_ms",
+];
+
+impl Summary {
+    pub fn new(
+        instance: &str,
+        algorithm: &str,
+        runs: &[Run],
+        best_known: Option<u32>,
+        lower_bound: Option<u32>,
+        target: Option<u32>,
+    ) -> Summary {
+        assert!(!runs.is_empty(), "A summary needs at least one run");
+
+        let n = runs.len() as f64;
+        let mean = runs.iter().map(|r| r.value as f64).sum::<f64>() / n;
+        let min = runs.iter().map(|r| r.value).min().unwrap();
+        let max = runs.iter().map(|r| r.value).max().unwrap();
+        let stddev = match runs.len() {
+            1 => 0.0,
+            _ => (runs
+                .iter()
+                .map(|r| (r.value as f64 - mean).powi(2))
+                .sum::<f64>()
+                / (n - 1.0))
+                .sqrt(),
+        };
+
+        let hit_times: Vec<f64> = runs
+            .iter()
+            .filter(|r| target.is_some_and(|t| r.value <= t))
+            .map(|r| r.elapsed.as_secs_f64() * 1000.0)
+            .collect();
+
+        Summary {
+            instance: instance.to_string(),
+            algorithm: algorithm.to_string(),
+            replications: runs.len(),
+            mean,
+            min,
+            max,
+            stddev,
+            mean_time_ms: runs
+                .iter()
+                .map(|r| r.elapsed.as_secs_f64() * 1000.0)
+                .sum::<f64>()
+                / n,
+            best_known,
+            mean_rpd: best_known.and_then(|b| rpd(mean, b)),
+            min_rpd: best_known.and_then(|b| rpd(min as f64, b)),
+            lower_bound,
+            lower_bound_gap: lower_bound.and_then(|b| rpd(min as f64, b)),
+            target,
+            hits: hit_times.len(),
+            time_to_target_ms: match hit_times.is_empty() {
+                true => None,
+                false => Some(hit_times.iter().sum::<f64>() / hit_times.len() as f64),
+            },
+        }
+    }
+
+    // The summary as a row of summary.csv, with the columns in SUMMARY_HEADER
+    pub fn record(&self) -> Vec<String> {
+        let optional = |v: Option<String>| v.unwrap_or_default();
+
+        vec![
+            self.instance.clone(),
+            self.algorithm.clone(),
+            self.replications.to_string(),
+            format!("{:.2}", self.mean),
+            self.min.to_string(),
+            self.max.to_string(),
+            format!("{:.2}", self.stddev),
+            format!("{:.1}", self.mean_time_ms),
+            optional(self.best_known.map(|b| b.to_string())),
+            optional(self.mean_rpd.map(|r| format!("{:.3}", r))),
+            optional(self.min_rpd.map(|r| format!("{:.3}", r))),
+            optional(self.lower_bound.map(|b| b.to_string())),
+            optional(self.lower_bound_gap.map(|r| format!("{:.3}", r))),
+            optional(self.target.map(|t| t.to_string())),
+            self.hits.to_string(),
+            optional(self.time_to_target_ms.map(|t| format!("{:.1}", t))),
+        ]
+    }
+}
+
+// Relative percentage deviation of the value from the best known value, None if the best known value is 0
+// (e.g. no tardiness), where there is no relative deviation
+pub fn rpd(value: f64, best_known: u32) -> Option<f64> {
+    match best_known {
+        0 => None,
+        b => Some(100.0 * (value - b as f64) / b as f64),
+    }
+}
+
+// The largest value within rpd percent of the best known value
+pub fn target_value(best_known: u32, rpd: f64) -> u32 {
+    (best_known as f64 * (1.0 + rpd / 100.0)).floor() as u32
+}
+
+// Reads best known values by instance name (the file stem of the instance, e.g. n20m2-01) from a JSON
+// object ({"n20m2-01": 123}), a registry of best known solutions (see common::best_known) or a CSV file with
+// rows of instance and value. Rows without a value are skipped, so the CSV file may have a header
+pub fn read_best_known<P: AsRef<Path>>(path: P) -> Result<HashMap<String, u32>, Box<dyn Error>> {
+    let path = path.as_ref();
+
+    let values: Vec<(String, u32)> = match path.extension().and_then(|e| e.to_str()) {
+        Some("json") => {
+            match serde_json::from_str::<HashMap<String, u32>>(&fs::read_to_string(path)?) {
+                Ok(values) => values.into_iter().collect(),
+                Err(_) => best_known::read(path)?
+                    .into_iter()
+                    .map(|(instance, b)| (instance, b.makespan))
+                    .collect(),
+            }
+        }
+        _ => {
+            let mut reader = ReaderBuilder::new()
+                .has_headers(false)
+                .flexible(true)
+                .from_path(path)?;
+            let mut values = Vec::new();
+            for record in reader.records() {
+                let record = record?;
+                if let (Some(instance), Some(Ok(value))) = (
+                    record.get(0),
+                    record.get(1).map(|v| v.trim().parse::<u32>()),
+                ) {
+                    values.push((instance.trim().to_string(), value));
+                }
+            }
+            values
+        }
+    };
+
+    Ok(values
+        .into_iter()
+        .map(|(instance, value)| (instance_name(&instance), value))
+        .collect())
+}
+
+// The file stem of an instance path, so that best known values can be given by path or name
+pub fn instance_name(instance: &str) -> String {
+    Path::new(instance)
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or(instance)
+        .to_string()
+}
+
+// Writes the summaries to summary.csv and summary.json in the folder
+pub fn write_summary(folder: &Path, summaries: &[Summary]) -> Result<(), Box<dyn Error>> {
+    fs::create_dir_all(folder)?;
+
+    let mut wtr = Writer::from_path(folder.join("summary.csv"))?;
+    wtr.write_record(SUMMARY_HEADER)?;
+    for summary in summaries.iter() {
+        wtr.write_record(summary.record())?;
+    }
+    wtr.flush()?;
+
+    fs::write(
+        folder.join("summary.json"),
+        serde_json::to_string_pretty(summaries)?,
+    )?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs, time::Duration};
+
+    use super::{read_best_known, rpd, target_value, Run, Summary, SUMMARY_HEADER};
+
+    #[test]
+    fn summary_of_runs() {
+        let runs: Vec<Run> = [(100, 10), (104, 20), (102, 30)]
+            .iter()
+            .map(|&(value, ms)| Run {
+                value,
+                elapsed: Duration::from_millis(ms),
+            })
+            .collect();
+        let summary = Summary::new("n20m2-01", "ga", &runs, Some(100), Some(95), Some(102));
+
+        assert_eq!(summary.mean, 102.0);
+        assert_eq!((summary.min, summary.max), (100, 104));
+        assert_eq!(summary.stddev, 2.0);
+        assert_eq!(summary.mean_time_ms, 20.0);
+        assert_eq!(summary.mean_rpd, Some(2.0));
+        assert_eq!(summary.min_rpd, Some(0.0));
+        assert!((summary.lower_bound_gap.unwrap() - 5.263).abs() < 0.001);
+        assert_eq!(summary.hits, 2);
+        assert_eq!(summary.time_to_target_ms, Some(20.0));
+        assert_eq!(summary.record().len(), SUMMARY_HEADER.len());
+
+        let summary = Summary::new("n20m2-01", "ga", &runs[1..2], None, None, None);
+        assert_eq!(summary.stddev, 0.0);
+        assert_eq!((summary.mean_rpd, summary.hits), (None, 0));
+        assert_eq!(summary.time_to_target_ms, None);
+
+        // A best known value and lower bound of 0 give no RPD, but runs at 0 still hit the target
+        let runs = [Run {
+            value: 0,
+            elapsed: Duration::from_millis(10),
+        }];
+        let summary = Summary::new("n20m2-01", "ga", &runs, Some(0), Some(0), Some(0));
+        assert_eq!((summary.mean_rpd, summary.min_rpd), (None, None));
+        assert_eq!(summary.lower_bound_gap, None);
+        assert_eq!(summary.hits, 1);
+        assert!(!summary.record().iter().any(|field| field.contains("NaN")));
+
+        assert_eq!(rpd(101.0, 100), Some(1.0));
+        assert_eq!(rpd(5.0, 0), None);
+        assert_eq!(target_value(200, 1.0), 202);
+    }
+
+    #[test]
+    fn best_known_files() {
+        let folder = env::temp_dir().join("tio4905_best_known");
+        fs::create_dir_all(&folder).unwrap();
+
+        let csv = folder.join("best_known.csv");
+        fs::write(
+            &csv,
+            "instance,value\ninstances/ruiz/json/n20m2-01.json,100\nn20m2-02, 200\n",
+        )
+        .unwrap();
+        let best_known = read_best_known(&csv).unwrap();
+        assert_eq!(best_known.len(), 2);
+        assert_eq!(best_known["n20m2-01"], 100);
+        assert_eq!(best_known["n20m2-02"], 200);
+
+        let json = folder.join("best_known.json");
+        fs::write(&json, r#"{"n20m2-01": 100}"#).unwrap();
+        assert_eq!(read_best_known(&json).unwrap()["n20m2-01"], 100);
+    }
+}
@@ -0,0 +1,330 @@
+use std::time::{Duration, Instant};
+
+use cpu_time::ProcessTime;
+use serde_derive::{Deserialize, Serialize};
+
+use super::{bounds, instance::Instance, objective::ObjectiveType, utils};
+
+/*
+Termination criteria shared by the GA and IG. A run stops as soon as one of the criteria that are set is met:
+    - time_limit: wall time in milliseconds
+    - cpu_time_limit: CPU time of the process in milliseconds (all threads, so use it for single runs)
+    - max_evaluations: evaluated schedules, as counted by Makespan::count
+    - max_generations: generations of the GA (iterations of the steady state GA and the IG)
+    - stagnation: generations in a row without improvement of the best objective value
+    - target: the best objective value is at most the target, e.g. a lower bound or best known value
+    - lower_bound: the makespan reaches the lower bound of the instance (see common::bounds), which is optimal
+
+If no time, CPU time, evaluation or generation limit is set, the time limit is given by the instance size
+(see utils::get_duration), as runs could otherwise go on forever. Both the default time limit and the lower
+bound are added to the criteria by for_instance when a run starts.
+
+Runs are reproducible bit for bit for a given seed when they are bounded by evaluations or generations only
+(see is_reproducible), as time limits depend on the load of the machine. This also holds for the parallel runs of
+tune, where each problem file is solved on its own with its own random number generator.
+
+In TOML config files the criteria are set as
+[termination]
+max_evaluations = 100000
+stagnation = 500
+*/
+
+#[derive(clap::Args, Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Termination {
+    /// Time limit of each run in milliseconds [default: given by the instance size]
+    #[clap(short, long)]
+    pub time_limit: Option<u64>,
+
+    /// CPU time limit of each run in milliseconds
+    #[clap(long)]
+    pub cpu_time_limit: Option<u64>,
+
+    /// Maximum number of evaluated schedules
+    #[clap(long)]
+    pub max_evaluations: Option<u32>,
+
+    /// Maximum number of generations (iterations of the steady state GA and IG)
+    #[clap(long)]
+    pub max_generations: Option<usize>,
+
+    /// Stop after this many generations without improvement
+    #[clap(long)]
+    pub stagnation: Option<usize>,
+
+    /// Stop when the objective value is at most the target, e.g. a lower bound
+    #[clap(long)]
+    pub target: Option<u32>,
+
+    /// Stop when the makespan reaches the lower bound of the instance
+    #[clap(long)]
+    pub lower_bound: bool,
+}
+
+// The criterion that terminated a run
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Criterion {
+    Time,
+    CpuTime,
+    Evaluations,
+    Generations,
+    Stagnation,
+    Target,
+}
+
+impl Termination {
+    // Returns the criteria with a time limit given by the instance size if the run is not bounded otherwise
+    pub fn or_default_time(&self, instance: &Instance) -> Termination {
+        let bounded = self.time_limit.is_some()
+            || self.cpu_time_limit.is_some()
+            || self.max_evaluations.is_some()
+            || self.max_generations.is_some();
+
+        match bounded {
+            true => self.clone(),
+            false => Termination {
+                time_limit: Some(utils::get_duration(instance)),
+                ..self.clone()
+            },
+        }
+    }
+
+    // True if the run is bounded by evaluations or generations, and not by time
+    pub fn is_reproducible(&self) -> bool {
+        self.time_limit.is_none()
+            && self.cpu_time_limit.is_none()
+            && (self.max_evaluations.is_some() || self.max_generations.is_some())
+    }
+
+    // Returns the criteria of a run on the instance, with the default time limit and the lower bound as target
+    pub fn for_instance(&self, instance: &Instance, objective: ObjectiveType) -> Termination {
+        let mut termination = self.or_default_time(instance);
+
+        if self.lower_bound && objective == ObjectiveType::Makespan {
+            let lower_bound = bounds::lower_bound(instance);
+            termination.target = Some(
+                termination
+                    .target
+                    .map_or(lower_bound, |t| t.max(lower_bound)),
+            );
+        }
+
+        termination
+    }
+
+    // Overrides the criteria with the ones set in other
+    pub fn set(&mut self, other: &Termination) {
+        self.time_limit = other.time_limit.or(self.time_limit);
+        self.cpu_time_limit = other.cpu_time_limit.or(self.cpu_time_limit);
+        self.max_evaluations = other.max_evaluations.or(self.max_evaluations);
+        self.max_generations = other.max_generations.or(self.max_generations);
+        self.stagnation = other.stagnation.or(self.stagnation);
+        self.target = other.target.or(self.target);
+        self.lower_bound |= other.lower_bound;
+    }
+}
+
+// Progress of a run, checked against the termination criteria
+pub struct Progress {
+    termination: Termination,
+    start_time: Instant,
+    start_cpu_time: ProcessTime,
+    // Time spent before the progress started, e.g. on the initial population of the GA
+    spent_time: Duration,
+    start_evaluations: u32,
+    pub generation: usize,
+    stagnant_generations: usize,
+    best: Option<u32>,
+}
+
+impl Progress {
+    // Starts the progress of a run, where evaluations is Makespan::count at the start of the run
+    pub fn new(termination: &Termination, evaluations: u32, spent_time: Duration) -> Progress {
+        Progress {
+            termination: termination.clone(),
+            start_time: Instant::now(),
+            start_cpu_time: ProcessTime::now(),
+            spent_time,
+            start_evaluations: evaluations,
+            generation: 0,
+            stagnant_generations: 0,
+            best: None,
+        }
+    }
+
+    // Records a completed generation with the best objective value found so far
+    pub fn next_generation(&mut self, best: u32) {
+        match self.best {
+            Some(b) if best >= b => self.stagnant_generations += 1,
+            _ => {
+                self.best = Some(best);
+                self.stagnant_generations = 0;
+            }
+        }
+        self.generation += 1;
+    }
+
+    // Records n completed generations with the best objective value found at the end of them
+    pub fn next_generations(&mut self, n: usize, best: u32) {
+        match self.best {
+            Some(b) if best >= b => self.stagnant_generations += n,
+            _ => {
+                self.best = Some(best);
+                self.stagnant_generations = 0;
+            }
+        }
+        self.generation += n;
+    }
+
+    // Wall time of the run, including the time spent before it started
+    pub fn elapsed(&self) -> Duration {
+        self.spent_time + self.start_time.elapsed()
+    }
+
+    pub fn is_terminated(&self, evaluations: u32) -> bool {
+        self.criterion(evaluations).is_some()
+    }
+
+    // Returns the first criterion that is met, where evaluations is the current Makespan::count
+    pub fn criterion(&self, evaluations: u32) -> Option<Criterion> {
+        let t = &self.termination;
+
+        if t.target
+            .is_some_and(|target| self.best.is_some_and(|b| b <= target))
+        {
+            Some(Criterion::Target)
+        } else if t.max_generations.is_some_and(|g| self.generation >= g) {
+            Some(Criterion::Generations)
+        } else if t.stagnation.is_some_and(|s| self.stagnant_generations >= s) {
+            Some(Criterion::Stagnation)
+        } else if t
+            .max_evaluations
+            .is_some_and(|e| evaluations.saturating_sub(self.start_evaluations) >= e)
+        {
+            Some(Criterion::Evaluations)
+        } else if t
+            .time_limit
+            .is_some_and(|ms| self.elapsed() >= Duration::from_millis(ms))
+        {
+            Some(Criterion::Time)
+        } else if t.cpu_time_limit.is_some_and(|ms| {
+            self.spent_time + self.start_cpu_time.elapsed() >= Duration::from_millis(ms)
+        }) {
+            Some(Criterion::CpuTime)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::{
+        common::{bounds, instance::parse, objective::ObjectiveType},
+        genetic_algorithm::{entities::options::Options, ga},
+    };
+
+    use super::{Criterion, Progress, Termination};
+
+    #[test]
+    fn criteria() {
+        let termination = Termination {
+            max_evaluations: Some(100),
+            stagnation: Some(2),
+            target: Some(400),
+            ..Termination::default()
+        };
+        let mut progress = Progress::new(&termination, 50, Duration::ZERO);
+        assert_eq!(progress.criterion(149), None);
+        assert_eq!(progress.criterion(150), Some(Criterion::Evaluations));
+
+        progress.next_generation(500);
+        progress.next_generation(500);
+        assert_eq!(progress.criterion(50), None);
+        progress.next_generation(501);
+        assert_eq!(progress.criterion(50), Some(Criterion::Stagnation));
+
+        progress.next_generation(400);
+        assert_eq!(progress.criterion(50), Some(Criterion::Target));
+
+        let termination = Termination {
+            time_limit: Some(10),
+            ..Termination::default()
+        };
+        let progress = Progress::new(&termination, 0, Duration::from_millis(10));
+        assert_eq!(progress.criterion(0), Some(Criterion::Time));
+    }
+
+    #[test]
+    fn default_time_and_overrides() {
+        let instance = parse("./instances/ruiz/json/n20m2-01.json").unwrap();
+
+        let termination = Termination {
+            stagnation: Some(10),
+            ..Termination::default()
+        };
+        assert!(termination.or_default_time(&instance).time_limit.is_some());
+
+        let mut termination = Termination {
+            max_generations: Some(10),
+            ..termination
+        };
+        assert_eq!(termination.or_default_time(&instance), termination);
+
+        termination.set(&Termination {
+            max_generations: Some(20),
+            target: Some(600),
+            ..Termination::default()
+        });
+        assert_eq!(termination.max_generations, Some(20));
+        assert_eq!(termination.stagnation, Some(10));
+        assert_eq!(termination.target, Some(600));
+        assert!(termination.is_reproducible());
+
+        // The lower bound is a target for the makespan only
+        termination.lower_bound = true;
+        let lower_bound = bounds::lower_bound(&instance);
+        assert_eq!(
+            termination
+                .for_instance(&instance, ObjectiveType::Makespan)
+                .target,
+            Some(lower_bound.max(600))
+        );
+        assert_eq!(
+            termination
+                .for_instance(&instance, ObjectiveType::TotalFlowTime)
+                .target,
+            Some(600)
+        );
+
+        termination.set(&Termination {
+            cpu_time_limit: Some(100),
+            ..Termination::default()
+        });
+        assert!(!termination.is_reproducible());
+    }
+
+    #[test]
+    fn ga_generations() {
+        let instance = parse("./instances/ruiz/json/n20m2-43.json").unwrap();
+
+        for steady_state in [false, true] {
+            let options = Options {
+                pop_size: 20,
+                steady_state,
+                write_improvement: false,
+                termination: Termination {
+                    max_generations: Some(5),
+                    ..Termination::default()
+                },
+                ..Options::default()
+            };
+            let ga = ga::solve_instance(instance.clone(), options);
+
+            // The last row of the improvement is the final generation
+            assert_eq!(ga.best_makespan.last().unwrap()[0], "5");
+        }
+    }
+}
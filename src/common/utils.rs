@@ -1,15 +1,16 @@
 use std::{
     error::Error,
-    fs,
+    fs, io,
     path::{Path, PathBuf},
 };
 
 use csv::Writer;
 use indicatif::{ProgressBar, ProgressStyle};
+use lexical_sort::natural_lexical_cmp;
 
 use crate::genetic_algorithm::params;
 
-use super::instance::Instance;
+use super::instance::{parse, Instance, InstanceError};
 
 #[allow(dead_code)]
 pub fn get_problem_files(run_all: bool) -> Vec<PathBuf> {
@@ -23,6 +24,47 @@ pub fn get_problem_files(run_all: bool) -> Vec<PathBuf> {
     }
 }
 
+// Returns the problem files given by paths to files, directories (all files in the directory)
+// and glob patterns (e.g. "instances/ruiz/json/n20*.json"), in natural order within each path
+pub fn expand_problem_files(paths: &[String]) -> Result<Vec<PathBuf>, io::Error> {
+    let mut problem_files = Vec::new();
+
+    for path in paths.iter() {
+        let mut files: Vec<PathBuf> = if Path::new(path).is_dir() {
+            fs::read_dir(path)?
+                .map(|entry| entry.map(|e| e.path()))
+                .filter(|entry| entry.as_ref().map_or(true, |p| p.is_file()))
+                .collect::<Result<_, _>>()?
+        } else if path.contains(['*', '?', '[']) {
+            glob::glob(path)
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?
+                .filter(|entry| entry.as_ref().map_or(true, |p| p.is_file()))
+                .collect::<Result<_, _>>()
+                .map_err(io::Error::from)?
+        } else {
+            vec![PathBuf::from(path)]
+        };
+
+        if files.is_empty() {
+            let message = format!("no problem files in {}", path);
+            return Err(io::Error::new(io::ErrorKind::NotFound, message));
+        }
+
+        files.sort_by(|a, b| natural_lexical_cmp(&a.to_string_lossy(), &b.to_string_lossy()));
+        problem_files.append(&mut files);
+    }
+
+    Ok(problem_files)
+}
+
+// Parses all problem files, and returns the files that are not valid instances with the reason why
+pub fn invalid_problem_files(problem_files: &[PathBuf]) -> Vec<(PathBuf, InstanceError)> {
+    problem_files
+        .iter()
+        .filter_map(|path| parse(path).err().map(|e| (path.clone(), e)))
+        .collect()
+}
+
 pub fn get_duration(instance: &Instance) -> u64 {
     let n_jobs = instance.jobs as f64;
     let m_stages = instance.stages as f64;
@@ -159,7 +201,24 @@ pub fn create_progress_bar(len: u64) -> ProgressBar {
     pb
 }
 
-pub fn write_results(folder_name: &str, records: &Vec<Vec<String>>) -> Result<(), Box<dyn Error>> {
+// Writes the records to results.csv in the folder, with the seed of the runs after the first column
+// (the problem file) of each record, and of the header if given
+pub fn write_results(
+    folder_name: &str,
+    header: Option<&[&str]>,
+    seed: u64,
+    records: &[Vec<String>],
+) -> Result<(), Box<dyn Error>> {
+    let records: Vec<(u64, Vec<String>)> = records.iter().map(|r| (seed, r.clone())).collect();
+    write_results_with_seeds(folder_name, header, &records)
+}
+
+// As write_results, for records of runs with different seeds given as (seed, record)
+pub fn write_results_with_seeds(
+    folder_name: &str,
+    header: Option<&[&str]>,
+    records: &[(u64, Vec<String>)],
+) -> Result<(), Box<dyn Error>> {
     match Path::new(folder_name).is_dir() {
         false => fs::create_dir_all(folder_name)?,
         _ => (),
@@ -167,19 +226,25 @@ pub fn write_results(folder_name: &str, records: &Vec<Vec<String>>) -> Result<()
 
     let mut wtr = Writer::from_path(String::from(folder_name) + "/results.csv")?;
 
-    for record in records {
-        wtr.write_record(record)?;
+    if let Some(header) = header {
+        wtr.write_record(with_seed(header, "seed"))?;
+    }
+    for (seed, record) in records {
+        wtr.write_record(with_seed(record, &seed.to_string()))?;
     }
 
     wtr.flush()?;
     Ok(())
 }
 
+// Writes the improvement of a run to folder/filename, with the seed of the run as the first column
 pub fn write_makespan_improvement(
+    folder: &Path,
     filename: PathBuf,
+    seed: u64,
     records: &Vec<Vec<String>>,
 ) -> Result<(), Box<dyn Error>> {
-    let filename = PathBuf::from("./solutions/improvement").join(filename);
+    let filename = folder.join(filename);
 
     let parent_folder = Path::new(&filename).parent().unwrap();
 
@@ -191,6 +256,8 @@ pub fn write_makespan_improvement(
     let mut wtr = Writer::from_path(filename)?;
 
     for record in records {
+        let mut record = record.clone();
+        record.insert(0, seed.to_string());
         wtr.write_record(record)?;
     }
 
@@ -198,11 +265,21 @@ pub fn write_makespan_improvement(
     Ok(())
 }
 
+// Inserts the seed after the first column of the record
+fn with_seed<S: AsRef<str>>(record: &[S], seed: &str) -> Vec<String> {
+    let mut record: Vec<String> = record.iter().map(|r| r.as_ref().to_string()).collect();
+    record.insert(record.len().min(1), seed.to_string());
+    record
+}
+
 #[cfg(test)]
 mod tests {
     use crate::common::instance::parse;
 
-    use super::{get_duration, get_test_problems};
+    use super::{
+        expand_problem_files, get_duration, get_problem_files, get_test_problems,
+        invalid_problem_files, write_results_with_seeds,
+    };
 
     #[test]
     fn iterative_improvement_insertion_test() {
@@ -226,4 +303,53 @@ mod tests {
                 Ok(_) => (),
             })
     }
+
+    #[test]
+    fn all_problem_files_valid() {
+        let invalid = invalid_problem_files(&get_problem_files(true));
+        assert!(invalid.is_empty(), "{:?}", invalid);
+    }
+
+    #[test]
+    fn expand_files_directories_and_globs() {
+        let paths = [
+            "./instances/ruiz/json/n20m2-01.json".to_string(),
+            "./instances/ruiz/json/n80m8-?.json".to_string(),
+            "./instances/ruiz/json/n120m8-[0-9]*.json".to_string(),
+        ];
+        let files = expand_problem_files(&paths).unwrap();
+
+        assert_eq!(files.len(), 1 + 9 + 80);
+        assert!(files[1].ends_with("n80m8-1.json"));
+        assert!(files[10].ends_with("n120m8-01.json"));
+        assert!(files[89].ends_with("n120m8-80.json"));
+
+        let directory = expand_problem_files(&["./instances/ruiz/json".to_string()]).unwrap();
+        assert_eq!(directory.len(), get_problem_files(true).len());
+
+        assert!(expand_problem_files(&["./instances/ruiz/json/n7*.json".to_string()]).is_err());
+    }
+
+    #[test]
+    fn results_with_seeds() {
+        let folder = std::env::temp_dir().join("tio4905_results_with_seeds");
+        let records = vec![
+            (7, vec!["a.json".to_string(), "0".to_string()]),
+            (8, vec!["a.json".to_string(), "1".to_string()]),
+        ];
+        write_results_with_seeds(
+            folder.to_str().unwrap(),
+            Some(&["instance", "replication"]),
+            &records,
+        )
+        .unwrap();
+        let written = std::fs::read_to_string(folder.join("results.csv")).unwrap();
+        std::fs::remove_dir_all(&folder).unwrap();
+
+        // Each run has its own seed after the problem file
+        assert_eq!(
+            written,
+            "instance,seed,replication\na.json,7,0\na.json,8,1\n"
+        );
+    }
 }
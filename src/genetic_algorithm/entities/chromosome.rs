@@ -9,7 +9,8 @@ use crate::common::{instance::Instance, makespan::Makespan};
 #[derive(Debug, Eq, Clone)]
 pub struct Chromosome {
     pub jobs: Vec<u32>,
-    pub makespan: Option<u32>,
+    // Objective value of the decoded schedule (the makespan by default), lower is better
+    pub fitness: Option<u32>,
     pub updated: bool,
 }
 
@@ -20,29 +21,28 @@ impl Chromosome {
 
         Chromosome {
             jobs,
-            makespan: None,
+            fitness: None,
             updated: true,
         }
     }
 
-    pub fn new_with_makespan(jobs: Vec<u32>, m: u32) -> Self {
+    pub fn new_with_fitness(jobs: Vec<u32>, m: u32) -> Self {
         Chromosome {
             jobs: jobs.to_vec(),
-            makespan: Some(m),
+            fitness: Some(m),
             updated: false,
         }
     }
 
-    pub fn makespan(&mut self, mks: &mut Makespan) {
-        let (m, _) = mks.makespan(&self.jobs);
-        self.makespan = Some(m);
+    pub fn evaluate(&mut self, mks: &mut Makespan) {
+        self.fitness = Some(mks.evaluate(&self.jobs));
         self.updated = false;
     }
 }
 
 impl Ord for Chromosome {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.makespan.unwrap().cmp(&other.makespan.unwrap())
+        self.fitness.unwrap().cmp(&other.fitness.unwrap())
     }
 }
 
@@ -54,7 +54,7 @@ impl PartialOrd for Chromosome {
 
 impl PartialEq for Chromosome {
     fn eq(&self, other: &Self) -> bool {
-        self.makespan == other.makespan
+        self.fitness == other.fitness
     }
 }
 
@@ -62,7 +62,7 @@ impl From<Vec<u32>> for Chromosome {
     fn from(jobs: Vec<u32>) -> Self {
         Chromosome {
             jobs: jobs.to_vec(),
-            makespan: None,
+            fitness: None,
             updated: true,
         }
     }
@@ -79,8 +79,8 @@ impl Display for Chromosome {
 
         dash_separated.push_str(&self.jobs[self.jobs.len() - 1].to_string());
 
-        match self.makespan {
-            Some(m) => dash_separated.push_str(&format!(" (makespan: {})", m)[..]),
+        match self.fitness {
+            Some(m) => dash_separated.push_str(&format!(" (fitness: {})", m)[..]),
             _ => (),
         };
 
@@ -0,0 +1,355 @@
+use std::{error::Error, fmt, fs, io, path::Path, path::PathBuf};
+
+use serde_json::{Map, Value};
+
+use super::options::{Args, Options};
+
+/*
+Options are set in the following order, where later ones take precedence:
+1. The defaults in params.rs (Options::default)
+2. The config file given by --config, in TOML (.toml) or JSON
+3. The flags -s, -l, --decoding and --objective
+4. Each --set OPTION=VALUE, in the order they are given
+5. The termination criteria (--time-limit, --max-evaluations, ...) and --seed of the subcommands (see cli.rs)
+
+Options are named as the fields of Options, and enums by their variants, e.g. in TOML:
+pop_size = 100
+xover_type = "BCBX"
+construction = { MDDR = 0.5 }
+decoding = "LeastLoaded"
+
+The island model and the options of the other algorithms of the solve and bench subcommands are tables:
+[islands]
+count = 4
+[sa]
+cooling = "LundyMees"
+[tabu]
+tenure = 10
+[vns]
+shaking = ["Swap", "Reversal"]
+[alns]
+repair = ["Greedy", { Regret = 3 }]
+
+The value of --set is read as JSON, or as a string if it is not valid JSON (--set xover_type=PMX).
+Options out of their range, e.g. pop_size = 0 or mutation_prob = 1.5, are rejected (see Options::validate).
+The effective options are written to config.json in the solution folder, and can be used as a config file.
+*/
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(PathBuf, io::Error),
+    // The config file could not be parsed
+    File(PathBuf, String),
+    // A --set is not on the form OPTION=VALUE
+    Setting(String),
+    // An option is unknown, has a value of the wrong type or a value out of its range
+    Option(serde_json::Error),
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Io(path, e) => write!(f, "could not read {}: {}", path.display(), e),
+            ConfigError::File(path, e) => write!(f, "could not parse {}: {}", path.display(), e),
+            ConfigError::Setting(setting) => {
+                write!(f, "--set {} is not on the form OPTION=VALUE", setting)
+            }
+            ConfigError::Option(e) => write!(f, "invalid option: {}", e),
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+impl Options {
+    // Returns the options given by the defaults, the config file and the flags, see the top of the file
+    pub fn from_args(args: &Args) -> Result<Options, ConfigError> {
+        let mut options = match serde_json::to_value(Options::default()) {
+            Ok(Value::Object(options)) => options,
+            _ => unreachable!("options are serialized as a map"),
+        };
+
+        if let Some(path) = args.config.as_ref() {
+            options.extend(read_config(path)?);
+        }
+
+        let flags = [
+            ("steady_state", args.steady_state),
+            ("local_search", args.local_search),
+        ];
+        for (option, set) in flags {
+            if set {
+                options.insert(option.to_string(), Value::Bool(true));
+            }
+        }
+        if let Some(decoding) = args.decoding {
+            options.insert("decoding".to_string(), to_value(decoding)?);
+        }
+        if let Some(objective) = args.objective {
+            options.insert("objective".to_string(), to_value(objective)?);
+        }
+
+        for setting in args.settings.iter() {
+            let (option, value) = setting
+                .split_once('=')
+                .ok_or_else(|| ConfigError::Setting(setting.clone()))?;
+            let value = serde_json::from_str(value).unwrap_or(Value::String(value.to_string()));
+            options.insert(option.trim().to_string(), value);
+        }
+
+        let options: Options =
+            serde_json::from_value(Value::Object(options)).map_err(ConfigError::Option)?;
+        options
+            .validate()
+            .map_err(|e| ConfigError::Option(serde::de::Error::custom(e)))?;
+
+        Ok(options)
+    }
+
+    // Checks that the options are in the range the GA can run with
+    fn validate(&self) -> Result<(), String> {
+        let at_least = [
+            ("pop_size", self.pop_size, 1),
+            ("k_tournament", self.k_tournament, 1),
+            ("reversal_percent", self.reversal_percent, 1),
+            ("sa.reversal_percent", self.sa.reversal_percent, 1),
+        ];
+        for (option, value, min) in at_least {
+            if value < min {
+                return Err(format!(
+                    "{} must be at least {}, got {}",
+                    option, min, value
+                ));
+            }
+        }
+        if self.elitism > self.pop_size {
+            return Err(format!(
+                "elitism must be at most pop_size ({}), got {}",
+                self.pop_size, self.elitism
+            ));
+        }
+
+        let probabilities = [
+            ("keep_best", self.keep_best as f64),
+            ("xover_prob", self.xover_prob as f64),
+            ("mutation_prob", self.mutation_prob as f64),
+            ("allways_keep", self.allways_keep),
+        ];
+        for (option, value) in probabilities {
+            if !(0.0..=1.0).contains(&value) {
+                return Err(format!("{} must be between 0 and 1, got {}", option, value));
+            }
+        }
+
+        Ok(())
+    }
+
+    // Writes the options to config.json in the solution folder
+    pub fn write_config(&self) -> Result<(), Box<dyn Error>> {
+        fs::create_dir_all(&self.solution_folder)?;
+        let config = serde_json::to_string_pretty(self)?;
+        fs::write(self.solution_folder.join("config.json"), config)?;
+
+        Ok(())
+    }
+}
+
+fn to_value<T: serde::Serialize>(value: T) -> Result<Value, ConfigError> {
+    serde_json::to_value(value).map_err(ConfigError::Option)
+}
+
+// Reads the options in a TOML or JSON config file
+fn read_config(path: &Path) -> Result<Map<String, Value>, ConfigError> {
+    let content = fs::read_to_string(path).map_err(|e| ConfigError::Io(path.to_path_buf(), e))?;
+
+    let config: Result<Map<String, Value>, String> =
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => toml::from_str(&content).map_err(|e| e.to_string()),
+            _ => serde_json::from_str(&content).map_err(|e| e.to_string()),
+        };
+
+    config.map_err(|e| ConfigError::File(path.to_path_buf(), e))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use clap::StructOpt;
+
+    use crate::{
+        common::{
+            construction::{
+                neh::{NehOrder, TieBreak},
+                Construction,
+            },
+            decoding::DecodingType,
+            neighborhood::NTYPE,
+            objective::ObjectiveType,
+        },
+        genetic_algorithm::{
+            entities::options::{Args, Options},
+            operators::crossover::XTYPE,
+            params,
+        },
+        large_neighborhood_search::repair::RepairType,
+        simulated_annealing::options::{Cooling, Options as SaOptions},
+    };
+
+    use super::ConfigError;
+
+    fn from_args(args: &[&str]) -> Result<Options, ConfigError> {
+        let args = Args::parse_from(std::iter::once("tio4905").chain(args.iter().copied()));
+        Options::from_args(&args)
+    }
+
+    #[test]
+    fn defaults_and_settings() {
+        let options = from_args(&[]).unwrap();
+        assert_eq!(options.pop_size, params::POPULATION_SIZE);
+        assert_eq!(options.decoding, params::DECODING);
+
+        let options = options_with_settings();
+        assert_eq!(options.pop_size, 20);
+        assert_eq!(options.keep_best, 0.5);
+        assert!(matches!(options.xover_type, XTYPE::BCBX));
+        assert!(matches!(options.construction, Construction::MDDR(c) if c == 0.25));
+        assert_eq!(options.decoding, DecodingType::LeastLoaded);
+        assert!(options.steady_state);
+
+        let options = from_args(&[
+            "--set",
+            r#"construction={"NEHVariant": ["Johnson", "IdleTime"]}"#,
+        ])
+        .unwrap();
+        assert!(matches!(
+            options.construction,
+            Construction::NEHVariant(NehOrder::Johnson, TieBreak::IdleTime)
+        ));
+
+        let options = from_args(&[
+            "--objective",
+            "weighted",
+            "--set",
+            r#"objective_weights=[["TotalTardiness", 3]]"#,
+        ])
+        .unwrap();
+        assert_eq!(options.objective, ObjectiveType::Weighted);
+        assert_eq!(
+            options.objective_weights,
+            vec![(ObjectiveType::TotalTardiness, 3)]
+        );
+
+        assert!(matches!(
+            from_args(&["--set", "pop_size"]),
+            Err(ConfigError::Setting(_))
+        ));
+        assert!(matches!(
+            from_args(&["--set", "population=20"]),
+            Err(ConfigError::Option(_))
+        ));
+        assert!(matches!(
+            from_args(&["--set", "pop_size=many"]),
+            Err(ConfigError::Option(_))
+        ));
+    }
+
+    #[test]
+    fn options_out_of_range() {
+        for setting in [
+            "pop_size=0",
+            "k_tournament=0",
+            "reversal_percent=0",
+            r#"sa={"reversal_percent": 0}"#,
+            "elitism=1000",
+            "keep_best=1.5",
+            "mutation_prob=-0.1",
+            "allways_keep=2",
+        ] {
+            assert!(
+                matches!(from_args(&["--set", setting]), Err(ConfigError::Option(_))),
+                "{}",
+                setting
+            );
+        }
+
+        // The bounds themselves are in range
+        let options = from_args(&["--set", "reversal_percent=1", "--set", "xover_prob=1"]).unwrap();
+        assert_eq!(options.reversal_percent, 1);
+    }
+
+    fn options_with_settings() -> Options {
+        from_args(&[
+            "-s",
+            "--decoding",
+            "least-loaded",
+            "--set",
+            "pop_size=20",
+            "--set",
+            "keep_best=0.5",
+            "--set",
+            "xover_type=BCBX",
+            "--set",
+            r#"construction={"MDDR": 0.25}"#,
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn config_files() {
+        let folder = std::env::temp_dir().join("tio4905_config_files");
+        fs::create_dir_all(&folder).unwrap();
+
+        let toml = folder.join("config.toml");
+        fs::write(
+            &toml,
+            "pop_size = 30\nmutation_type = \"Reverse\"\nconstruction = { MDDR = 0.5 }\n",
+        )
+        .unwrap();
+        let toml = toml.to_str().unwrap();
+
+        let options = from_args(&["--config", toml]).unwrap();
+        assert_eq!(options.pop_size, 30);
+        assert!(matches!(options.construction, Construction::MDDR(c) if c == 0.5));
+
+        let tables = folder.join("tables.toml");
+        fs::write(
+            &tables,
+            "[sa]\ncooling = \"LundyMees\"\n[tabu]\ntenure = 10\n[vns]\nshaking = [\"Swap\"]\n\
+             [alns]\nrepair = [\"Greedy\", { Regret = 3 }]\n",
+        )
+        .unwrap();
+        let options = from_args(&["--config", tables.to_str().unwrap()]).unwrap();
+        assert_eq!(options.sa.cooling, Cooling::LundyMees);
+        assert_eq!(options.sa.alpha, SaOptions::default().alpha);
+        assert_eq!(options.tabu.tenure, 10);
+        assert_eq!(options.vns.shaking, vec![NTYPE::Swap]);
+        assert_eq!(
+            options.alns.repair,
+            vec![RepairType::Greedy, RepairType::Regret(3)]
+        );
+        // The run options of the tables are those of the GA options
+        assert!(matches!(
+            from_args(&["--set", r#"sa={"seed": 1}"#]),
+            Err(ConfigError::Option(_))
+        ));
+
+        // Flags take precedence over the config file
+        let options = from_args(&["--config", toml, "--set", "pop_size=40"]).unwrap();
+        assert_eq!(options.pop_size, 40);
+
+        // The effective options can be read back as a JSON config file
+        let options = Options {
+            solution_folder: folder.clone(),
+            ..options_with_settings()
+        };
+        options.write_config().unwrap();
+        let json = folder.join("config.json");
+        let read = from_args(&["--config", json.to_str().unwrap()]).unwrap();
+        assert_eq!(read.pop_size, 20);
+        assert_eq!(read.decoding, DecodingType::LeastLoaded);
+        assert_eq!(read.solution_folder, folder);
+
+        fs::remove_dir_all(&folder).unwrap();
+    }
+}
@@ -1,2 +1,3 @@
 pub mod chromosome;
+pub mod config;
 pub mod options;
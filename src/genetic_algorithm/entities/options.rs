@@ -1,36 +1,38 @@
 use clap::Parser;
 use itertools::iproduct;
 use rand::{prelude::StdRng, SeedableRng};
-use serde_derive::Serialize;
+use serde_derive::{Deserialize, Serialize};
 use std::{borrow::Cow, path::PathBuf, time::Instant};
 
 use crate::{
     common::{
         construction::{gch::GCH, neh::NEH, Construction},
-        instance::parse,
+        decoding::DecodingType,
+        instance::{parse, Instance},
         makespan::Makespan,
+        objective::ObjectiveType,
+        termination::Termination,
     },
     genetic_algorithm::{
         ga::GA,
-        operators::{crossover::XTYPE, crowding::DTYPE, mutation::MTYPE, replacement::RTYPE},
+        island::Islands,
+        operators::{
+            crossover::XTYPE, crowding::DTYPE, local_search::LSTYPE, mutation::MTYPE,
+            replacement::RTYPE,
+        },
         params,
     },
+    large_neighborhood_search::options::Options as AlnsOptions,
+    simulated_annealing::options::Options as SaOptions,
+    tabu_search::options::Options as TabuOptions,
+    variable_neighborhood::options::Options as VnsOptions,
 };
 
 use super::chromosome::Chromosome;
 
-/// Genetic algorithm configuration
+/// Genetic algorithm configuration, shared by the subcommands in cli.rs
 #[derive(Parser, Debug)]
-#[clap(author, version, about, long_about = None)]
 pub struct Args {
-    /// Run all problem instances
-    #[clap(short, long)]
-    pub run_all: bool,
-
-    /// Run through all possible parameter values
-    #[clap(short, long)]
-    pub all_params: bool,
-
     /// Steady state generational scheme
     #[clap(short, long)]
     pub steady_state: bool,
@@ -39,36 +41,39 @@ pub struct Args {
     #[clap(short, long)]
     pub local_search: bool,
 
-    /// Run all problem instances
-    #[clap(short, long)]
-    pub mddr: bool,
+    /// Rule for dispatching jobs to machines in each stage [default: fifo]
+    #[clap(long, arg_enum)]
+    pub decoding: Option<DecodingType>,
 
-    /// Run all problem instances
-    #[clap(short, long)]
-    pub neh: bool,
+    /// Objective to minimize [default: makespan]
+    #[clap(long, arg_enum)]
+    pub objective: Option<ObjectiveType>,
 
-    /// Run all problem instances
+    /// Read GA options from a TOML or JSON file, options left out keep their default in params.rs
     #[clap(short, long)]
-    pub iterated_greedy: bool,
+    pub config: Option<PathBuf>,
+
+    /// Set a GA option, e.g. --set pop_size=100 or --set 'construction={"MDDR": 0.5}'
+    #[clap(long = "set", value_name = "OPTION=VALUE", multiple_occurrences = true)]
+    pub settings: Vec<String>,
 }
 
-#[derive(Clone)]
+// All options can be set in a config file and with --set, see config.rs
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
 pub struct Options {
     // Path to input file defining problem
     pub problem_file: Cow<'static, PathBuf>,
 
-    // Runs through all problem files if true
-    pub run_all: bool,
-
-    // Run through all possible parameter values
-    pub all_params: bool,
-
     // Runs in steady state if true
     pub steady_state: bool,
 
     // Local search after mutations
     pub local_search: bool,
 
+    // Local search type to be used
+    pub ls_type: LSTYPE,
+
     // Size of the population
     pub pop_size: usize,
 
@@ -128,16 +133,54 @@ pub struct Options {
 
     // Distance metric in crowding
     pub distance_metric: DTYPE,
+
+    // Rule for dispatching jobs to machines when decoding a permutation
+    pub decoding: DecodingType,
+
+    // Objective to minimize
+    pub objective: ObjectiveType,
+
+    // Objectives and their weights summed by the weighted objective, e.g. [["Makespan", 10], ["TotalFlowTime", 1]]
+    pub objective_weights: Vec<(ObjectiveType, u32)>,
+
+    // Survivor selection by crowding
+    pub perform_crowding: bool,
+
+    // Write the best solution and its improvement over the iterations to file
+    pub write_improvement: bool,
+
+    // Folder of the improvement files, see utils::write_makespan_improvement
+    pub improvement_folder: PathBuf,
+
+    // Folder for results of all problem files and the effective options
+    pub solution_folder: PathBuf,
+
+    // When to stop the GA, see common::termination
+    pub termination: Termination,
+
+    // Seed of the random number generator
+    pub seed: u64,
+
+    // Island model, see island.rs
+    pub islands: Islands,
+
+    // Options of the other algorithms of the solve and bench subcommands, see solve.rs
+    pub sa: SaOptions,
+
+    pub tabu: TabuOptions,
+
+    pub vns: VnsOptions,
+
+    pub alns: AlnsOptions,
 }
 
 impl Default for Options {
     fn default() -> Self {
         Self {
             problem_file: Cow::Owned(PathBuf::from(params::PROBLEM_FILE)),
-            run_all: false,
-            all_params: false,
             steady_state: false,
             local_search: params::LOCAL_SEARCH,
+            ls_type: params::LSTYPE,
             pop_size: params::POPULATION_SIZE,
             iterations: params::ITERATIONS,
             elitism: params::ELITISM,
@@ -158,23 +201,53 @@ impl Default for Options {
             crowding_scale: params::CROWDING_SCALE,
             k_nearest: params::K_NEAREST,
             distance_metric: params::DISTANCE_METRIC,
+            decoding: params::DECODING,
+            objective: params::OBJECTIVE,
+            objective_weights: params::OBJECTIVE_WEIGHTS.to_vec(),
+            perform_crowding: params::PERFORM_CROWDING,
+            write_improvement: params::WRITE_IMPROVEMENT,
+            improvement_folder: PathBuf::from(params::IMPROVEMENT_FOLDER),
+            solution_folder: PathBuf::from(params::SOLUTION_FOLDER),
+            termination: Termination::default(),
+            seed: params::SEED,
+            islands: Islands::default(),
+            sa: SaOptions::default(),
+            tabu: TabuOptions::default(),
+            vns: VnsOptions::default(),
+            alns: AlnsOptions::default(),
         }
     }
 }
 
 impl Options {
     pub fn build(self) -> GA {
-        // Parse specified instance
-        let instance = parse(self.problem_file.as_ref()).unwrap();
+        let instance = self.instance();
+        self.build_for(instance)
+    }
 
+    // Parses the problem file
+    pub fn instance(&self) -> Instance {
+        parse(self.problem_file.as_ref()).unwrap_or_else(|e| {
+            panic!(
+                "Invalid problem file {}: {}",
+                self.problem_file.display(),
+                e
+            )
+        })
+    }
+
+    // Builds the GA for an instance that is already parsed, problem_file is then only used to name output files
+    pub fn build_for(self, instance: Instance) -> GA {
         // Create makespan struct from instance
-        let mut makespan = Makespan::new(&instance);
+        let mut makespan = Makespan::new_incremental(&instance, self.decoding);
+        makespan.objective = self.objective;
+        makespan.objective_weights = self.objective_weights.clone();
 
         // Initiate population and mating pools
         let mut population = Vec::with_capacity(self.pop_size);
         let mating_pool = Vec::with_capacity(self.pop_size);
 
-        let mut rng = StdRng::seed_from_u64(123);
+        let mut rng = StdRng::seed_from_u64(self.seed);
 
         // Calculate initialization duration
         let start_time = Instant::now();
@@ -195,7 +268,15 @@ impl Options {
             Construction::NEH => {
                 let (neh_permutation, mks) = NEH::neh(&mut makespan);
                 let mut neh_chromosome = Chromosome::from(neh_permutation);
-                neh_chromosome.makespan = Some(mks);
+                neh_chromosome.fitness = Some(mks);
+                neh_chromosome.updated = false;
+                population.push(neh_chromosome);
+            }
+            Construction::NEHVariant(order, tie_break) => {
+                let (neh_permutation, mks) =
+                    NEH::neh_variant(&mut makespan, order, tie_break, &mut rng);
+                let mut neh_chromosome = Chromosome::from(neh_permutation);
+                neh_chromosome.fitness = Some(mks);
                 neh_chromosome.updated = false;
                 population.push(neh_chromosome);
             }
@@ -207,13 +288,15 @@ impl Options {
             population.push(Chromosome::new(&instance, &mut rng));
         }
 
-        let mut makespan = Makespan::new(&instance);
+        let mut makespan = Makespan::new_incremental(&instance, self.decoding);
+        makespan.objective = self.objective;
+        makespan.objective_weights = self.objective_weights.clone();
 
         // Calculate makespan for initial population
         population
             .iter_mut()
             .filter(|c| c.updated)
-            .for_each(|c| c.makespan(&mut makespan));
+            .for_each(|c| c.evaluate(&mut makespan));
 
         let init_duration = start_time.elapsed();
 
@@ -225,7 +308,7 @@ impl Options {
                 .iter()
                 .min()
                 .unwrap()
-                .makespan
+                .fitness
                 .unwrap()
                 .to_string(),
             "0".to_string(),
@@ -391,6 +474,14 @@ impl OptionsGrid {
             distance_metric: opt.11,
             // k_tournament: opt.12,
             problem_file: Cow::Owned(options.problem_file.as_ref().clone()),
+            solution_folder: options.solution_folder.clone(),
+            improvement_folder: options.improvement_folder.clone(),
+            termination: options.termination.clone(),
+            objective_weights: options.objective_weights.clone(),
+            sa: options.sa.clone(),
+            tabu: options.tabu.clone(),
+            vns: options.vns.clone(),
+            alns: options.alns.clone(),
             ..options
         })
         .collect()
@@ -455,6 +546,12 @@ pub struct Params {
 
     // Distance metric in crowding
     pub distance_metric: DTYPE,
+
+    // Rule for dispatching jobs to machines when decoding a permutation
+    pub decoding: DecodingType,
+
+    // Objective to minimize
+    pub objective: ObjectiveType,
 }
 
 impl From<&Options> for Params {
@@ -480,6 +577,9 @@ impl From<&Options> for Params {
                 Construction::Random => Construction::Random,
                 Construction::MDDR(num) => Construction::MDDR(num),
                 Construction::NEH => Construction::NEH,
+                Construction::NEHVariant(order, tie_break) => {
+                    Construction::NEHVariant(order, tie_break)
+                }
             },
             mutation_prob: options.mutation_prob,
             mutation_type: match options.mutation_type {
@@ -505,6 +605,8 @@ impl From<&Options> for Params {
                 DTYPE::ExactMatch => DTYPE::ExactMatch,
                 DTYPE::DeviationDistance => DTYPE::DeviationDistance,
             },
+            decoding: options.decoding,
+            objective: options.objective,
         }
     }
 }
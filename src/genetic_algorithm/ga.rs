@@ -1,18 +1,16 @@
-use crate::common::instance::{Instance, Solution};
+use crate::common::instance::Instance;
 use crate::common::makespan::Makespan;
+use crate::common::termination::Progress;
 use crate::common::utils;
-use crate::genetic_algorithm::entities::options::Args;
 
 use super::entities::chromosome::Chromosome;
 use super::entities::options::{Options, OptionsGrid, Params};
-use super::operators::crossover::{
-    Crossover, CrossoverFn, Qlearning, Random, BCBX, PMX, SB2OX, SJ2OX, XTYPE,
-};
-use super::operators::local_search::ls_ig;
+use super::island;
+use super::operators::crossover::{Crossover, Qlearning, BCBX, PMX, SB2OX, SJ2OX, XTYPE};
+use super::operators::local_search::local_search;
 use super::operators::mutation::{self, Greedy, Mutation, Reverse, Swap, MTYPE, SHIFT};
 use super::operators::replacement::{Replacement, RTYPE};
 use super::operators::{crowding, replacement};
-use super::params;
 
 use csv::Writer;
 use lexical_sort::natural_lexical_cmp;
@@ -22,7 +20,7 @@ use rayon::prelude::*;
 use std::borrow::Cow;
 use std::path::Path;
 use std::sync::{Arc, Mutex};
-use std::time::{Duration, Instant};
+use std::time::Duration;
 use std::{error::Error, fs, path::PathBuf};
 
 pub struct GA {
@@ -43,26 +41,23 @@ impl Default for GA {
 }
 
 impl GA {
-    pub fn run(&mut self) {
-        let crossovers: Vec<CrossoverFn> =
-            vec![SJ2OX::apply, BCBX::apply, SB2OX::apply, PMX::apply];
+    // Returns the number of generations run
+    pub fn run(&mut self) -> usize {
+        let crossovers = vec![XTYPE::SJ2OX, XTYPE::BCBX, XTYPE::SB2OX, XTYPE::PMX];
         let mut q_crossover =
             Qlearning::new(crossovers, self.options.learning_rate, self.options.epsilon);
 
         let mut non_improvement_counter: usize = 0;
-        let mut iteration = 0;
-        let start_time = Instant::now();
-        let duration_millis = utils::get_duration(&self.instance);
-        let duration = Duration::from_millis(duration_millis);
-        let allowed_time = duration.checked_sub(self.init_duration);
-
-        let time_to_spare = match allowed_time {
-            Some(t) => t,
-            None => Duration::from_millis(0),
-        };
+        let termination = self
+            .options
+            .termination
+            .for_instance(&self.instance, self.options.objective);
+        let mut progress = Progress::new(&termination, self.makespan.count, self.init_duration);
 
         // Go through generations
-        while start_time.elapsed() < time_to_spare {
+        while !progress.is_terminated(self.makespan.count) {
+            let iteration = progress.generation;
+
             // Replace the chromosomes with the worst fit if there has been no improvement in the best fit for y iterations
             if self.options.allways_keep < 1.0
                 && non_improvement_counter >= self.options.non_improving_iterations
@@ -92,27 +87,38 @@ impl GA {
                         XTYPE::SB2OX => {
                             SB2OX::apply(&p[0], &p[1], None, &mut self.makespan, &mut self.rng)
                         }
-                        XTYPE::BCBX => {
-                            BCBX::apply(&p[0], &p[1], None, &mut self.makespan, &mut self.rng)
-                        }
+                        XTYPE::BCBX => BCBX::crossover(
+                            &p[0],
+                            &p[1],
+                            None,
+                            self.options.keep_best,
+                            &mut self.makespan,
+                            &mut self.rng,
+                        ),
                         XTYPE::PMX => {
                             PMX::apply(&p[0], &p[1], None, &mut self.makespan, &mut self.rng)
                         }
-                        XTYPE::Random => {
-                            Random::apply(&p[0], &p[1], None, &mut self.makespan, &mut self.rng)
-                        }
+                        XTYPE::Random => XTYPE::Random.apply(
+                            &p[0],
+                            &p[1],
+                            None,
+                            self.options.keep_best,
+                            &mut self.makespan,
+                            &mut self.rng,
+                        ),
                         XTYPE::QLearning => q_crossover.crossover(
                             &p[0],
                             &p[1],
                             None,
+                            self.options.keep_best,
                             &mut self.makespan,
                             &mut self.rng,
                         ),
                     };
 
-                    if params::PERFORM_CROWDING {
-                        c1.makespan(&mut self.makespan);
-                        c2.makespan(&mut self.makespan);
+                    if self.options.perform_crowding {
+                        c1.evaluate(&mut self.makespan);
+                        c2.evaluate(&mut self.makespan);
 
                         let [winner1, winner2] = crowding::survivor_selection(
                             &[c1, c2],
@@ -146,12 +152,17 @@ impl GA {
                 if self.rng.gen::<f32>() < self.options.mutation_prob {
                     match self.options.mutation_type {
                         MTYPE::Shift => SHIFT::apply(c, &mut self.makespan, &mut self.rng),
-                        MTYPE::Reverse => Reverse::apply(c, &mut self.makespan, &mut self.rng),
+                        MTYPE::Reverse => {
+                            Reverse::reverse(c, self.options.reversal_percent, &mut self.rng)
+                        }
                         MTYPE::Swap => Swap::apply(c, &mut self.makespan, &mut self.rng),
                         MTYPE::Greedy => Greedy::apply(c, &mut self.makespan, &mut self.rng),
-                        MTYPE::Random => {
-                            mutation::Random::apply(c, &mut self.makespan, &mut self.rng)
-                        }
+                        MTYPE::Random => MTYPE::Random.apply(
+                            c,
+                            &mut self.makespan,
+                            self.options.reversal_percent,
+                            &mut self.rng,
+                        ),
                     }
                 }
             });
@@ -159,7 +170,8 @@ impl GA {
             // Local search
             if self.options.local_search {
                 self.mating_pool.iter_mut().for_each(|c| {
-                    ls_ig(
+                    local_search(
+                        self.options.ls_type,
                         c,
                         &mut self.makespan,
                         self.options.approx_calc as u32,
@@ -171,8 +183,8 @@ impl GA {
             // Check if any of the new chromosomes are improvements to the current best
             // Sort population for elitism
             self.population.sort();
-            // Calculate makespan for new individuals in mating pool
-            self.makespan();
+            // Calculate fitness for new individuals in mating pool
+            self.evaluate();
 
             // Check if any of the new chromosomes are improvements to the current best
             let best_offspring = self.mating_pool.iter().min().unwrap();
@@ -182,16 +194,18 @@ impl GA {
                 non_improvement_counter = 0;
                 self.best_makespan.push(vec![
                     iteration.to_string(),
-                    best_offspring.makespan.unwrap().to_string(),
+                    best_offspring.fitness.unwrap().to_string(),
                     self.makespan.count.to_string(),
-                    start_time.elapsed().as_millis().to_string(),
+                    (progress.elapsed() - self.init_duration)
+                        .as_millis()
+                        .to_string(),
                 ]);
             }
 
             // Elitism
             for c in self.population.iter().take(self.options.elitism) {
                 let mut elite = Chromosome::from(c.jobs.to_vec());
-                elite.makespan = c.makespan;
+                elite.fitness = c.fitness;
                 elite.updated = false;
                 self.mating_pool.push(elite);
             }
@@ -200,24 +214,27 @@ impl GA {
 
             self.mating_pool.iter().for_each(|c| {
                 let mut new_c = Chromosome::from(c.jobs.to_vec());
-                new_c.makespan = c.makespan;
+                new_c.fitness = c.fitness;
                 new_c.updated = false;
                 self.population.push(new_c);
             });
 
-            iteration += 1;
+            let best = self.population.iter().min().unwrap();
+            progress.next_generation(best.fitness.unwrap());
         }
 
-        self.final_makespan(iteration, duration_millis);
+        self.final_makespan(progress.generation, progress.elapsed().as_millis() as u64);
 
-        if params::WRITE_IMPROVEMENT {
+        if self.options.write_improvement {
             write_improvement(&self.options, &self.best_makespan);
         }
+
+        progress.generation
     }
 
-    pub fn run_steady_state(&mut self) {
-        let crossovers: Vec<CrossoverFn> =
-            vec![SJ2OX::apply, BCBX::apply, SB2OX::apply, PMX::apply];
+    // Returns the number of generations run
+    pub fn run_steady_state(&mut self) -> usize {
+        let crossovers = vec![XTYPE::SJ2OX, XTYPE::BCBX, XTYPE::SB2OX, XTYPE::PMX];
 
         let mut q_crossover =
             Qlearning::new(crossovers, self.options.learning_rate, self.options.epsilon);
@@ -225,35 +242,15 @@ impl GA {
         // Calculate makespan for all individuals in population
         self.population.sort();
         let mut non_improvement_counter: usize = 0;
-        let mut iteration = 0;
-
-        let start_time = Instant::now();
-        let duration_millis = utils::get_duration(&self.instance);
-        let duration = Duration::from_millis(duration_millis);
-        let allowed_time = duration.checked_sub(self.init_duration);
-
-        let time_to_spare = match allowed_time {
-            Some(t) => {
-                // println!(
-                //     "Init took {} ms. Available time {} ms",
-                //     self.init_duration.as_millis(),
-                //     duration.as_millis(),
-                // );
-                t
-            }
-            None => {
-                // println!(
-                //     "Failing for instance `{:?}`. It took {} ms. Available time {} ms",
-                //     self.options.problem_file.as_os_str(),
-                //     self.init_duration.as_millis(),
-                //     duration.as_millis(),
-                // );
-                Duration::from_millis(0)
-            }
-        };
+        let termination = self
+            .options
+            .termination
+            .for_instance(&self.instance, self.options.objective);
+        let mut progress = Progress::new(&termination, self.makespan.count, self.init_duration);
 
         // Go through generations
-        while start_time.elapsed() < time_to_spare {
+        while !progress.is_terminated(self.makespan.count) {
+            let iteration = progress.generation;
             // Replace the chromosomes with the worst fit if there has been no improvement in the best fit for y iterations
             if self.options.allways_keep < 1.0
                 && non_improvement_counter >= self.options.non_improving_iterations
@@ -274,6 +271,7 @@ impl GA {
                     RTYPE::Mutate => replacement::Mutate::replace(
                         &mut self.population,
                         self.options.allways_keep,
+                        self.options.reversal_percent,
                         &mut self.makespan,
                         &mut self.rng,
                     ),
@@ -291,12 +289,31 @@ impl GA {
             let (mut c1, mut c2) = match self.options.xover_type {
                 XTYPE::SJ2OX => SJ2OX::apply(&p1, &p2, None, &mut self.makespan, &mut self.rng),
                 XTYPE::SB2OX => SB2OX::apply(&p1, &p2, None, &mut self.makespan, &mut self.rng),
-                XTYPE::BCBX => BCBX::apply(&p1, &p2, None, &mut self.makespan, &mut self.rng),
+                XTYPE::BCBX => BCBX::crossover(
+                    &p1,
+                    &p2,
+                    None,
+                    self.options.keep_best,
+                    &mut self.makespan,
+                    &mut self.rng,
+                ),
                 XTYPE::PMX => PMX::apply(&p1, &p2, None, &mut self.makespan, &mut self.rng),
-                XTYPE::Random => Random::apply(&p1, &p2, None, &mut self.makespan, &mut self.rng),
-                XTYPE::QLearning => {
-                    q_crossover.crossover(&p1, &p2, None, &mut self.makespan, &mut self.rng)
-                }
+                XTYPE::Random => XTYPE::Random.apply(
+                    &p1,
+                    &p2,
+                    None,
+                    self.options.keep_best,
+                    &mut self.makespan,
+                    &mut self.rng,
+                ),
+                XTYPE::QLearning => q_crossover.crossover(
+                    &p1,
+                    &p2,
+                    None,
+                    self.options.keep_best,
+                    &mut self.makespan,
+                    &mut self.rng,
+                ),
             };
 
             // Mutate
@@ -304,12 +321,17 @@ impl GA {
                 if self.rng.gen::<f32>() < self.options.mutation_prob {
                     match self.options.mutation_type {
                         MTYPE::Shift => SHIFT::apply(c, &mut self.makespan, &mut self.rng),
-                        MTYPE::Reverse => Reverse::apply(c, &mut self.makespan, &mut self.rng),
+                        MTYPE::Reverse => {
+                            Reverse::reverse(c, self.options.reversal_percent, &mut self.rng)
+                        }
                         MTYPE::Swap => mutation::Swap::apply(c, &mut self.makespan, &mut self.rng),
                         MTYPE::Greedy => Greedy::apply(c, &mut self.makespan, &mut self.rng),
-                        MTYPE::Random => {
-                            mutation::Random::apply(c, &mut self.makespan, &mut self.rng)
-                        }
+                        MTYPE::Random => MTYPE::Random.apply(
+                            c,
+                            &mut self.makespan,
+                            self.options.reversal_percent,
+                            &mut self.rng,
+                        ),
                     }
                 }
             };
@@ -318,13 +340,15 @@ impl GA {
 
             // Local search
             if self.options.local_search {
-                ls_ig(
+                local_search(
+                    self.options.ls_type,
                     &mut c1,
                     &mut self.makespan,
                     self.options.approx_calc as u32,
                     &mut self.rng,
                 );
-                ls_ig(
+                local_search(
+                    self.options.ls_type,
                     &mut c2,
                     &mut self.makespan,
                     self.options.approx_calc as u32,
@@ -332,20 +356,18 @@ impl GA {
                 )
             }
 
-            let mut makespan = |c: &mut Chromosome| c.makespan(&mut self.makespan);
-            makespan(&mut c1);
-            makespan(&mut c2);
+            let mut evaluate = |c: &mut Chromosome| c.evaluate(&mut self.makespan);
+            evaluate(&mut c1);
+            evaluate(&mut c2);
 
             // If non of the new chromosomes are better than the current best, the count increases
             if c1 < *self.population.first().unwrap() || c2 < *self.population.first().unwrap() {
                 non_improvement_counter = 0;
                 self.best_makespan.push(vec![
                     iteration.to_string(),
-                    std::cmp::min(&c1, &c2).makespan.unwrap().to_string(),
+                    std::cmp::min(&c1, &c2).fitness.unwrap().to_string(),
                     self.makespan.count.to_string(),
-                    (start_time.elapsed() + self.init_duration)
-                        .as_millis()
-                        .to_string(),
+                    progress.elapsed().as_millis().to_string(),
                 ]);
             } else {
                 non_improvement_counter += 1;
@@ -359,7 +381,7 @@ impl GA {
                 Else
                     Replace c_i with least fit element in whole population
             */
-            if params::PERFORM_CROWDING {
+            if self.options.perform_crowding {
                 let mut replace = |c: Chromosome| {
                     let replace_idx = crowding::k_nearest_replacement(
                         &c,
@@ -406,21 +428,23 @@ impl GA {
                 replace(c2);
             }
 
-            iteration += 1;
+            progress.next_generation(self.population.first().unwrap().fitness.unwrap());
         }
 
-        self.final_makespan(iteration, duration_millis);
+        self.final_makespan(progress.generation, progress.elapsed().as_millis() as u64);
 
-        if params::WRITE_IMPROVEMENT {
+        if self.options.write_improvement {
             write_improvement(&self.options, &self.best_makespan);
         }
+
+        progress.generation
     }
 
-    pub fn makespan(&mut self) {
+    pub fn evaluate(&mut self) {
         self.mating_pool
             .iter_mut()
             .filter(|c| c.updated)
-            .for_each(|c| c.makespan(&mut self.makespan));
+            .for_each(|c| c.evaluate(&mut self.makespan));
     }
 
     fn tournament(&mut self) -> Chromosome {
@@ -439,7 +463,7 @@ impl GA {
         };
         // Create a new chromosome from the tournament winner
         let mut winner_clone = Chromosome::from(winner.jobs.to_vec());
-        winner_clone.makespan = winner.makespan;
+        winner_clone.fitness = winner.fitness;
         winner_clone.updated = false;
         winner_clone
     }
@@ -448,8 +472,8 @@ impl GA {
     //     println!(
     //         "{}: {}-{}",
     //         iteration,
-    //         self.population[0].makespan.unwrap(),
-    //         self.population.iter().last().unwrap().makespan.unwrap()
+    //         self.population[0].fitness.unwrap(),
+    //         self.population.iter().last().unwrap().fitness.unwrap()
     //     );
     // }
 
@@ -460,7 +484,7 @@ impl GA {
                 .iter()
                 .min()
                 .unwrap()
-                .makespan
+                .fitness
                 .unwrap()
                 .to_string(),
             self.makespan.count.to_string(),
@@ -470,19 +494,13 @@ impl GA {
 }
 
 // Run all problems for all parameter combinations
-pub fn run_all(args: &Args) {
-    // Get vector of all problem files (twice as we have to consume them)
-    let problem_files = utils::get_test_problems();
-    let problem_files_consumed = utils::get_test_problems();
-
-    // Make sure problem files are in same order
-    assert_eq!(
-        &problem_files, &problem_files_consumed,
-        "Order of problem files does not equal"
-    );
+pub fn run_all(problem_files: Vec<PathBuf>, options: &Options) {
+    let problem_files_consumed = problem_files.clone();
 
     let num_problems = problem_files.len();
 
+    options.write_config().unwrap();
+
     // Initiate 2D vector of results: results[problem_file][parameter_combination]
     let results: Arc<Mutex<Vec<Vec<String>>>> =
         Arc::new(Mutex::new(Vec::with_capacity(problem_files.len())));
@@ -499,24 +517,17 @@ pub fn run_all(args: &Args) {
         .into_par_iter()
         .enumerate()
         .for_each(|(i, problem_file)| {
-            // Get default options to be used in constructing OptionsGrid
+            // Get options to be used in constructing OptionsGrid
             let options = Options {
                 problem_file: Cow::Owned(problem_file),
-                run_all: args.run_all,
-                steady_state: args.steady_state,
-                all_params: args.all_params,
-                ..Options::default()
+                ..options.clone()
             };
 
             // Get vector of all option combinations possible
             let all_options = OptionsGrid::default().get_options(options);
 
             if i == 0 {
-                write_params_to_file(
-                    String::from(params::SOLUTION_FOLDER) + "/params.csv",
-                    &all_options,
-                )
-                .unwrap();
+                write_params_to_file(&all_options[0].solution_folder, &all_options).unwrap();
             }
 
             // Store filename and result from each parameter combination in vector
@@ -527,7 +538,7 @@ pub fn run_all(args: &Args) {
             ));
 
             all_options.into_iter().for_each(|options| {
-                row.push(run(options, false).to_string());
+                row.push(run(options).to_string());
             });
 
             // Increase progress bar
@@ -543,70 +554,49 @@ pub fn run_all(args: &Args) {
         .unwrap()
         .sort_by(|a, b| natural_lexical_cmp(&a[0], &b[0]));
 
-    utils::write_results(params::SOLUTION_FOLDER, &results.lock().unwrap()).unwrap();
-    println!(
-        "All problems run, results are stored in `{}`",
-        params::SOLUTION_FOLDER
-    );
+    let folder = options.solution_folder.to_str().unwrap();
+    utils::write_results(folder, None, options.seed, &results.lock().unwrap()).unwrap();
+    println!("All problems run, results are stored in `{}`", folder);
 }
 
-pub fn run_one(args: &Args) {
-    let options = Options {
-        problem_file: Cow::Owned(PathBuf::from(params::PROBLEM_FILE)),
-        run_all: args.run_all,
-        steady_state: args.steady_state,
-        all_params: args.all_params,
-        ..Options::default()
-    };
-
-    let makespan = run(options, true);
-    println!("GA completed with best makespan: {makespan}");
+// Builds and runs the GA, and returns it with its final population
+pub fn solve(options: Options) -> GA {
+    solve_instance(options.instance(), options)
 }
 
-pub fn run(options: Options, run_one: bool) -> u32 {
-    let mut ga = options.build();
+// Builds and runs the GA on an instance that is already parsed, on islands if there are more than one
+pub fn solve_instance(instance: Instance, options: Options) -> GA {
+    match options.islands.count > 1 {
+        true => island::evolve(instance, options),
+        false => evolve(options.build_for(instance)),
+    }
+}
 
+fn evolve(mut ga: GA) -> GA {
     if ga.options.steady_state {
         ga.run_steady_state();
     } else {
         ga.run();
     }
 
-    // Find the best solution
-    let winner = ga.population.into_iter().min().unwrap();
-    let (best_makespan, machine_completions) = ga.makespan.makespan(&winner.jobs);
-
-    // We store the best solution if we only run one problem
-    if run_one && params::WRITE_IMPROVEMENT {
-        let solution: Solution = Solution::new(machine_completions, best_makespan, &ga.instance);
+    ga
+}
 
-        let problem = ga
-            .options
-            .problem_file
-            .as_ref()
-            .to_str()
-            .unwrap()
-            .split("/")
-            .last()
-            .unwrap();
-
-        let path = String::from("solutions/ga/") + problem;
-        solution.write(path);
-    }
+pub fn run(options: Options) -> u32 {
+    let ga = solve(options);
 
-    best_makespan
+    // Find the best solution
+    let winner = ga.population.into_iter().min().unwrap();
+    winner.fitness.unwrap()
 }
 
-fn write_params_to_file(
-    filename: String,
-    all_options: &Vec<Options>,
-) -> Result<(), Box<dyn Error>> {
-    match Path::new(params::SOLUTION_FOLDER).is_dir() {
-        false => fs::create_dir_all(params::SOLUTION_FOLDER)?,
+fn write_params_to_file(folder: &Path, all_options: &Vec<Options>) -> Result<(), Box<dyn Error>> {
+    match folder.is_dir() {
+        false => fs::create_dir_all(folder)?,
         _ => (),
     }
 
-    let mut wtr = Writer::from_path(filename).unwrap();
+    let mut wtr = Writer::from_path(folder.join("params.csv")).unwrap();
 
     all_options
         .iter()
@@ -617,7 +607,7 @@ fn write_params_to_file(
     Ok(())
 }
 
-fn write_improvement(options: &Options, best_makespan: &Vec<Vec<String>>) {
+pub fn write_improvement(options: &Options, best_makespan: &Vec<Vec<String>>) {
     let mut filename = PathBuf::from(
         PathBuf::from(options.problem_file.as_os_str())
             .file_name()
@@ -628,5 +618,11 @@ fn write_improvement(options: &Options, best_makespan: &Vec<Vec<String>>) {
 
     filename = PathBuf::from("ga/init_gch2").join(filename);
 
-    utils::write_makespan_improvement(filename, best_makespan).unwrap();
+    utils::write_makespan_improvement(
+        &options.improvement_folder,
+        filename,
+        options.seed,
+        best_makespan,
+    )
+    .unwrap();
 }
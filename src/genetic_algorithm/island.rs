@@ -0,0 +1,432 @@
+use std::time::{Duration, Instant};
+
+use rand::{prelude::StdRng, seq::SliceRandom, Rng, SeedableRng};
+use rayon::prelude::*;
+use serde_derive::{Deserialize, Serialize};
+
+use crate::common::{
+    instance::Instance,
+    termination::{Progress, Termination},
+};
+
+use super::{
+    entities::{chromosome::Chromosome, options::Options},
+    ga::{self, GA},
+    params,
+};
+
+/*
+Island model of the GA. Each island is a GA with its own population and random number generator (seeded with
+seed + island), and the islands are run in parallel on the rayon thread pool. Every migration_interval
+generations the islands stop, and each island sends copies of migrants individuals to its neighbours:
+    Ring: island i sends to island i + 1 (and the last island to the first)
+    FullyConnected: every island sends to all other islands
+The emigrants are the best or random individuals of the island, and the immigrants replace the worst or random
+individuals (never the best) of the receiving island. All emigrants are chosen before any immigrants arrive.
+
+The islands share the termination criteria of the options: the generations are counted for the model as a
+whole (so max_generations = 100 gives each island 100 generations), and the evaluations are summed over the
+islands. Time and evaluation limits are split among the islands between migrations, while the CPU time limit
+(which counts all threads) and stagnation are checked at the migrations. Each island restarts its generation
+loop after a migration, which resets the Q-learning of the crossover and the count of non improving iterations.
+
+In TOML config files the island model is set as
+[islands]
+count = 4
+topology = "Ring"
+migration_interval = 20
+migrants = 2
+emigration = "Best"
+immigration = "Worst"
+*/
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum Topology {
+    Ring,
+    FullyConnected,
+}
+
+// Which individuals of an island are sent to its neighbours
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum Emigration {
+    Best,
+    Random,
+}
+
+// Which individuals of an island are replaced by immigrants
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum Immigration {
+    Worst,
+    Random,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Islands {
+    // Number of populations, the island model is only used with more than one
+    pub count: usize,
+
+    pub topology: Topology,
+
+    // Generations between migrations
+    pub migration_interval: usize,
+
+    // Individuals sent from an island to each of its neighbours in a migration
+    pub migrants: usize,
+
+    pub emigration: Emigration,
+
+    pub immigration: Immigration,
+}
+
+impl Default for Islands {
+    fn default() -> Self {
+        Self {
+            count: params::ISLANDS,
+            topology: params::TOPOLOGY,
+            migration_interval: params::MIGRATION_INTERVAL,
+            migrants: params::MIGRANTS,
+            emigration: params::EMIGRATION,
+            immigration: params::IMMIGRATION,
+        }
+    }
+}
+
+impl Topology {
+    // Islands receiving the migrants of the island
+    pub fn neighbours(&self, island: usize, islands: usize) -> Vec<usize> {
+        match self {
+            Topology::Ring if islands > 1 => vec![(island + 1) % islands],
+            Topology::Ring => Vec::new(),
+            Topology::FullyConnected => (0..islands).filter(|&i| i != island).collect(),
+        }
+    }
+}
+
+impl Emigration {
+    fn select(
+        &self,
+        population: &mut [Chromosome],
+        migrants: usize,
+        rng: &mut StdRng,
+    ) -> Vec<Chromosome> {
+        match self {
+            Emigration::Best => {
+                population.sort();
+                population.iter().take(migrants).cloned().collect()
+            }
+            Emigration::Random => population.choose_multiple(rng, migrants).cloned().collect(),
+        }
+    }
+}
+
+impl Immigration {
+    fn replace(&self, population: &mut [Chromosome], immigrants: &[Chromosome], rng: &mut StdRng) {
+        population.sort();
+        // The best individual is never replaced, so a population of one receives no immigrants
+        let replaceable = population.len() - 1;
+        if replaceable == 0 {
+            return;
+        }
+
+        match self {
+            Immigration::Worst => {
+                for (i, immigrant) in immigrants.iter().take(replaceable).enumerate() {
+                    population[replaceable - i] = immigrant.clone();
+                }
+            }
+            Immigration::Random => {
+                for immigrant in immigrants.iter() {
+                    let i = rng.gen_range(1..=replaceable);
+                    population[i] = immigrant.clone();
+                }
+            }
+        }
+    }
+}
+
+// Runs the island model on the instance, and returns the island with the best individual. Its makespan
+// count is the evaluations of all islands, and its improvement is the improvement of the best individual
+pub fn evolve(instance: Instance, options: Options) -> GA {
+    let start_time = Instant::now();
+    let config = options.islands;
+    let termination = options
+        .termination
+        .for_instance(&instance, options.objective);
+
+    let mut islands: Vec<GA> = (0..config.count)
+        .into_par_iter()
+        .map(|island| {
+            Options {
+                seed: options.seed + island as u64,
+                write_improvement: false,
+                ..options.clone()
+            }
+            .build_for(instance.clone())
+        })
+        .collect();
+    // The initialization is counted once for the model, not by the islands
+    islands
+        .iter_mut()
+        .for_each(|ga| ga.init_duration = Duration::ZERO);
+
+    let mut rng = StdRng::seed_from_u64(options.seed);
+    let mut progress = Progress::new(&termination, evaluations(&islands), start_time.elapsed());
+    let start_evaluations = evaluations(&islands);
+    let mut best = best_fitness(&islands);
+    let mut improvement = vec![improvement_row(0, best, &islands, Duration::ZERO)];
+
+    while !progress.is_terminated(evaluations(&islands)) {
+        let epoch = epoch_termination(
+            &termination,
+            &progress,
+            evaluations(&islands) - start_evaluations,
+            &config,
+        );
+
+        // Generations of the epoch, less than the interval if the islands stopped early
+        let generations = islands
+            .par_iter_mut()
+            .map(|ga| {
+                ga.options.termination = epoch.clone();
+                if ga.options.steady_state {
+                    ga.run_steady_state()
+                } else {
+                    ga.run()
+                }
+            })
+            .max()
+            .unwrap();
+
+        let epoch_best = best_fitness(&islands);
+        progress.next_generations(generations, epoch_best);
+        if epoch_best < best {
+            best = epoch_best;
+            improvement.push(improvement_row(
+                progress.generation,
+                best,
+                &islands,
+                progress.elapsed(),
+            ));
+        }
+
+        migrate(&mut islands, &config, &mut rng);
+    }
+
+    let total_evaluations = evaluations(&islands);
+    improvement.push(improvement_row(
+        progress.generation,
+        best,
+        &islands,
+        progress.elapsed(),
+    ));
+
+    let (index, _) = islands
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, ga)| ga.population.iter().min().unwrap().fitness)
+        .unwrap();
+    let mut ga = islands.swap_remove(index);
+    ga.makespan.count = total_evaluations;
+    ga.best_makespan = improvement;
+    ga.options = options;
+
+    if ga.options.write_improvement {
+        ga::write_improvement(&ga.options, &ga.best_makespan);
+    }
+
+    ga
+}
+
+// Sends the emigrants of each island to its neighbours
+fn migrate(islands: &mut [GA], config: &Islands, rng: &mut StdRng) {
+    let emigrants: Vec<Vec<Chromosome>> = islands
+        .iter_mut()
+        .map(|ga| {
+            config
+                .emigration
+                .select(&mut ga.population, config.migrants, rng)
+        })
+        .collect();
+
+    for (island, migrants) in emigrants.iter().enumerate() {
+        for neighbour in config.topology.neighbours(island, islands.len()) {
+            config
+                .immigration
+                .replace(&mut islands[neighbour].population, migrants, rng);
+        }
+    }
+}
+
+// Criteria of the islands until the next migration, splitting what is left of the time and evaluations, and
+// stopping at the generations left
+fn epoch_termination(
+    termination: &Termination,
+    progress: &Progress,
+    evaluations: u32,
+    config: &Islands,
+) -> Termination {
+    Termination {
+        time_limit: termination.time_limit.map(|ms| {
+            ms.saturating_sub(progress.elapsed().as_millis() as u64)
+                .max(1)
+        }),
+        max_evaluations: termination
+            .max_evaluations
+            .map(|e| (e.saturating_sub(evaluations) / config.count as u32).max(1)),
+        max_generations: Some(
+            termination
+                .max_generations
+                .map_or(config.migration_interval, |g| {
+                    config
+                        .migration_interval
+                        .min(g.saturating_sub(progress.generation))
+                })
+                .max(1),
+        ),
+        target: termination.target,
+        ..Termination::default()
+    }
+}
+
+fn evaluations(islands: &[GA]) -> u32 {
+    islands.iter().map(|ga| ga.makespan.count).sum()
+}
+
+fn best_fitness(islands: &[GA]) -> u32 {
+    islands
+        .iter()
+        .flat_map(|ga| ga.population.iter())
+        .min()
+        .unwrap()
+        .fitness
+        .unwrap()
+}
+
+// A row of the improvement, as in GA::best_makespan
+fn improvement_row(generation: usize, best: u32, islands: &[GA], elapsed: Duration) -> Vec<String> {
+    vec![
+        generation.to_string(),
+        best.to_string(),
+        evaluations(islands).to_string(),
+        elapsed.as_millis().to_string(),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        common::{instance::parse, termination::Termination},
+        genetic_algorithm::{entities::options::Options, ga},
+    };
+
+    use rand::{prelude::StdRng, SeedableRng};
+
+    use super::{Immigration, Islands, Topology};
+
+    #[test]
+    fn topologies() {
+        assert_eq!(Topology::Ring.neighbours(2, 3), vec![0]);
+        assert_eq!(Topology::Ring.neighbours(0, 1), Vec::<usize>::new());
+        assert_eq!(Topology::FullyConnected.neighbours(1, 3), vec![0, 2]);
+    }
+
+    #[test]
+    fn islands_share_the_budget() {
+        let instance = parse("./instances/ruiz/json/n20m2-43.json").unwrap();
+
+        for topology in [Topology::Ring, Topology::FullyConnected] {
+            let options = Options {
+                pop_size: 20,
+                write_improvement: false,
+                termination: Termination {
+                    max_generations: Some(20),
+                    ..Termination::default()
+                },
+                islands: Islands {
+                    count: 3,
+                    topology,
+                    migration_interval: 5,
+                    ..Islands::default()
+                },
+                ..Options::default()
+            };
+
+            let first = ga::solve_instance(instance.clone(), options.clone());
+            let second = ga::solve_instance(instance.clone(), options);
+            // The best individual is never lost in a migration
+            let best = |ga: &ga::GA| ga.population.iter().min().unwrap().fitness.unwrap();
+            let values: Vec<u32> = first
+                .best_makespan
+                .iter()
+                .map(|r| r[1].parse().unwrap())
+                .collect();
+            assert!(values.windows(2).all(|w| w[0] >= w[1]));
+            assert_eq!(best(&first), *values.last().unwrap());
+
+            assert_eq!(first.best_makespan.last().unwrap()[0], "20");
+            assert_eq!(best(&first), best(&second), "{:?}", topology);
+            assert_eq!(first.makespan.count, second.makespan.count);
+        }
+    }
+
+    #[test]
+    fn epochs_stop_at_the_limits() {
+        let instance = parse("./instances/ruiz/json/n20m2-43.json").unwrap();
+        let options = |termination| Options {
+            pop_size: 20,
+            write_improvement: false,
+            termination,
+            islands: Islands {
+                count: 3,
+                migration_interval: 5,
+                ..Islands::default()
+            },
+            ..Options::default()
+        };
+        let generations =
+            |ga: &ga::GA| -> usize { ga.best_makespan.last().unwrap()[0].parse().unwrap() };
+
+        // The last epoch is shorter than the migration interval
+        let ga = ga::solve_instance(
+            instance.clone(),
+            options(Termination {
+                max_generations: Some(7),
+                ..Termination::default()
+            }),
+        );
+        assert_eq!(generations(&ga), 7);
+
+        // Only the generations run before the evaluations are spent are counted
+        let ga = ga::solve_instance(
+            instance,
+            options(Termination {
+                max_evaluations: Some(30),
+                ..Termination::default()
+            }),
+        );
+        assert!(generations(&ga) < 5);
+    }
+
+    #[test]
+    fn single_individual_receives_no_immigrants() {
+        let instance = parse("./instances/ruiz/json/n20m2-43.json").unwrap();
+        let mut population = Options {
+            pop_size: 1,
+            ..Options::default()
+        }
+        .build_for(instance.clone())
+        .population;
+        let immigrants = Options::default().build_for(instance).population;
+        let before = population.clone();
+
+        let mut rng = StdRng::seed_from_u64(0);
+        for immigration in [Immigration::Worst, Immigration::Random] {
+            immigration.replace(&mut population, &immigrants, &mut rng);
+            assert_eq!(population, before);
+        }
+    }
+}
@@ -1,5 +1,6 @@
 pub mod entities;
 pub mod ga;
+pub mod island;
 pub mod operators;
 pub mod params;
 pub mod tests;
@@ -6,10 +6,10 @@ use crate::common::best_insertion::find_best_insertion;
 
 use rand::prelude::{SliceRandom, StdRng};
 use rand::Rng;
-use serde_derive::Serialize;
+use serde_derive::{Deserialize, Serialize};
 
 #[allow(dead_code)]
-#[derive(Clone, Serialize)]
+#[derive(Clone, Deserialize, Serialize)]
 pub enum XTYPE {
     SJ2OX,
     SB2OX,
@@ -33,7 +33,33 @@ pub struct SJ2OX;
 pub struct SB2OX;
 pub struct BCBX;
 pub struct PMX;
-pub struct Random;
+
+impl XTYPE {
+    // Applies the crossover of the type, where keep_best is used by BCBX. QLearning chooses among the other
+    // types by the state of a Qlearning, see Qlearning::crossover
+    pub fn apply(
+        &self,
+        p1: &Chromosome,
+        p2: &Chromosome,
+        k: Option<usize>,
+        keep_best: f32,
+        makespan: &mut Makespan,
+        rng: &mut StdRng,
+    ) -> (Chromosome, Chromosome) {
+        match self {
+            XTYPE::SJ2OX => SJ2OX::apply(p1, p2, k, makespan, rng),
+            XTYPE::SB2OX => SB2OX::apply(p1, p2, k, makespan, rng),
+            XTYPE::BCBX => BCBX::crossover(p1, p2, k, keep_best, makespan, rng),
+            XTYPE::PMX => PMX::apply(p1, p2, k, makespan, rng),
+            XTYPE::Random => {
+                let xovers = [XTYPE::BCBX, XTYPE::PMX, XTYPE::SB2OX, XTYPE::SJ2OX];
+                let xtype = xovers.choose(rng).unwrap();
+                xtype.apply(p1, p2, k, keep_best, makespan, rng)
+            }
+            XTYPE::QLearning => unreachable!("QLearning crossovers are applied by Qlearning"),
+        }
+    }
+}
 
 impl Crossover for SJ2OX {
     fn apply(
@@ -69,8 +95,8 @@ impl Crossover for SJ2OX {
         let mut c1 = Chromosome::from(c1);
         let mut c2 = Chromosome::from(c2);
 
-        c1.makespan(makespan);
-        c2.makespan(makespan);
+        c1.evaluate(makespan);
+        c2.evaluate(makespan);
 
         (c1, c2)
     }
@@ -109,18 +135,21 @@ impl Crossover for SB2OX {
         let mut c1 = Chromosome::from(c1);
         let mut c2 = Chromosome::from(c2);
 
-        c1.makespan(makespan);
-        c2.makespan(makespan);
+        c1.evaluate(makespan);
+        c2.evaluate(makespan);
 
         (c1, c2)
     }
 }
 
-impl Crossover for BCBX {
-    fn apply(
+impl BCBX {
+    // Block crossover where each block is inserted in its best position with probability keep_best,
+    // and in a random position otherwise
+    pub fn crossover(
         p1: &Chromosome,
         p2: &Chromosome,
         k: Option<usize>,
+        keep_best: f32,
         makespan: &mut Makespan,
         rng: &mut StdRng,
     ) -> (Chromosome, Chromosome) {
@@ -147,13 +176,13 @@ impl Crossover for BCBX {
         let c2 = filter(p2.jobs.to_vec(), block1);
 
         // Test each possible insertion, record best index
-        let (c1, m1) = find_best_insertion(c1, block2, makespan, true, rng);
-        let (c2, m2) = find_best_insertion(c2, block1, makespan, true, rng);
+        let (c1, m1) = find_best_insertion(c1, block2, makespan, Some(keep_best), rng);
+        let (c2, m2) = find_best_insertion(c2, block1, makespan, Some(keep_best), rng);
 
         // Return new chromosomes
         (
-            Chromosome::new_with_makespan(c1, m1),
-            Chromosome::new_with_makespan(c2, m2),
+            Chromosome::new_with_fitness(c1, m1),
+            Chromosome::new_with_fitness(c2, m2),
         )
     }
 }
@@ -174,34 +203,13 @@ impl Crossover for PMX {
         let mut c1 = Chromosome::from(c1);
         let mut c2 = Chromosome::from(c2);
 
-        c1.makespan(makespan);
-        c2.makespan(makespan);
+        c1.evaluate(makespan);
+        c2.evaluate(makespan);
 
         (c1, c2)
     }
 }
 
-impl Crossover for Random {
-    fn apply(
-        p1: &Chromosome,
-        p2: &Chromosome,
-        k: Option<usize>,
-        makespan: &mut Makespan,
-        rng: &mut StdRng,
-    ) -> (Chromosome, Chromosome) {
-        let xovers = vec![XTYPE::BCBX, XTYPE::PMX, XTYPE::SB2OX, XTYPE::SJ2OX];
-        let xtype = xovers.choose(rng).unwrap();
-
-        match xtype {
-            XTYPE::BCBX => BCBX::apply(p1, p2, k, makespan, rng),
-            XTYPE::PMX => PMX::apply(p1, p2, k, makespan, rng),
-            XTYPE::SB2OX => SB2OX::apply(p1, p2, k, makespan, rng),
-            XTYPE::SJ2OX => SJ2OX::apply(p1, p2, k, makespan, rng),
-            _ => PMX::apply(p1, p2, k, makespan, rng),
-        }
-    }
-}
-
 fn pmx(p1: &[u32], p2: &[u32], rng: &mut StdRng) -> Vec<u32> {
     let n_jobs = p1.len();
 
@@ -289,17 +297,8 @@ fn insert_remaining(
 }
 
 // Q-learning using the previously intoduced crossovers
-pub type CrossoverFn = fn(
-    &Chromosome,
-    &Chromosome,
-    Option<usize>,
-    &mut Makespan,
-    &mut StdRng,
-) -> (Chromosome, Chromosome);
-
 pub struct Qlearning {
-    actions: Vec<CrossoverFn>,
-    // pub actions: Vec<Box<dyn Crossover>>,
+    actions: Vec<XTYPE>,
     q_values: Vec<f64>,
     learning_rate: f64,
     epsilon: f64,
@@ -307,7 +306,7 @@ pub struct Qlearning {
 }
 
 impl Qlearning {
-    pub fn new(actions: Vec<CrossoverFn>, learning_rate: f64, epsilon: f64) -> Qlearning {
+    pub fn new(actions: Vec<XTYPE>, learning_rate: f64, epsilon: f64) -> Qlearning {
         Qlearning {
             q_values: vec![0.0; actions.len()],
             counts: vec![0; actions.len()],
@@ -322,6 +321,7 @@ impl Qlearning {
         p1: &Chromosome,
         p2: &Chromosome,
         k: Option<usize>,
+        keep_best: f32,
         makespan: &mut Makespan,
         rng: &mut StdRng,
     ) -> (Chromosome, Chromosome) {
@@ -329,7 +329,7 @@ impl Qlearning {
         if rng.gen_ratio((self.epsilon * 1000.0) as u32, 1000) {
             let crossover: usize = rng.gen_range(0..self.q_values.len());
             self.counts[crossover] += 1;
-            return self.evaluate(p1, p2, k, makespan, rng, crossover);
+            return self.evaluate(p1, p2, k, keep_best, makespan, rng, crossover);
         }
         // Else the crossover with the hightest q-value is used
         else {
@@ -343,26 +343,28 @@ impl Qlearning {
                 .unwrap();
 
             self.counts[crossover] += 1;
-            return self.evaluate(p1, p2, k, makespan, rng, crossover);
+            return self.evaluate(p1, p2, k, keep_best, makespan, rng, crossover);
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn evaluate(
         &mut self,
         p1: &Chromosome,
         p2: &Chromosome,
         k: Option<usize>,
+        keep_best: f32,
         makespan: &mut Makespan,
         rng: &mut StdRng,
         crossover: usize,
     ) -> (Chromosome, Chromosome) {
         // Generate children
-        let (c1, c2) = self.actions[crossover](p1, p2, k, makespan, rng);
+        let (c1, c2) = self.actions[crossover].apply(p1, p2, k, keep_best, makespan, rng);
 
         // Calculate the improvement
         let reward: i32 = max(
-            (min(p1.makespan.unwrap(), p2.makespan.unwrap()) as i32)
-                - (min(c1.makespan.unwrap(), c2.makespan.unwrap()) as i32),
+            (min(p1.fitness.unwrap(), p2.fitness.unwrap()) as i32)
+                - (min(c1.fitness.unwrap(), c2.fitness.unwrap()) as i32),
             0,
         );
 
@@ -382,16 +384,18 @@ mod xover_test {
     use rand::SeedableRng;
 
     use crate::common::best_insertion::find_best_insertion;
+    use crate::common::decoding::DecodingType;
     use crate::common::instance::Instance;
     use crate::common::makespan::Makespan;
     use crate::genetic_algorithm::operators::crossover::{self, Chromosome, Crossover};
+    use crate::genetic_algorithm::params;
 
     #[test]
     fn test_block_insertion() {
         // jobs, block, instance
 
         let instance = test_instance();
-        let mut makespan = Makespan::new(&instance);
+        let mut makespan = Makespan::new(&instance, DecodingType::Fifo);
         let jobs = test_chromosome(&instance);
         let block = &[2, 3];
         let mut rng = StdRng::seed_from_u64(123);
@@ -402,15 +406,22 @@ mod xover_test {
         };
 
         let jobs = filter(jobs.jobs.to_vec(), block);
-        let (jobs, _) = find_best_insertion(jobs, block, &mut makespan, true, &mut rng);
+        let (jobs, _) = find_best_insertion(
+            jobs,
+            block,
+            &mut makespan,
+            Some(params::KEEP_BEST),
+            &mut rng,
+        );
 
-        // Possible permutations
-        // [2, 3, 0, 1, 4]
-        // [0, 2, 3, 1, 4]
-        // [0, 1, 2, 3, 4]
-        // [0, 1, 4, 2, 3]
+        // Possible permutations and their makespans
+        // [2, 3, 0, 1, 4] 357
+        // [0, 2, 3, 1, 4] 357
+        // [0, 1, 2, 3, 4] 335
+        // [0, 1, 4, 2, 3] 335
+        // Job 2 skips stage 0, so it is ready at its release date and not when the job before it completes
 
-        assert_eq!(jobs, &[2, 3, 0, 1, 4]);
+        assert_eq!(jobs, &[0, 1, 2, 3, 4]);
     }
 
     #[test]
@@ -431,7 +442,7 @@ mod xover_test {
             &p1,
             &p2,
             Some(8),
-            &mut Makespan::new(&test_instance()),
+            &mut Makespan::new(&test_instance(), DecodingType::Fifo),
             &mut rng,
         );
 
@@ -456,7 +467,7 @@ mod xover_test {
             &p1,
             &p2,
             None,
-            &mut Makespan::new(&test_instance()),
+            &mut Makespan::new(&test_instance(), DecodingType::Fifo),
             &mut rng,
         );
 
@@ -475,7 +486,7 @@ mod xover_test {
             &p1,
             &p2,
             None,
-            &mut Makespan::new(&test_instance()),
+            &mut Makespan::new(&test_instance(), DecodingType::Fifo),
             &mut rng,
         );
 
@@ -516,6 +527,7 @@ mod xover_test {
                     vec![3, 2, 3, 4, 5],
                 ],
             ],
+            ..Default::default()
         }
     }
 
@@ -524,7 +536,7 @@ mod xover_test {
 
         Chromosome {
             jobs,
-            makespan: None,
+            fitness: None,
             updated: true,
         }
     }
@@ -2,12 +2,12 @@ use rand::{
     prelude::{SliceRandom, StdRng},
     Rng,
 };
-use serde_derive::Serialize;
+use serde_derive::{Deserialize, Serialize};
 
 use crate::genetic_algorithm::entities::chromosome::Chromosome;
 
 #[allow(dead_code)]
-#[derive(Clone, Serialize, Copy)]
+#[derive(Clone, Deserialize, Serialize, Copy)]
 pub enum DTYPE {
     ExactMatch,
     DeviationDistance,
@@ -101,10 +101,10 @@ fn find_winner(
 
     // A scale of 0 implies deterministic crowding
     if scale_is_zero {
-        if parent.makespan.unwrap() < child.makespan.unwrap() {
+        if parent.fitness.unwrap() < child.fitness.unwrap() {
             // Parent is fitter
             return parent.clone();
-        } else if child.makespan.unwrap() < parent.makespan.unwrap() {
+        } else if child.fitness.unwrap() < parent.fitness.unwrap() {
             // Child is fitter
             return child.clone();
         } else {
@@ -121,9 +121,9 @@ fn find_winner(
     let logistic_f = |x: f64| 1f64 / (1f64 + e.powf(-x));
     let scale_f = |diff| scale.powf(logistic_f(diff));
 
-    // Find inverse of fitness to account for minimization of makespan
-    let c_fitness = 1f64 / child.makespan.unwrap() as f64;
-    let p_fitness = 1f64 / parent.makespan.unwrap() as f64;
+    // Find inverse of fitness to account for minimization (shifted by one as an objective value can be zero)
+    let c_fitness = 1f64 / (child.fitness.unwrap() as f64 + 1f64);
+    let p_fitness = 1f64 / (parent.fitness.unwrap() as f64 + 1f64);
 
     let prob_child = (scale_f(p_fitness - c_fitness) * c_fitness)
         / (scale_f(p_fitness - c_fitness) * c_fitness + scale_f(c_fitness - p_fitness) * p_fitness);
@@ -167,7 +167,7 @@ pub fn k_nearest_replacement(
     // Find least fit of the k closest individuals
     let least_fit = k_nearest
         .iter()
-        .max_by_key(|(idx, _)| pop.get(*idx).unwrap().makespan.unwrap())
+        .max_by_key(|(idx, _)| pop.get(*idx).unwrap().fitness.unwrap())
         .unwrap()
         .0;
 
@@ -219,11 +219,11 @@ mod test {
         let mut c1 = Chromosome::from(vec![5, 4, 3, 1, 2, 0]);
         let mut c2 = Chromosome::from(vec![1, 0, 2, 3, 4, 5]);
 
-        p1.makespan = Some(10);
-        p2.makespan = Some(20);
+        p1.fitness = Some(10);
+        p2.fitness = Some(20);
 
-        c1.makespan = Some(15);
-        c2.makespan = Some(5);
+        c1.fitness = Some(15);
+        c2.fitness = Some(5);
 
         let mut rng = StdRng::seed_from_u64(123);
 
@@ -239,8 +239,8 @@ mod test {
         let mut p1 = Chromosome::from(vec![5, 4, 3, 2, 1, 0]);
         let mut c2 = Chromosome::from(vec![1, 0, 2, 3, 4, 5]);
 
-        p1.makespan = Some(10);
-        c2.makespan = Some(5);
+        p1.fitness = Some(10);
+        c2.fitness = Some(5);
 
         assert_eq!(res, [p1, c2]);
     }
@@ -253,11 +253,11 @@ mod test {
         let mut c1 = Chromosome::from(vec![1, 0, 3, 2, 4, 5]);
         let mut c2 = Chromosome::from(vec![4, 5, 3, 1, 2, 0]);
 
-        p1.makespan = Some(10);
-        p2.makespan = Some(20);
+        p1.fitness = Some(10);
+        p2.fitness = Some(20);
 
-        c1.makespan = Some(15);
-        c2.makespan = Some(5);
+        c1.fitness = Some(15);
+        c2.fitness = Some(5);
 
         let mut rng = StdRng::seed_from_u64(123);
 
@@ -273,8 +273,8 @@ mod test {
         let mut c1 = Chromosome::from(vec![1, 0, 3, 2, 4, 5]);
         let mut c2 = Chromosome::from(vec![4, 5, 3, 1, 2, 0]);
 
-        c1.makespan = Some(15);
-        c2.makespan = Some(5);
+        c1.fitness = Some(15);
+        c2.fitness = Some(5);
 
         assert_eq!(res, [c2, c1]);
     }
@@ -286,10 +286,10 @@ mod test {
         let mut p3 = Chromosome::from(vec![5, 4, 3, 2, 1, 0]);
         let mut c = Chromosome::from(vec![4, 5, 3, 2, 1, 0]);
 
-        p1.makespan = Some(10);
-        p2.makespan = Some(12);
-        p3.makespan = Some(20);
-        c.makespan = Some(15);
+        p1.fitness = Some(10);
+        p2.fitness = Some(12);
+        p3.fitness = Some(20);
+        c.fitness = Some(15);
 
         let pop = vec![p1, p2, p3];
 
@@ -309,10 +309,10 @@ mod test {
         let mut p3 = Chromosome::from(vec![5, 4, 3, 2, 1, 0]);
         let mut c = Chromosome::from(vec![4, 5, 3, 2, 1, 0]);
 
-        p1.makespan = Some(20);
-        p2.makespan = Some(20);
-        p3.makespan = Some(10);
-        c.makespan = Some(15);
+        p1.fitness = Some(20);
+        p2.fitness = Some(20);
+        p3.fitness = Some(10);
+        c.fitness = Some(15);
 
         let pop = vec![p1, p2, p3];
         let mut rng = StdRng::seed_from_u64(123);
@@ -331,10 +331,10 @@ mod test {
         let mut p3 = Chromosome::from(vec![5, 4, 3, 2, 1, 0]);
         let mut c = Chromosome::from(vec![4, 5, 3, 2, 1, 0]);
 
-        p1.makespan = Some(20);
-        p2.makespan = Some(12);
-        p3.makespan = Some(10);
-        c.makespan = Some(15);
+        p1.fitness = Some(20);
+        p2.fitness = Some(12);
+        p3.fitness = Some(10);
+        c.fitness = Some(15);
 
         let pop = vec![p1, p2, p3];
 
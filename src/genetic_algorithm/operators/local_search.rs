@@ -1,8 +1,40 @@
+use std::time::Duration;
+
 use rand::prelude::StdRng;
+use serde_derive::{Deserialize, Serialize};
 
-use crate::common::makespan::Makespan;
+use crate::common::{
+    makespan::Makespan,
+    run::RunOptions,
+    termination::{Progress, Termination},
+};
 use crate::genetic_algorithm::entities::chromosome::Chromosome;
-use crate::iterated_greedy::iterated_greedy::iterated_greedy;
+use crate::iterated_greedy::{iterated_greedy::iterated_greedy, options::Options};
+use crate::tabu_search::{options::Options as TabuOptions, tabu_search::tabu_search};
+use crate::variable_neighborhood::{options::Options as VnsOptions, variable_neighborhood::vnd};
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum LSTYPE {
+    IG,
+    Tabu,
+    VND,
+}
+
+// Runs the local search of the type on the chromosome
+pub fn local_search(
+    ls_type: LSTYPE,
+    chromosome: &mut Chromosome,
+    makespan: &mut Makespan,
+    approx_calc: u32,
+    rng: &mut StdRng,
+) {
+    match ls_type {
+        LSTYPE::IG => ls_ig(chromosome, makespan, approx_calc, rng),
+        LSTYPE::Tabu => ls_tabu(chromosome, makespan, approx_calc, rng),
+        LSTYPE::VND => ls_vnd(chromosome, makespan, approx_calc, rng),
+    }
+}
 
 // Iterated greedy
 pub fn ls_ig(
@@ -11,11 +43,103 @@ pub fn ls_ig(
     approx_calc: u32,
     rng: &mut StdRng,
 ) {
-    let original_schedule: Option<(Vec<u32>, u32)> = Some((
-        chromosome.jobs.clone(),
-        makespan.makespan(&chromosome.jobs).0,
-    ));
+    let original_schedule: Option<(Vec<u32>, u32)> =
+        Some((chromosome.jobs.clone(), makespan.evaluate(&chromosome.jobs)));
+    // Run approx_calc iterations, without writing the improvement to file
+    let options = Options {
+        termination: Termination {
+            max_generations: Some(approx_calc as usize),
+            ..Termination::default()
+        },
+        write_improvement: false,
+        ..Options::default()
+    };
     let new_schedule: (Vec<u32>, u32) =
-        iterated_greedy(makespan, original_schedule, approx_calc, None, rng);
+        iterated_greedy(makespan, original_schedule, Some(options), rng);
     chromosome.jobs = new_schedule.0
 }
+
+// Tabu search with approx_calc evaluations
+pub fn ls_tabu(
+    chromosome: &mut Chromosome,
+    makespan: &mut Makespan,
+    approx_calc: u32,
+    rng: &mut StdRng,
+) {
+    let original_schedule = Some((chromosome.jobs.clone(), makespan.evaluate(&chromosome.jobs)));
+    let options = TabuOptions {
+        run: RunOptions {
+            termination: Termination {
+                max_evaluations: Some(approx_calc),
+                ..Termination::default()
+            },
+            write_improvement: false,
+            ..RunOptions::default()
+        },
+        ..TabuOptions::default()
+    };
+    let (jobs, value) = tabu_search(makespan, original_schedule, Some(options), rng);
+    chromosome.jobs = jobs;
+    chromosome.fitness = Some(value);
+    chromosome.updated = false;
+}
+
+// Variable neighborhood descent with the neighborhoods of the VNS, stopped after approx_calc evaluations
+pub fn ls_vnd(
+    chromosome: &mut Chromosome,
+    makespan: &mut Makespan,
+    approx_calc: u32,
+    rng: &mut StdRng,
+) {
+    let schedule = (chromosome.jobs.clone(), makespan.evaluate(&chromosome.jobs));
+    let termination = Termination {
+        max_evaluations: Some(approx_calc),
+        ..Termination::default()
+    };
+    let progress = Progress::new(&termination, makespan.count, Duration::ZERO);
+    let options = VnsOptions::default();
+
+    let (jobs, value) = vnd(
+        makespan,
+        schedule,
+        &options.neighborhoods,
+        options.exploration,
+        &progress,
+        rng,
+    );
+    chromosome.jobs = jobs;
+    chromosome.fitness = Some(value);
+    chromosome.updated = false;
+}
+
+#[cfg(test)]
+mod ls_test {
+    use rand::{prelude::StdRng, SeedableRng};
+
+    use crate::{
+        common::{decoding::DecodingType, instance::parse, makespan::Makespan},
+        genetic_algorithm::entities::chromosome::Chromosome,
+    };
+
+    use super::{local_search, LSTYPE};
+
+    #[test]
+    fn local_searches() {
+        let instance = parse("./instances/ruiz/json/n20m2-43.json").unwrap();
+        let mut makespan = Makespan::new_incremental(&instance, DecodingType::Fifo);
+        let mut rng = StdRng::seed_from_u64(123);
+
+        for ls_type in [LSTYPE::Tabu, LSTYPE::VND] {
+            let mut c = Chromosome::new(&instance, &mut rng);
+            c.evaluate(&mut makespan);
+            let before = c.fitness.unwrap();
+
+            local_search(ls_type, &mut c, &mut makespan, 300, &mut rng);
+
+            // The fitness is set by the local search
+            assert!(!c.updated);
+            assert!(c.fitness.unwrap() <= before, "{:?}", ls_type);
+            assert_eq!(makespan.evaluate(&c.jobs), c.fitness.unwrap());
+        }
+    }
+}
@@ -1,16 +1,16 @@
 use crate::{
     common::{best_insertion::find_best_insertion, makespan::Makespan},
-    genetic_algorithm::{entities::chromosome::Chromosome, params},
+    genetic_algorithm::entities::chromosome::Chromosome,
 };
 
 use rand::{
     prelude::{SliceRandom, StdRng},
     Rng,
 };
-use serde_derive::Serialize;
+use serde_derive::{Deserialize, Serialize};
 
 #[allow(dead_code)]
-#[derive(Clone, Serialize)]
+#[derive(Clone, Deserialize, Serialize)]
 pub enum MTYPE {
     Shift,
     Reverse,
@@ -27,7 +27,29 @@ pub struct SHIFT;
 pub struct Reverse;
 pub struct Swap;
 pub struct Greedy;
-pub struct Random;
+
+impl MTYPE {
+    // Applies the mutation of the type, where reversal_percent is used by Reverse
+    pub fn apply(
+        &self,
+        c: &mut Chromosome,
+        m: &mut Makespan,
+        reversal_percent: usize,
+        rng: &mut StdRng,
+    ) {
+        match self {
+            MTYPE::Shift => SHIFT::apply(c, m, rng),
+            MTYPE::Reverse => Reverse::reverse(c, reversal_percent, rng),
+            MTYPE::Swap => Swap::apply(c, m, rng),
+            MTYPE::Greedy => Greedy::apply(c, m, rng),
+            MTYPE::Random => {
+                let mtypes = [MTYPE::Shift, MTYPE::Reverse, MTYPE::Swap, MTYPE::Greedy];
+                let mtype = mtypes.choose(rng).unwrap();
+                mtype.apply(c, m, reversal_percent, rng)
+            }
+        }
+    }
+}
 
 impl Mutation for SHIFT {
     // Move a job from one location to another random location
@@ -44,12 +66,15 @@ impl Mutation for SHIFT {
     }
 }
 
-impl Mutation for Reverse {
-    // Change order of jobs in the chosen range
-    fn apply(c: &mut Chromosome, _m: &mut Makespan, rng: &mut StdRng) {
-        let size = c.jobs.len() / params::REVERSAL_PERCENT;
+impl Reverse {
+    // Change order of jobs in a random range of 1 / reversal_percent of the jobs, but at least two
+    pub fn reverse(c: &mut Chromosome, reversal_percent: usize, rng: &mut StdRng) {
+        if c.jobs.len() < 2 {
+            return;
+        }
+        let size = (c.jobs.len() / reversal_percent).clamp(2, c.jobs.len());
 
-        let start = rng.gen_range(0..c.jobs.len() - size);
+        let start = rng.gen_range(0..=c.jobs.len() - size);
 
         c.jobs[start..start + size].reverse();
         c.updated = true;
@@ -78,25 +103,10 @@ impl Mutation for Greedy {
 
         let job = c.jobs.remove(rand_job);
 
-        let (new_jobs, makespan) = find_best_insertion(c.jobs.to_vec(), &[job], m, false, rng);
+        let (new_jobs, makespan) = find_best_insertion(c.jobs.to_vec(), &[job], m, None, rng);
 
         c.jobs = new_jobs;
-        c.makespan = Some(makespan);
-    }
-}
-
-impl Mutation for Random {
-    fn apply(c: &mut Chromosome, m: &mut Makespan, rng: &mut StdRng) {
-        let mtypes = vec![MTYPE::Shift, MTYPE::Reverse, MTYPE::Swap, MTYPE::Greedy];
-        let mtype = mtypes.choose(rng).unwrap();
-
-        match mtype {
-            MTYPE::Shift => SHIFT::apply(c, m, rng),
-            MTYPE::Reverse => Reverse::apply(c, m, rng),
-            MTYPE::Swap => Swap::apply(c, m, rng),
-            MTYPE::Greedy => Greedy::apply(c, m, rng),
-            _ => SHIFT::apply(c, m, rng),
-        };
+        c.fitness = Some(makespan);
     }
 }
 
@@ -105,10 +115,11 @@ mod test {
     use rand::{prelude::StdRng, SeedableRng};
 
     use crate::{
-        common::makespan::Makespan,
+        common::{decoding::DecodingType, makespan::Makespan},
         genetic_algorithm::{
             entities::chromosome::Chromosome,
             operators::mutation::{Mutation, Reverse, Swap},
+            params,
             tests::tests::test_instance,
         },
     };
@@ -126,11 +137,7 @@ mod test {
         let mut rng = StdRng::seed_from_u64(123);
 
         // Apply reversal mutation
-        Reverse::apply(
-            &mut jobs_mutate,
-            &mut Makespan::new(&test_instance()),
-            &mut rng,
-        );
+        Reverse::reverse(&mut jobs_mutate, params::REVERSAL_PERCENT, &mut rng);
 
         // Count the number of jobs that are now equal
         let count = jobs_mutate
@@ -145,6 +152,26 @@ mod test {
         assert_eq!(count, 18);
     }
 
+    #[test]
+    fn test_reverse_few_jobs() {
+        let mut rng = StdRng::seed_from_u64(123);
+
+        // A single job can not be reversed
+        let mut c = Chromosome::from(vec![0]);
+        Reverse::reverse(&mut c, params::REVERSAL_PERCENT, &mut rng);
+        assert_eq!(c.jobs, vec![0]);
+
+        // At least two jobs are reversed
+        let mut c = Chromosome::from(vec![0, 1]);
+        Reverse::reverse(&mut c, params::REVERSAL_PERCENT, &mut rng);
+        assert_eq!(c.jobs, vec![1, 0]);
+
+        // All jobs are reversed with a reversal_percent of 1
+        let mut c = Chromosome::from((0..5).collect::<Vec<u32>>());
+        Reverse::reverse(&mut c, 1, &mut rng);
+        assert_eq!(c.jobs, vec![4, 3, 2, 1, 0]);
+    }
+
     #[test]
     fn test_swap() {
         let mut c = Chromosome::from((0..10).collect::<Vec<u32>>());
@@ -163,7 +190,7 @@ mod test {
 
         Swap::apply(
             &mut jobs_mutate,
-            &mut Makespan::new(&test_instance()),
+            &mut Makespan::new(&test_instance(), DecodingType::Fifo),
             &mut rng,
         );
 
@@ -183,7 +210,7 @@ mod test {
     #[test]
     fn test_greedy() {
         let instance = test_instance();
-        let mut makespan = Makespan::new(&instance);
+        let mut makespan = Makespan::new(&instance, DecodingType::Fifo);
         let mut c = Chromosome::from((0..instance.jobs).collect::<Vec<u32>>());
 
         // Calculate makespan before mutation
@@ -1,15 +1,15 @@
 use rand::prelude::{SliceRandom, StdRng};
-use serde_derive::Serialize;
+use serde_derive::{Deserialize, Serialize};
 
 use crate::{
     common::{construction::gch, makespan::Makespan},
     genetic_algorithm::entities::chromosome::Chromosome,
 };
 
-use super::mutation::{self, Mutation};
+use super::mutation::MTYPE;
 
 #[allow(dead_code)]
-#[derive(Clone, Serialize)]
+#[derive(Clone, Deserialize, Serialize)]
 pub enum RTYPE {
     Random,
     GCH,
@@ -32,7 +32,7 @@ impl Replacement for Random {
 
         for index in always_keep..population.len() {
             let mut new_c = Chromosome::new(&m.instance, rng);
-            new_c.makespan(m);
+            new_c.evaluate(m);
             population[index] = new_c;
         }
     }
@@ -54,8 +54,16 @@ impl Replacement for GCH {
     }
 }
 
-impl Replacement for Mutate {
-    fn replace(population: &mut Vec<Chromosome>, keep: f64, m: &mut Makespan, rng: &mut StdRng) {
+impl Mutate {
+    // Replaces half of the individuals that are not kept by random mutations of the kept ones, where
+    // reversal_percent is used by the Reverse mutation
+    pub fn replace(
+        population: &mut [Chromosome],
+        keep: f64,
+        reversal_percent: usize,
+        m: &mut Makespan,
+        rng: &mut StdRng,
+    ) {
         let popsize = population.len();
         let always_keep = (popsize as f64 * keep) as usize;
 
@@ -67,8 +75,8 @@ impl Replacement for Mutate {
                 .to_vec();
 
             let mut new_c = Chromosome::from(new_c);
-            mutation::Random::apply(&mut new_c, m, rng);
-            new_c.makespan(m);
+            MTYPE::Random.apply(&mut new_c, m, reversal_percent, rng);
+            new_c.evaluate(m);
 
             population[index] = new_c;
         }
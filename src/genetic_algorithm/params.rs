@@ -1,15 +1,22 @@
-use crate::common::construction::Construction;
+use crate::common::{construction::Construction, decoding::DecodingType, objective::ObjectiveType};
 
-use super::operators::{crossover::XTYPE, crowding::DTYPE, mutation::MTYPE, replacement::RTYPE};
+use super::island::{Emigration, Immigration, Topology};
+use super::operators::{
+    crossover::XTYPE, crowding::DTYPE, local_search::LSTYPE, mutation::MTYPE, replacement::RTYPE,
+};
 
+// Defaults of the GA options, which can be changed without a rebuild by a config file
+// or by flags (see entities/config.rs)
 pub const PROBLEM_FILE: &str = "./instances/ruiz/json/n120m8-02.json";
 // pub const PROBLEM_FILE: &str = "./instances/ruiz/json/n120m8-21.json";
 // pub const IMPROVEMENT_FILE: &str = "./solutions/improvement/ig/n20m2-01.csv";
 pub const WRITE_IMPROVEMENT: bool = true;
+pub const IMPROVEMENT_FOLDER: &str = "./solutions/improvement";
 pub const POPULATION_SIZE: usize = 150;
 pub const ITERATIONS: usize = 180;
 pub const ELITISM: usize = 2;
 pub const LOCAL_SEARCH: bool = false;
+pub const LSTYPE: LSTYPE = LSTYPE::IG;
 pub const KEEP_BEST: f32 = 0.8;
 pub const K_TOURNAMENT: usize = 2;
 pub const XOVER_PROB: f32 = 0.5;
@@ -19,6 +26,14 @@ pub const NON_IMPROVING_ITERATIONS: usize = 3000; // use 50, 100, 150 (because o
 pub const RTYPE: RTYPE = RTYPE::Mutate;
 pub const ALLWAYS_KEEP: f64 = 1.0; // Percentage of population to always keep
 pub const APPROX_CALC: usize = 300;
+pub const DECODING: DecodingType = DecodingType::Fifo;
+pub const OBJECTIVE: ObjectiveType = ObjectiveType::Makespan;
+pub const SEED: u64 = 123;
+// Objectives and their weights summed by the weighted objective
+pub const OBJECTIVE_WEIGHTS: [(ObjectiveType, u32); 2] = [
+    (ObjectiveType::Makespan, 10),
+    (ObjectiveType::TotalFlowTime, 1),
+];
 
 // Q-Learning
 pub const LEARNING_RATE: f64 = 0.2;
@@ -35,7 +50,13 @@ pub const CROWDING_SCALE: f64 = 0.0;
 pub const K_NEAREST: usize = 20; // Only used in steady state crowding version
 pub const DISTANCE_METRIC: DTYPE = DTYPE::DeviationDistance;
 
-pub const IG_GRID_SEARCH: bool = true;
+// ISLAND MODEL
+pub const ISLANDS: usize = 1; // A single population, no island model
+pub const TOPOLOGY: Topology = Topology::Ring;
+pub const MIGRATION_INTERVAL: usize = 20;
+pub const MIGRANTS: usize = 2;
+pub const EMIGRATION: Emigration = Emigration::Best;
+pub const IMMIGRATION: Immigration = Immigration::Worst;
 
 // Solution folder for parameter grid search
 
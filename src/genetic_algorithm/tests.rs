@@ -3,6 +3,7 @@ pub mod tests {
 
     use crate::{
         common::{
+            decoding::DecodingType,
             instance::{parse, Instance},
             makespan::Makespan,
         },
@@ -16,13 +17,10 @@ pub mod tests {
         let mut c1 = Chromosome::from(vec![0, 1, 2, 3, 4]);
         let mut c2 = Chromosome::from(vec![4, 3, 2, 1, 0]);
 
-        let mut makespan = Makespan {
-            count: 0,
-            instance: problem.clone(),
-        };
+        let mut makespan = Makespan::new(&problem, DecodingType::Fifo);
 
-        c1.makespan(&mut makespan);
-        c2.makespan(&mut makespan);
+        c1.evaluate(&mut makespan);
+        c2.evaluate(&mut makespan);
 
         assert!(c1 > c2);
         assert!(c1 >= c2);
@@ -62,6 +60,7 @@ pub mod tests {
                     vec![3, 2, 3, 4, 5],
                 ],
             ],
+            ..Default::default()
         }
     }
 }
@@ -31,10 +31,7 @@ Iterative_Greedy(Instance) {
 }
 */
 
-use std::{
-    path::PathBuf,
-    time::{Duration, Instant},
-};
+use std::{cmp::max, path::PathBuf, time::Duration};
 
 use rand::{prelude::StdRng, Rng, SeedableRng};
 
@@ -44,8 +41,9 @@ use crate::{
             neh::{insert_job, NEH},
             solver::Solver,
         },
-        instance::parse,
         makespan::Makespan,
+        objective::ObjectiveType,
+        termination::Progress,
         utils,
     },
     genetic_algorithm::params,
@@ -57,19 +55,19 @@ pub struct IteratedGreedy {}
 
 impl Solver for IteratedGreedy {
     fn run(makespan: &mut Makespan, option: Option<Options>) -> u32 {
-        let mut rng = StdRng::seed_from_u64(123);
-        let result = iterated_greedy(makespan, None, params::ITERATIONS as u32, option, &mut rng);
+        let seed = option.as_ref().map_or(params::SEED, |o| o.seed);
+        let mut rng = StdRng::seed_from_u64(seed);
+        let result = iterated_greedy(makespan, None, option, &mut rng);
 
         result.1
     }
 }
 
-// All schedules are tuples of the schedule and makespan of the schedule
+// All schedules are tuples of the schedule and objective value (the makespan by default) of the schedule
 
 pub fn iterated_greedy(
     makespan: &mut Makespan,
     schedule: Option<(Vec<u32>, u32)>,
-    max_iteration: u32,
     option: Option<Options>,
     rng: &mut StdRng,
 ) -> (Vec<u32>, u32) {
@@ -91,36 +89,35 @@ pub fn iterated_greedy(
     current_schedule = iterative_improvement_insertion(makespan, &current_schedule.0, rng);
     let mut best_schedule: (Vec<u32>, u32) = (current_schedule.0.clone(), current_schedule.1);
 
-    let (t, d) = match option {
+    let (t, d, termination, write_improvement) = match option {
         Some(_) => {
             let o = option.as_ref().unwrap();
-            (o.temp, o.block_size)
+            (
+                o.temp,
+                o.block_size,
+                o.termination.clone(),
+                o.write_improvement,
+            )
         }
         None => {
             let o = Options::default();
-            (o.temp, o.block_size)
+            (o.temp, o.block_size, o.termination, o.write_improvement)
         }
     };
 
-    let temp: f64 = find_temp(&makespan, t);
+    let temp: f64 = find_temp(&makespan, t) * objective_scale(makespan, &current_schedule.0);
 
-    let mut iteration: u32 = 0;
-    let start_time = Instant::now();
-    let duration_millis = utils::get_duration(&makespan.instance);
-    let allowed_duration = Duration::from_millis(duration_millis);
+    let termination = termination.for_instance(&makespan.instance, makespan.objective);
+    let mut progress = Progress::new(&termination, makespan.count, Duration::ZERO);
 
     // Go through generations
-    while !is_terminated(
-        start_time.elapsed(),
-        allowed_duration,
-        iteration,
-        max_iteration,
-    ) {
+    while !progress.is_terminated(makespan.count) {
+        let iteration = progress.generation;
         let mut schedule_permutation = current_schedule.clone();
         let mut deleted_jobs: Vec<u32> = Vec::with_capacity(makespan.instance.jobs as usize);
         for _ in 0..d {
             let (reduced_schedule, deleted_job) = remove_random(&schedule_permutation.0, rng);
-            let reduced_schedule_makspan = makespan.makespan(&reduced_schedule).0;
+            let reduced_schedule_makspan = makespan.evaluate(&reduced_schedule);
             schedule_permutation = (reduced_schedule, reduced_schedule_makspan).clone();
             deleted_jobs.push(deleted_job.clone())
         }
@@ -139,32 +136,38 @@ pub fn iterated_greedy(
         {
             current_schedule = (schedule_permutation.0.clone(), schedule_permutation.1);
         }
-        if params::WRITE_IMPROVEMENT {
+        if write_improvement {
             makespan_improvement.push(vec![
                 iteration.to_string(),
                 best_schedule.1.to_string(),
                 makespan.count.to_string(),
-                start_time.elapsed().as_millis().to_string(),
+                progress.elapsed().as_millis().to_string(),
             ]);
         }
 
-        iteration += 1;
+        progress.next_generation(best_schedule.1);
     }
 
-    if params::WRITE_IMPROVEMENT {
+    if write_improvement {
         makespan_improvement.push(vec![
-            iteration.to_string(),
+            progress.generation.to_string(),
             best_schedule.1.to_string(),
             makespan.count.to_string(),
-            duration_millis.to_string(),
+            progress.elapsed().as_millis().to_string(),
         ]);
 
-        let file = option.unwrap();
-        let file = file.problem_file.as_os_str();
+        let option = option.unwrap_or_default();
+        let file = option.problem_file.as_os_str();
         let mut filename = PathBuf::from(PathBuf::from(file).file_name().unwrap());
         filename.set_extension("csv");
         filename = PathBuf::from("ig/all").join(filename);
-        utils::write_makespan_improvement(filename, &makespan_improvement).unwrap();
+        utils::write_makespan_improvement(
+            &option.improvement_folder,
+            filename,
+            option.seed,
+            &makespan_improvement,
+        )
+        .unwrap();
     }
 
     return best_schedule;
@@ -182,6 +185,19 @@ fn find_temp(makespan: &Makespan, t: f64) -> f64 {
     return t * total_production_time * denominator;
 }
 
+// Ratio between the objective value and the makespan of a schedule, used to scale the temperature
+// (which is based on processing times) to the magnitude of the objective
+pub fn objective_scale(makespan: &mut Makespan, schedule: &Vec<u32>) -> f64 {
+    match makespan.objective {
+        ObjectiveType::Makespan => 1.0,
+        _ => {
+            let value = makespan.evaluate(schedule);
+            let (mks, _) = makespan.makespan(schedule);
+            max(value, 1) as f64 / mks as f64
+        }
+    }
+}
+
 // Local search removing one job and finding a better place for it. Runs until solution is not improving
 pub fn iterative_improvement_insertion(
     makespan: &mut Makespan,
@@ -192,7 +208,7 @@ pub fn iterative_improvement_insertion(
     // n is the amout of tries before each check of improvement. Should not be larger than the amount of jobs
     let n = schedule.len() / 5;
     // current_schedule keeps track of the best schedule and the makespan of it
-    let mut current_schedule: (Vec<u32>, u32) = (schedule.clone(), makespan.makespan(schedule).0);
+    let mut current_schedule: (Vec<u32>, u32) = (schedule.clone(), makespan.evaluate(schedule));
     // Perform the local search as long as it is improving
     while improvement == true {
         improvement = false;
@@ -233,46 +249,19 @@ fn remove_random(schedule: &Vec<u32>, rng: &mut StdRng) -> (Vec<u32>, u32) {
     return (new_schedule, job);
 }
 
-pub fn run_one() {
-    let i = parse(params::PROBLEM_FILE).unwrap();
-    let mut m = Makespan::new(&i);
-    let mut rng = StdRng::seed_from_u64(123);
-
-    let result = iterated_greedy(
-        &mut m,
-        None,
-        params::ITERATIONS as u32,
-        Some(Options::default()),
-        &mut rng,
-    );
-    println!("Iterated greedy finished with makespan {}", result.1);
-}
-
-fn is_terminated(
-    current_duration: Duration,
-    allowed_duration: Duration,
-    iteration: u32,
-    max_iteration: u32,
-) -> bool {
-    // Either, we compare with time
-    if params::IG_GRID_SEARCH {
-        current_duration >= allowed_duration
-    // Or we compare with makespan count
-    } else {
-        iteration > max_iteration
-    }
-}
-
 #[cfg(test)]
 mod ig_tests {
 
     use super::*;
-    use crate::common::{instance::parse, instance::Instance, makespan::Makespan};
+    use crate::common::{
+        decoding::DecodingType, instance::parse, instance::Instance, makespan::Makespan,
+        termination::Termination,
+    };
 
     #[test]
     fn iterative_improvement_insertion_test() {
         let i: Instance = parse("instances\\ruiz\\json\\n20m2-1.json").unwrap();
-        let mut m: Makespan = Makespan::new(&i);
+        let mut m: Makespan = Makespan::new(&i, DecodingType::Fifo);
         let schedule: Vec<u32> = (0..20).collect();
 
         let mut rng = StdRng::seed_from_u64(123);
@@ -290,11 +279,19 @@ mod ig_tests {
     #[test]
     fn iterated_greedy_test() {
         let i: Instance = parse("instances\\ruiz\\json\\n20m2-1.json").unwrap();
-        let mut m: Makespan = Makespan::new(&i);
+        let mut m: Makespan = Makespan::new(&i, DecodingType::Fifo);
 
         let mut rng = StdRng::seed_from_u64(123);
 
-        let ig = iterated_greedy(&mut m, None, 5000, None, &mut rng);
+        let options = Options {
+            termination: Termination {
+                max_generations: Some(5000),
+                ..Termination::default()
+            },
+            write_improvement: false,
+            ..Options::default()
+        };
+        let ig = iterated_greedy(&mut m, None, Some(options), &mut rng);
 
         let schedule: Vec<u32> = (0..20).collect();
         let schedule_makespan = m.makespan(&schedule).0;
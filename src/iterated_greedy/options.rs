@@ -2,7 +2,7 @@ use std::{borrow::Cow, path::PathBuf};
 
 use itertools::iproduct;
 
-use crate::genetic_algorithm::params;
+use crate::{common::termination::Termination, genetic_algorithm::params};
 
 #[derive(Clone, Debug)]
 pub struct Options {
@@ -14,6 +14,18 @@ pub struct Options {
 
     // Number of jobs to remove
     pub block_size: i32,
+
+    // When to stop the IG, see common::termination
+    pub termination: Termination,
+
+    // Seed of the random number generator
+    pub seed: u64,
+
+    // Write the improvement over the iterations to file
+    pub write_improvement: bool,
+
+    // Folder of the improvement files, see utils::write_makespan_improvement
+    pub improvement_folder: PathBuf,
 }
 
 impl Default for Options {
@@ -22,6 +34,10 @@ impl Default for Options {
             problem_file: Cow::Owned(PathBuf::from(params::PROBLEM_FILE)),
             temp: 0.5,
             block_size: 2,
+            termination: Termination::default(),
+            seed: params::SEED,
+            write_improvement: params::WRITE_IMPROVEMENT,
+            improvement_folder: PathBuf::from(params::IMPROVEMENT_FOLDER),
         }
     }
 }
@@ -52,6 +68,9 @@ impl OptionsGrid {
                 temp: opt.0,
                 block_size: opt.1,
                 problem_file: Cow::Owned(options.problem_file.as_ref().clone()),
+                termination: options.termination.clone(),
+                improvement_folder: options.improvement_folder.clone(),
+                ..options
             })
             .collect()
     }
@@ -0,0 +1,198 @@
+use rand::{
+    prelude::{SliceRandom, StdRng},
+    Rng,
+};
+use serde_derive::{Deserialize, Serialize};
+
+use crate::common::{bounds, instance::Instance};
+
+/*
+Destroy operators of the ALNS, which remove q jobs from a permutation. The operators that rank jobs pick the
+job at rank floor(y^RANDOMIZATION * jobs left) with y uniform in [0, 1), so that they mostly, but not always,
+take the highest ranked job (Ropke and Pisinger, 2006).
+    Random: q random jobs
+    Block: q consecutive jobs at a random position
+    WorstSetup: the jobs with the largest setup times from the job before them in the permutation, summed over
+                the stages they visit (the setups on the machines depend on the decoding, and are not known)
+    Related: a random job and the q - 1 jobs with the smallest setup times to and from it, summed over the stages
+    Bottleneck: the jobs with the longest operations on the stage with the most work per machine
+*/
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum DestroyType {
+    Random,
+    Block,
+    WorstSetup,
+    Related,
+    Bottleneck,
+}
+
+pub trait Destroy {
+    // Removes q jobs, and returns the permutation of the jobs left and the removed jobs
+    fn destroy(
+        jobs: &[u32],
+        q: usize,
+        instance: &Instance,
+        rng: &mut StdRng,
+    ) -> (Vec<u32>, Vec<u32>);
+}
+
+pub struct RandomRemoval;
+pub struct BlockRemoval;
+pub struct WorstSetupRemoval;
+pub struct RelatedRemoval;
+pub struct BottleneckRemoval;
+
+// Determinism of the removal of ranked jobs, higher values take the highest ranked job more often
+const RANDOMIZATION: f64 = 3.0;
+
+impl Destroy for RandomRemoval {
+    fn destroy(
+        jobs: &[u32],
+        q: usize,
+        _instance: &Instance,
+        rng: &mut StdRng,
+    ) -> (Vec<u32>, Vec<u32>) {
+        let removed: Vec<u32> = jobs.choose_multiple(rng, q).copied().collect();
+        remove(jobs, removed)
+    }
+}
+
+impl Destroy for BlockRemoval {
+    fn destroy(
+        jobs: &[u32],
+        q: usize,
+        _instance: &Instance,
+        rng: &mut StdRng,
+    ) -> (Vec<u32>, Vec<u32>) {
+        let start = rng.gen_range(0..=jobs.len() - q);
+
+        let mut left = jobs.to_vec();
+        let removed = left.drain(start..start + q).collect();
+        (left, removed)
+    }
+}
+
+impl Destroy for WorstSetupRemoval {
+    fn destroy(
+        jobs: &[u32],
+        q: usize,
+        instance: &Instance,
+        rng: &mut StdRng,
+    ) -> (Vec<u32>, Vec<u32>) {
+        // Setup time of each job from the job before it in each stage, the initial setup for the first job
+        let mut previous: Vec<Option<usize>> = vec![None; instance.stages as usize];
+        let mut setups: Vec<(u32, u32)> = Vec::with_capacity(jobs.len());
+        for &job in jobs {
+            let job = job as usize;
+            let mut setup = 0;
+            for (stage, prev) in previous.iter_mut().enumerate() {
+                if instance.processing_times[job][stage] != 0 {
+                    setup += instance.setup_times[stage][prev.unwrap_or(job)][job];
+                    *prev = Some(job);
+                }
+            }
+            setups.push((setup, job as u32));
+        }
+
+        // Largest setup first
+        setups.sort_by(|a, b| b.0.cmp(&a.0).then(a.1.cmp(&b.1)));
+        let ranked = setups.into_iter().map(|(_, job)| job).collect();
+        remove(jobs, pick_ranked(ranked, q, rng))
+    }
+}
+
+impl Destroy for RelatedRemoval {
+    fn destroy(
+        jobs: &[u32],
+        q: usize,
+        instance: &Instance,
+        rng: &mut StdRng,
+    ) -> (Vec<u32>, Vec<u32>) {
+        let seed = *jobs.choose(rng).unwrap();
+
+        // Setup times between the seed and the other jobs, in both directions and over all stages
+        let distance = |job: u32| -> u32 {
+            (0..instance.stages as usize)
+                .map(|stage| {
+                    instance.setup_times[stage][seed as usize][job as usize]
+                        + instance.setup_times[stage][job as usize][seed as usize]
+                })
+                .sum()
+        };
+        let mut ranked: Vec<(u32, u32)> = jobs
+            .iter()
+            .filter(|&&job| job != seed)
+            .map(|&job| (distance(job), job))
+            .collect();
+        ranked.sort_unstable();
+
+        let mut removed = vec![seed];
+        removed.extend(pick_ranked(
+            ranked.into_iter().map(|(_, job)| job).collect(),
+            q - 1,
+            rng,
+        ));
+        remove(jobs, removed)
+    }
+}
+
+impl Destroy for BottleneckRemoval {
+    fn destroy(
+        jobs: &[u32],
+        q: usize,
+        instance: &Instance,
+        rng: &mut StdRng,
+    ) -> (Vec<u32>, Vec<u32>) {
+        let operations = bounds::operation_times(instance);
+        let bottleneck = bounds::bottleneck_stage(instance, &operations);
+
+        // Longest operation on the bottleneck stage first, jobs skipping it last
+        let mut ranked = jobs.to_vec();
+        ranked.sort_by(|&a, &b| {
+            operations[b as usize][bottleneck]
+                .cmp(&operations[a as usize][bottleneck])
+                .then(a.cmp(&b))
+        });
+        remove(jobs, pick_ranked(ranked, q, rng))
+    }
+}
+
+impl DestroyType {
+    pub fn destroy(
+        &self,
+        jobs: &[u32],
+        q: usize,
+        instance: &Instance,
+        rng: &mut StdRng,
+    ) -> (Vec<u32>, Vec<u32>) {
+        match self {
+            DestroyType::Random => RandomRemoval::destroy(jobs, q, instance, rng),
+            DestroyType::Block => BlockRemoval::destroy(jobs, q, instance, rng),
+            DestroyType::WorstSetup => WorstSetupRemoval::destroy(jobs, q, instance, rng),
+            DestroyType::Related => RelatedRemoval::destroy(jobs, q, instance, rng),
+            DestroyType::Bottleneck => BottleneckRemoval::destroy(jobs, q, instance, rng),
+        }
+    }
+}
+
+// Picks q of the ranked jobs, mostly the highest ranked, see the top of the file
+fn pick_ranked(mut ranked: Vec<u32>, q: usize, rng: &mut StdRng) -> Vec<u32> {
+    (0..q)
+        .map(|_| {
+            let rank = (rng.gen::<f64>().powf(RANDOMIZATION) * ranked.len() as f64) as usize;
+            ranked.remove(rank)
+        })
+        .collect()
+}
+
+// The jobs that are not removed, in the order of the permutation, and the removed jobs
+fn remove(jobs: &[u32], removed: Vec<u32>) -> (Vec<u32>, Vec<u32>) {
+    let left = jobs
+        .iter()
+        .filter(|job| !removed.contains(job))
+        .copied()
+        .collect();
+    (left, removed)
+}
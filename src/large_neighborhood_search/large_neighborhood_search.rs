@@ -0,0 +1,290 @@
+/*
+Adaptive large neighborhood search (ALNS) on the job permutation, after Ropke and Pisinger (2006).
+
+ALNS(Instance) {
+    current = NEH(instance);
+    best = current;
+    while termination criteria not met {
+        d, r = destroy and repair operators by roulette wheel on their weights;
+        q = random number of jobs in min_remove..=max_remove;
+        candidate = r(d(current, q));          (see destroy.rs and repair.rs)
+        if candidate is not worse than current or random <= exp(-delta / T) {
+            current = candidate;
+            if current is better than best { best = current }
+        }
+        add the score of the outcome to d and r;
+        every segment_length iterations, update the weights by the scores;
+    }
+    return best
+}
+
+The operators are scored scores[0] for a new best solution, scores[1] for a solution better than the current
+and scores[2] for an accepted worse solution. After each segment the weight of an operator used in it is
+    w = (1 - reaction) * w + reaction * (sum of its scores in the segment) / (times used in the segment)
+and the scores and uses are reset. The temperature T is constant, as in the IG (see
+simulated_annealing::initial_temperature).
+
+Every destroy and repair is one generation of the termination criteria (see common::termination).
+*/
+
+use std::time::Duration;
+
+use rand::{prelude::StdRng, Rng};
+
+use crate::{
+    common::{
+        construction::{neh::NEH, solver::Solver},
+        makespan::Makespan,
+        run::{self, Improvement},
+        termination::Progress,
+    },
+    iterated_greedy::{iterated_greedy::objective_scale, options::Options as IgOptions},
+    simulated_annealing::simulated_annealing::initial_temperature,
+};
+
+use super::options::Options;
+
+pub struct AdaptiveLargeNeighborhoodSearch {}
+
+impl Solver for AdaptiveLargeNeighborhoodSearch {
+    fn run(makespan: &mut Makespan, option: Option<IgOptions>) -> u32 {
+        run::run_solver(makespan, option, alns)
+    }
+}
+
+// Roulette wheel of the operators, with the scores and uses of the current segment
+#[derive(Clone, Debug)]
+pub struct Weights {
+    pub weights: Vec<f64>,
+    scores: Vec<f64>,
+    uses: Vec<u32>,
+}
+
+impl Weights {
+    pub fn new(operators: usize) -> Self {
+        Self {
+            weights: vec![1.0; operators],
+            scores: vec![0.0; operators],
+            uses: vec![0; operators],
+        }
+    }
+
+    // Index of an operator with probability proportional to its weight
+    pub fn select(&mut self, rng: &mut StdRng) -> usize {
+        let mut r = rng.gen::<f64>() * self.weights.iter().sum::<f64>();
+        let mut selected = self.weights.len() - 1;
+        for (i, weight) in self.weights.iter().enumerate() {
+            if r < *weight {
+                selected = i;
+                break;
+            }
+            r -= weight;
+        }
+
+        self.uses[selected] += 1;
+        selected
+    }
+
+    pub fn score(&mut self, operator: usize, score: f64) {
+        self.scores[operator] += score;
+    }
+
+    // Ends the segment, see the top of the file
+    pub fn update(&mut self, reaction: f64) {
+        for i in 0..self.weights.len() {
+            if self.uses[i] > 0 {
+                self.weights[i] = (1.0 - reaction) * self.weights[i]
+                    + reaction * self.scores[i] / self.uses[i] as f64;
+            }
+        }
+        self.scores.iter_mut().for_each(|s| *s = 0.0);
+        self.uses.iter_mut().for_each(|u| *u = 0);
+    }
+}
+
+// All schedules are tuples of the schedule and objective value (the makespan by default) of the schedule
+
+pub fn alns(
+    makespan: &mut Makespan,
+    schedule: Option<(Vec<u32>, u32)>,
+    option: Option<Options>,
+    rng: &mut StdRng,
+) -> (Vec<u32>, u32) {
+    let options = option.unwrap_or_default();
+    let mut current = schedule.unwrap_or_else(|| NEH::neh(makespan));
+    let mut best = current.clone();
+
+    let mut improvement = Improvement::new(&options.run, best.1, makespan.count);
+
+    // Nothing to destroy and repair
+    let n = current.0.len();
+    if n < 2 || options.destroy.is_empty() || options.repair.is_empty() {
+        return best;
+    }
+    let max_remove = options.max_remove.clamp(1, n - 1);
+    let min_remove = options.min_remove.clamp(1, max_remove);
+
+    let temp = initial_temperature(&makespan.instance, options.temp)
+        * objective_scale(makespan, &current.0);
+    let mut destroy_weights = Weights::new(options.destroy.len());
+    let mut repair_weights = Weights::new(options.repair.len());
+
+    let termination = options
+        .run
+        .termination
+        .for_instance(&makespan.instance, makespan.objective);
+    let mut progress = Progress::new(&termination, makespan.count, Duration::ZERO);
+
+    while !progress.is_terminated(makespan.count) {
+        let d = destroy_weights.select(rng);
+        let r = repair_weights.select(rng);
+        let q = rng.gen_range(min_remove..=max_remove);
+
+        let (left, removed) = options.destroy[d].destroy(&current.0, q, &makespan.instance, rng);
+        let candidate = options.repair[r].repair(left, &removed, makespan, rng);
+
+        let delta = candidate.1 as f64 - current.1 as f64;
+        let score = if candidate.1 < best.1 {
+            Some(options.scores[0])
+        } else if candidate.1 < current.1 {
+            Some(options.scores[1])
+        } else if rng.gen::<f64>() <= (-delta / temp).exp() {
+            Some(options.scores[2])
+        } else {
+            None
+        };
+
+        if let Some(score) = score {
+            current = candidate;
+            destroy_weights.score(d, score);
+            repair_weights.score(r, score);
+
+            if current.1 < best.1 {
+                best = current.clone();
+                improvement.push(&progress, best.1, makespan.count);
+            }
+        }
+
+        if (progress.generation + 1) % options.segment_length.max(1) == 0 {
+            destroy_weights.update(options.reaction);
+            repair_weights.update(options.reaction);
+        }
+
+        progress.next_generation(best.1);
+    }
+
+    improvement.write("alns", &options.run, &progress, best.1, makespan.count);
+
+    best
+}
+
+#[cfg(test)]
+mod alns_tests {
+    use rand::{prelude::StdRng, SeedableRng};
+
+    use crate::{
+        common::{
+            construction::neh::NEH, decoding::DecodingType, instance::parse, makespan::Makespan,
+            run::RunOptions, termination::Termination,
+        },
+        large_neighborhood_search::{destroy::DestroyType, options::Options},
+    };
+
+    use super::{alns, Weights};
+
+    #[test]
+    fn destroy_and_repair() {
+        let instance = parse("./instances/ruiz/json/n20m2-43.json").unwrap();
+        let mut makespan = Makespan::new_incremental(&instance, DecodingType::Fifo);
+        let mut rng = StdRng::seed_from_u64(123);
+        let jobs: Vec<u32> = (0..instance.jobs).collect();
+
+        for destroy in Options::default().destroy {
+            let (left, removed) = destroy.destroy(&jobs, 5, &instance, &mut rng);
+            assert_eq!(removed.len(), 5);
+            assert!(left.iter().all(|job| !removed.contains(job)));
+            let mut all = [left.clone(), removed.clone()].concat();
+            all.sort();
+            assert_eq!(all, jobs);
+
+            if destroy == DestroyType::Block {
+                let start = removed[0] as usize;
+                assert_eq!(removed, jobs[start..start + 5]);
+            }
+
+            for repair in Options::default().repair {
+                let (schedule, value) =
+                    repair.repair(left.clone(), &removed, &mut makespan, &mut rng);
+                let mut sorted = schedule.clone();
+                sorted.sort();
+                assert_eq!(sorted, jobs);
+                assert_eq!(makespan.evaluate(&schedule), value);
+            }
+        }
+    }
+
+    #[test]
+    fn weights_update() {
+        let mut rng = StdRng::seed_from_u64(123);
+        let mut weights = Weights::new(3);
+
+        let selected = weights.select(&mut rng);
+        weights.score(selected, 10.0);
+        let uses = weights.uses.clone();
+        weights.update(0.5);
+
+        // Unused operators keep their weights, and the scores and uses are reset
+        for (i, weight) in weights.weights.iter().enumerate() {
+            match i == selected {
+                true => assert_eq!(*weight, 0.5 + 0.5 * 10.0),
+                false => assert_eq!(*weight, 1.0),
+            }
+            assert_eq!(uses[i], (i == selected) as u32);
+        }
+        assert_eq!(weights.uses, vec![0; 3]);
+        assert_eq!(weights.scores, vec![0.0; 3]);
+
+        // Only used operators are selected
+        let mut weights = Weights {
+            weights: vec![0.0, 1.0, 0.0],
+            ..Weights::new(3)
+        };
+        assert!((0..100).all(|_| weights.select(&mut rng) == 1));
+    }
+
+    #[test]
+    fn alns_test() {
+        let instance = parse("./instances/ruiz/json/n20m2-43.json").unwrap();
+        let options = Options {
+            run: RunOptions {
+                termination: Termination {
+                    max_evaluations: Some(5000),
+                    ..Termination::default()
+                },
+                write_improvement: false,
+                ..RunOptions::default()
+            },
+            ..Options::default()
+        };
+
+        let run = || {
+            let mut makespan = Makespan::new_incremental(&instance, DecodingType::Fifo);
+            let mut rng = StdRng::seed_from_u64(7);
+            let neh = NEH::neh(&mut makespan);
+            let evaluations = makespan.count;
+            let result = alns(
+                &mut makespan,
+                Some(neh.clone()),
+                Some(options.clone()),
+                &mut rng,
+            );
+
+            assert!(makespan.count >= evaluations + 5000);
+            assert!(result.1 <= neh.1);
+            assert_eq!(makespan.evaluate(&result.0), result.1);
+            result
+        };
+
+        assert_eq!(run(), run());
+    }
+}
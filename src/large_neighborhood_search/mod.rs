@@ -0,0 +1,4 @@
+pub mod destroy;
+pub mod large_neighborhood_search;
+pub mod options;
+pub mod repair;
@@ -0,0 +1,68 @@
+use serde_derive::{Deserialize, Serialize};
+
+use crate::common::run::{AlgorithmOptions, RunOptions};
+
+use super::{destroy::DestroyType, repair::RepairType};
+
+// Set by the [alns] table of the config file, see genetic_algorithm::entities::config. The run options are
+// taken from the GA options, see solve.rs
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Options {
+    // Destroy and repair operators, chosen by their adaptive weights
+    pub destroy: Vec<DestroyType>,
+    pub repair: Vec<RepairType>,
+
+    // Number of jobs removed by the destroy operators, drawn uniformly from min_remove..=max_remove
+    pub min_remove: usize,
+    pub max_remove: usize,
+
+    // Scale of the temperature of the acceptance, as the temperature of the IG
+    pub temp: f64,
+
+    // Iterations between the updates of the weights
+    pub segment_length: usize,
+
+    // Share of the new weight given by the scores of the last segment
+    pub reaction: f64,
+
+    // Scores of the operators for a new best solution, a better solution and an accepted worse solution
+    pub scores: [f64; 3],
+
+    // Problem file, termination criteria, seed and improvement file, see common::run. The generations are the
+    // destroy and repair iterations
+    #[serde(skip)]
+    pub run: RunOptions,
+}
+
+impl AlgorithmOptions for Options {
+    fn run_options(&mut self) -> &mut RunOptions {
+        &mut self.run
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            destroy: vec![
+                DestroyType::Random,
+                DestroyType::Block,
+                DestroyType::WorstSetup,
+                DestroyType::Related,
+                DestroyType::Bottleneck,
+            ],
+            repair: vec![
+                RepairType::Greedy,
+                RepairType::Regret(2),
+                RepairType::Grasp(0.2),
+            ],
+            min_remove: 2,
+            max_remove: 6,
+            temp: 0.5,
+            segment_length: 100,
+            reaction: 0.1,
+            scores: [33.0, 9.0, 13.0],
+            run: RunOptions::default(),
+        }
+    }
+}
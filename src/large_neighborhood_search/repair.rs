@@ -0,0 +1,142 @@
+use rand::{
+    prelude::{SliceRandom, StdRng},
+    Rng,
+};
+use serde_derive::{Deserialize, Serialize};
+
+use crate::common::{construction::neh::insert_job, makespan::Makespan};
+
+/*
+Repair operators of the ALNS, which insert the removed jobs into the permutation of the jobs left.
+    Greedy: the removed jobs in random order, each at its best position (as the IG)
+    Regret(k): in each step, the job with the largest regret is inserted at its best position. The regret of a
+               job is the sum of the differences between its k best insertions and its best insertion, so
+               that jobs with few good positions are inserted first. Ties are broken by the best insertion
+    Grasp(alpha): in each step, a random job of the restricted candidate list is inserted at its best position.
+                  The list holds the jobs whose best insertion is within alpha of the range from the best to
+                  the worst best insertion of the jobs (alpha = 0 is greedy, and alpha = 1 random)
+*/
+
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum RepairType {
+    Greedy,
+    Regret(usize),
+    Grasp(f64),
+}
+
+impl RepairType {
+    // Inserts the removed jobs, and returns the permutation and its objective value
+    pub fn repair(
+        &self,
+        jobs: Vec<u32>,
+        removed: &[u32],
+        makespan: &mut Makespan,
+        rng: &mut StdRng,
+    ) -> (Vec<u32>, u32) {
+        match *self {
+            RepairType::Greedy => greedy(jobs, removed, makespan, rng),
+            RepairType::Regret(k) => regret(jobs, removed, k, makespan),
+            RepairType::Grasp(alpha) => grasp(jobs, removed, alpha, makespan, rng),
+        }
+    }
+}
+
+fn greedy(
+    jobs: Vec<u32>,
+    removed: &[u32],
+    makespan: &mut Makespan,
+    rng: &mut StdRng,
+) -> (Vec<u32>, u32) {
+    let mut removed = removed.to_vec();
+    removed.shuffle(rng);
+
+    let mut schedule = (jobs, 0);
+    for job in removed.iter() {
+        schedule = insert_job(makespan, &schedule.0, job);
+    }
+    schedule
+}
+
+// Objective values of inserting the job at all positions of the jobs
+fn insertions(jobs: &[u32], job: u32, makespan: &mut Makespan) -> Vec<u32> {
+    (0..=jobs.len())
+        .map(|position| {
+            let mut inserted = jobs.to_vec();
+            inserted.insert(position, job);
+            makespan.evaluate(&inserted)
+        })
+        .collect()
+}
+
+// Best position of the insertions and its value, the first of equal values
+fn best_insertion(values: &[u32]) -> (usize, u32) {
+    values
+        .iter()
+        .enumerate()
+        .min_by_key(|&(position, &value)| (value, position))
+        .map(|(position, &value)| (position, value))
+        .unwrap()
+}
+
+fn regret(
+    mut jobs: Vec<u32>,
+    removed: &[u32],
+    k: usize,
+    makespan: &mut Makespan,
+) -> (Vec<u32>, u32) {
+    let mut removed = removed.to_vec();
+    let mut value = makespan.evaluate(&jobs);
+
+    while !removed.is_empty() {
+        // (regret, best value, index in removed, best position), the largest regret and smallest value first
+        let (_, best, index, position) = removed
+            .iter()
+            .enumerate()
+            .map(|(index, &job)| {
+                let values = insertions(&jobs, job, makespan);
+                let (position, best) = best_insertion(&values);
+                let mut sorted = values;
+                sorted.sort_unstable();
+                let regret: u32 = sorted.iter().take(k).map(|v| v - best).sum();
+                (regret, best, index, position)
+            })
+            .max_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)).then(b.2.cmp(&a.2)))
+            .unwrap();
+
+        jobs.insert(position, removed.remove(index));
+        value = best;
+    }
+
+    (jobs, value)
+}
+
+fn grasp(
+    mut jobs: Vec<u32>,
+    removed: &[u32],
+    alpha: f64,
+    makespan: &mut Makespan,
+    rng: &mut StdRng,
+) -> (Vec<u32>, u32) {
+    let mut removed = removed.to_vec();
+    let mut value = makespan.evaluate(&jobs);
+
+    while !removed.is_empty() {
+        let best: Vec<(usize, u32)> = removed
+            .iter()
+            .map(|&job| best_insertion(&insertions(&jobs, job, makespan)))
+            .collect();
+        let min = best.iter().map(|b| b.1).min().unwrap() as f64;
+        let max = best.iter().map(|b| b.1).max().unwrap() as f64;
+
+        let candidates: Vec<usize> = (0..removed.len())
+            .filter(|&i| best[i].1 as f64 <= min + alpha * (max - min))
+            .collect();
+        let index = candidates[rng.gen_range(0..candidates.len())];
+
+        jobs.insert(best[index].0, removed.remove(index));
+        value = best[index].1;
+    }
+
+    (jobs, value)
+}
@@ -0,0 +1,39 @@
+/*
+Library of the algorithms for the hybrid flow shop with sequence dependent setup times.
+The solver API (solve, SolverConfig and SolveResult in solve.rs) runs any of the algorithms on an instance,
+and the modules give access to the instance model, decoder, construction heuristics, GA, IG, simulated
+annealing, tabu search, variable neighborhood search, adaptive large neighborhood search and the branch and
+bound for small instances.
+The tio4905 binary (main.rs and cli.rs) is a command line interface over the library.
+*/
+
+pub mod branch_and_bound;
+pub mod common;
+pub mod genetic_algorithm;
+pub mod iterated_greedy;
+pub mod large_neighborhood_search;
+pub mod simulated_annealing;
+mod solve;
+pub mod tabu_search;
+pub mod variable_neighborhood;
+
+pub use common::{
+    construction::{gch::GCH, mddr::MDDR, neh::NEH},
+    decoding::DecodingType,
+    instance::{parse, Instance, InstanceError, Solution},
+    makespan::Makespan,
+    objective::ObjectiveType,
+};
+pub use genetic_algorithm::{entities::options::Options as GaOptions, ga::GA};
+pub use iterated_greedy::{iterated_greedy::IteratedGreedy, options::Options as IgOptions};
+pub use large_neighborhood_search::{
+    large_neighborhood_search::AdaptiveLargeNeighborhoodSearch, options::Options as AlnsOptions,
+};
+pub use simulated_annealing::{
+    options::Options as SaOptions, simulated_annealing::SimulatedAnnealing,
+};
+pub use solve::{solve, Algorithm, SolveResult, SolverConfig};
+pub use tabu_search::{options::Options as TabuOptions, tabu_search::TabuSearch};
+pub use variable_neighborhood::{
+    options::Options as VnsOptions, variable_neighborhood::VariableNeighborhoodSearch,
+};
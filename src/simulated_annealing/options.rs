@@ -0,0 +1,84 @@
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    common::run::{AlgorithmOptions, RunOptions},
+    genetic_algorithm::params,
+};
+
+// Move applied to the current permutation to get a neighbour, see operators::mutation
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum Neighborhood {
+    Shift,
+    Swap,
+    Reverse,
+    // One of the moves above at random
+    Mixed,
+}
+
+// How the temperature is lowered, see simulated_annealing.rs
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum Cooling {
+    Geometric,
+    LundyMees,
+    Reheating,
+}
+
+// Set by the [sa] table of the config file, see genetic_algorithm::entities::config. The run options are
+// taken from the GA options, see solve.rs
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Options {
+    pub neighborhood: Neighborhood,
+
+    pub cooling: Cooling,
+
+    // The reverse move reverses 1 / reversal_percent of the jobs, see Reverse::reverse
+    pub reversal_percent: usize,
+
+    // Scale of the initial temperature, as the temperature of the IG
+    pub temp: f64,
+
+    // Factor of the geometric cooling, applied after every moves_per_temperature moves
+    pub alpha: f64,
+
+    // Moves at each temperature of the geometric cooling and reheating
+    pub moves_per_temperature: usize,
+
+    // Parameter of the Lundy-Mees cooling, applied after every move
+    pub beta: f64,
+
+    // Temperatures in a row without improvement of the best solution before reheating
+    pub reheat_after: usize,
+
+    // Share of the initial temperature the temperature is set back to when reheating
+    pub reheat_temp: f64,
+
+    // Problem file, termination criteria, seed and improvement file, see common::run. The generations are the moves
+    #[serde(skip)]
+    pub run: RunOptions,
+}
+
+impl AlgorithmOptions for Options {
+    fn run_options(&mut self) -> &mut RunOptions {
+        &mut self.run
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            neighborhood: Neighborhood::Mixed,
+            cooling: Cooling::Geometric,
+            reversal_percent: params::REVERSAL_PERCENT,
+            temp: 0.5,
+            alpha: 0.95,
+            moves_per_temperature: 100,
+            beta: 0.0001,
+            reheat_after: 20,
+            reheat_temp: 0.5,
+            run: RunOptions::default(),
+        }
+    }
+}
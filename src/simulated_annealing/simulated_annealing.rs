@@ -0,0 +1,273 @@
+/*
+Simulated annealing on the job permutation.
+
+Simulated_Annealing(Instance) {
+    current = NEH(instance);
+    best = current;
+    T = initial temperature;
+    while termination criteria not met {
+        neighbour = move(current);          (shift, swap or reverse, see operators::mutation)
+        delta = objective(neighbour) - objective(current);
+        if delta <= 0 or random <= exp(-delta / T) {
+            current = neighbour;
+            if current is better than best { best = current }
+        }
+        T = cool(T);
+    }
+    return best
+}
+
+The initial temperature is given by the processing times of the instance, as in the IG of Ruiz and Stützle:
+    T0 = temp * sum_js p_js / (n * m * 10)
+scaled to the magnitude of the objective (see iterated_greedy::objective_scale).
+
+Cooling schedules:
+    Geometric: T = alpha * T after every moves_per_temperature moves
+    LundyMees: T = T / (1 + beta * T) after every move
+    Reheating: geometric cooling, but the temperature is set back to reheat_temp * T0 when the best solution
+               has not improved in reheat_after temperatures in a row
+
+Every move is one generation of the termination criteria (see common::termination).
+*/
+
+use std::time::Duration;
+
+use rand::{prelude::StdRng, Rng};
+
+use crate::{
+    common::{
+        construction::{neh::NEH, solver::Solver},
+        instance::Instance,
+        makespan::Makespan,
+        run::{self, Improvement},
+        termination::Progress,
+    },
+    genetic_algorithm::{
+        entities::chromosome::Chromosome,
+        operators::mutation::{Mutation, Reverse, Swap, SHIFT},
+    },
+    iterated_greedy::{iterated_greedy::objective_scale, options::Options as IgOptions},
+};
+
+use super::options::{Cooling, Neighborhood, Options};
+
+pub struct SimulatedAnnealing {}
+
+impl Solver for SimulatedAnnealing {
+    fn run(makespan: &mut Makespan, option: Option<IgOptions>) -> u32 {
+        run::run_solver(makespan, option, simulated_annealing)
+    }
+}
+
+// All schedules are tuples of the schedule and objective value (the makespan by default) of the schedule
+
+pub fn simulated_annealing(
+    makespan: &mut Makespan,
+    schedule: Option<(Vec<u32>, u32)>,
+    option: Option<Options>,
+    rng: &mut StdRng,
+) -> (Vec<u32>, u32) {
+    let options = option.unwrap_or_default();
+    let mut current = schedule.unwrap_or_else(|| NEH::neh(makespan));
+    let mut best = current.clone();
+
+    let mut improvement = Improvement::new(&options.run, best.1, makespan.count);
+
+    // No other permutation to move to
+    if current.0.len() < 2 {
+        return best;
+    }
+
+    let initial_temp = initial_temperature(&makespan.instance, options.temp)
+        * objective_scale(makespan, &current.0);
+    let mut temp = initial_temp;
+    // Moves at the current temperature, and temperatures without improvement of the best solution
+    let mut moves = 0;
+    let mut stagnant_temperatures = 0;
+    let mut improved = false;
+
+    let termination = options
+        .run
+        .termination
+        .for_instance(&makespan.instance, makespan.objective);
+    let mut progress = Progress::new(&termination, makespan.count, Duration::ZERO);
+
+    while !progress.is_terminated(makespan.count) {
+        let neighbour = apply_move(
+            options.neighborhood,
+            options.reversal_percent,
+            &current.0,
+            makespan,
+            rng,
+        );
+        let value = makespan.evaluate(&neighbour);
+
+        let delta = value as f64 - current.1 as f64;
+        if delta <= 0.0 || rng.gen::<f64>() <= (-delta / temp).exp() {
+            current = (neighbour, value);
+            if current.1 < best.1 {
+                best = current.clone();
+                improved = true;
+                improvement.push(&progress, best.1, makespan.count);
+            }
+        }
+
+        moves += 1;
+        match options.cooling {
+            Cooling::LundyMees => temp /= 1.0 + options.beta * temp,
+            _ if moves < options.moves_per_temperature => (),
+            Cooling::Geometric => {
+                temp *= options.alpha;
+                moves = 0;
+            }
+            Cooling::Reheating => {
+                temp *= options.alpha;
+                moves = 0;
+                stagnant_temperatures = match improved {
+                    true => 0,
+                    false => stagnant_temperatures + 1,
+                };
+                improved = false;
+                if stagnant_temperatures >= options.reheat_after {
+                    temp = options.reheat_temp * initial_temp;
+                    stagnant_temperatures = 0;
+                }
+            }
+        }
+
+        progress.next_generation(best.1);
+    }
+
+    improvement.write("sa", &options.run, &progress, best.1, makespan.count);
+
+    best
+}
+
+// Initial temperature given by the processing times of the instance, see the top of the file
+pub fn initial_temperature(instance: &Instance, temp: f64) -> f64 {
+    let total_processing_time: u32 = instance.processing_times.iter().flatten().sum();
+
+    temp * total_processing_time as f64 / (instance.jobs as f64 * instance.stages as f64 * 10.0)
+}
+
+// Returns the permutation after a move of the neighborhood
+fn apply_move(
+    neighborhood: Neighborhood,
+    reversal_percent: usize,
+    jobs: &[u32],
+    makespan: &mut Makespan,
+    rng: &mut StdRng,
+) -> Vec<u32> {
+    let neighborhood = match neighborhood {
+        Neighborhood::Mixed => match rng.gen_range(0..3) {
+            0 => Neighborhood::Shift,
+            1 => Neighborhood::Swap,
+            _ => Neighborhood::Reverse,
+        },
+        n => n,
+    };
+
+    let mut c = Chromosome::from(jobs.to_vec());
+    match neighborhood {
+        Neighborhood::Shift => SHIFT::apply(&mut c, makespan, rng),
+        Neighborhood::Swap => Swap::apply(&mut c, makespan, rng),
+        _ => Reverse::reverse(&mut c, reversal_percent, rng),
+    }
+
+    c.jobs
+}
+
+#[cfg(test)]
+mod sa_tests {
+    use rand::{prelude::StdRng, SeedableRng};
+
+    use crate::common::{
+        construction::neh::NEH, decoding::DecodingType, instance::parse, makespan::Makespan,
+        run::RunOptions, termination::Termination,
+    };
+
+    use super::{initial_temperature, simulated_annealing};
+    use crate::simulated_annealing::options::{Cooling, Neighborhood, Options};
+
+    #[test]
+    fn simulated_annealing_test() {
+        let instance = parse("./instances/ruiz/json/n20m2-43.json").unwrap();
+        let mut neh_makespan = Makespan::new_incremental(&instance, DecodingType::Fifo);
+        let neh = NEH::neh(&mut neh_makespan);
+
+        // Ruiz and Stützle: temp * average processing time / 10
+        let average = instance.processing_times.iter().flatten().sum::<u32>() as f64 / 40.0;
+        assert!((initial_temperature(&instance, 0.5) - 0.05 * average).abs() < 1e-9);
+
+        for cooling in [Cooling::Geometric, Cooling::LundyMees, Cooling::Reheating] {
+            for neighborhood in [
+                Neighborhood::Shift,
+                Neighborhood::Swap,
+                Neighborhood::Reverse,
+                Neighborhood::Mixed,
+            ] {
+                let options = Options {
+                    neighborhood,
+                    cooling,
+                    run: RunOptions {
+                        termination: Termination {
+                            max_evaluations: Some(3000),
+                            ..Termination::default()
+                        },
+                        write_improvement: false,
+                        ..RunOptions::default()
+                    },
+                    ..Options::default()
+                };
+
+                let run = || {
+                    let mut makespan = Makespan::new_incremental(&instance, DecodingType::Fifo);
+                    let mut rng = StdRng::seed_from_u64(7);
+                    let result =
+                        simulated_annealing(&mut makespan, None, Some(options.clone()), &mut rng);
+                    // The evaluations of NEH are not counted in the budget
+                    assert_eq!(makespan.count, neh_makespan.count + 3000);
+                    assert_eq!(makespan.evaluate(&result.0), result.1);
+                    result
+                };
+
+                let first = run();
+                assert!(first.1 <= neh.1, "{:?} {:?}", cooling, neighborhood);
+                assert_eq!(first, run(), "{:?} {:?}", cooling, neighborhood);
+            }
+        }
+    }
+
+    #[test]
+    fn small_instances() {
+        let instance = parse("./instances/ruiz/test/test.json").unwrap();
+
+        for neighborhood in [
+            Neighborhood::Shift,
+            Neighborhood::Swap,
+            Neighborhood::Reverse,
+            Neighborhood::Mixed,
+        ] {
+            let options = Options {
+                neighborhood,
+                run: RunOptions {
+                    termination: Termination {
+                        max_evaluations: Some(200),
+                        ..Termination::default()
+                    },
+                    write_improvement: false,
+                    ..RunOptions::default()
+                },
+                ..Options::default()
+            };
+            let mut makespan = Makespan::new_incremental(&instance, DecodingType::Fifo);
+            let mut rng = StdRng::seed_from_u64(7);
+            let (mut jobs, value) =
+                simulated_annealing(&mut makespan, None, Some(options), &mut rng);
+
+            assert_eq!(makespan.evaluate(&jobs), value);
+            jobs.sort();
+            assert_eq!(jobs, vec![0, 1, 2], "{:?}", neighborhood);
+        }
+    }
+}
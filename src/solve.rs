@@ -0,0 +1,518 @@
+use std::{
+    borrow::Cow,
+    time::{Duration, Instant},
+};
+
+use clap::ArgEnum;
+use rand::{prelude::StdRng, SeedableRng};
+use serde_derive::{Deserialize, Serialize};
+
+use crate::{
+    branch_and_bound::branch_and_bound::branch_and_bound,
+    common::{
+        construction::{gch::GCH, mddr::MDDR, neh::NEH, Constructor},
+        decoding::DecodingType,
+        instance::{Instance, Solution},
+        makespan::Makespan,
+        objective::ObjectiveType,
+        run::RunOptions,
+        termination::Termination,
+    },
+    genetic_algorithm::{entities::options::Options, ga, params},
+    iterated_greedy::{iterated_greedy::iterated_greedy, options::Options as IgOptions},
+    large_neighborhood_search::{large_neighborhood_search::alns, options::Options as AlnsOptions},
+    simulated_annealing::{
+        options::Options as SaOptions, simulated_annealing::simulated_annealing,
+    },
+    tabu_search::{options::Options as TabuOptions, tabu_search::tabu_search},
+    variable_neighborhood::{options::Options as VnsOptions, variable_neighborhood::vns},
+};
+
+/*
+Solver API of the library. All algorithms are run on a parsed instance with the same configuration:
+
+let instance = tio4905::parse("instances/ruiz/json/n20m2-01.json")?;
+let result = tio4905::solve(
+    &instance,
+    SolverConfig {
+        algorithm: Algorithm::Ga,
+        termination: Termination {
+            time_limit: Some(5000),
+            ..Default::default()
+        },
+        ..Default::default()
+    },
+);
+result.solution.write("n20m2-01.json".to_string());
+
+The decoding rule, objective (and its weights), termination criteria and seed of the config are used by all algorithms,
+and override the same options in ga, ig, sa, tabu, vns and alns. The termination criteria do not apply to the construction heuristics, and the branch and bound
+(Bnb) only proves optimality if it completes before them. Nothing is written to file unless write_improvement is set in ga, ig, sa, tabu, vns or alns, which then write to their improvement_folder.
+*/
+
+#[allow(dead_code)]
+#[derive(ArgEnum, Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum Algorithm {
+    // Genetic algorithm
+    Ga,
+    // Iterated greedy
+    Ig,
+    // Simulated annealing
+    Sa,
+    // Tabu search
+    Tabu,
+    // Variable neighborhood search
+    Vns,
+    // Adaptive large neighborhood search
+    Alns,
+    // Nawaz-Enscore-Ham insertion heuristic
+    Neh,
+    // Dispatching rule, dispatches jobs to machines without a permutation
+    Mddr,
+    // Greedy construction heuristic
+    Gch,
+    // Branch and bound, optimal permutations of small instances (up to about 10 jobs)
+    Bnb,
+}
+
+impl Algorithm {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Algorithm::Ga => "ga",
+            Algorithm::Ig => "ig",
+            Algorithm::Sa => "sa",
+            Algorithm::Tabu => "tabu",
+            Algorithm::Vns => "vns",
+            Algorithm::Alns => "alns",
+            Algorithm::Neh => "neh",
+            Algorithm::Mddr => "mddr",
+            Algorithm::Gch => "gch",
+            Algorithm::Bnb => "bnb",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct SolverConfig {
+    pub algorithm: Algorithm,
+
+    // Rule for dispatching jobs to machines when decoding a permutation
+    pub decoding: DecodingType,
+
+    // Objective to minimize
+    pub objective: ObjectiveType,
+
+    // Objectives and their weights summed by the weighted objective
+    pub objective_weights: Vec<(ObjectiveType, u32)>,
+
+    // When to stop the GA, IG, SA, tabu search, VNS and ALNS, see common::termination
+    pub termination: Termination,
+
+    // Seed of the random number generator
+    pub seed: u64,
+
+    // Options of the GA
+    pub ga: Options,
+
+    // Options of the IG
+    pub ig: IgOptions,
+
+    // Options of the SA
+    pub sa: SaOptions,
+
+    // Options of the tabu search
+    pub tabu: TabuOptions,
+
+    // Options of the VNS
+    pub vns: VnsOptions,
+
+    // Options of the ALNS
+    pub alns: AlnsOptions,
+}
+
+impl Default for SolverConfig {
+    fn default() -> Self {
+        Self {
+            algorithm: Algorithm::Ga,
+            decoding: params::DECODING,
+            objective: params::OBJECTIVE,
+            objective_weights: params::OBJECTIVE_WEIGHTS.to_vec(),
+            termination: Termination::default(),
+            seed: params::SEED,
+            ga: Options {
+                write_improvement: false,
+                ..Options::default()
+            },
+            ig: IgOptions {
+                write_improvement: false,
+                ..IgOptions::default()
+            },
+            sa: SaOptions {
+                run: RunOptions {
+                    write_improvement: false,
+                    ..RunOptions::default()
+                },
+                ..SaOptions::default()
+            },
+            tabu: TabuOptions {
+                run: RunOptions {
+                    write_improvement: false,
+                    ..RunOptions::default()
+                },
+                ..TabuOptions::default()
+            },
+            vns: VnsOptions {
+                run: RunOptions {
+                    write_improvement: false,
+                    ..RunOptions::default()
+                },
+                ..VnsOptions::default()
+            },
+            alns: AlnsOptions {
+                run: RunOptions {
+                    write_improvement: false,
+                    ..RunOptions::default()
+                },
+                ..AlnsOptions::default()
+            },
+        }
+    }
+}
+
+impl SolverConfig {
+    // Returns the config given by GA options, as set by the config file and flags of the command line. The options
+    // of the SA, tabu search, VNS and ALNS are those of the GA options
+    pub fn from_options(algorithm: Algorithm, options: &Options) -> SolverConfig {
+        SolverConfig {
+            algorithm,
+            decoding: options.decoding,
+            objective: options.objective,
+            objective_weights: options.objective_weights.clone(),
+            termination: options.termination.clone(),
+            seed: options.seed,
+            ga: options.clone(),
+            ig: IgOptions {
+                problem_file: Cow::Owned(options.problem_file.as_ref().clone()),
+                write_improvement: options.write_improvement,
+                improvement_folder: options.improvement_folder.clone(),
+                ..IgOptions::default()
+            },
+            sa: SaOptions {
+                run: run_options(options),
+                ..options.sa.clone()
+            },
+            tabu: TabuOptions {
+                run: run_options(options),
+                ..options.tabu.clone()
+            },
+            vns: VnsOptions {
+                run: run_options(options),
+                ..options.vns.clone()
+            },
+            alns: AlnsOptions {
+                run: run_options(options),
+                ..options.alns.clone()
+            },
+        }
+    }
+}
+
+// Problem file and improvement file options of the GA options, for the algorithms with run options
+fn run_options(options: &Options) -> RunOptions {
+    RunOptions {
+        problem_file: Cow::Owned(options.problem_file.as_ref().clone()),
+        write_improvement: options.write_improvement,
+        improvement_folder: options.improvement_folder.clone(),
+        ..RunOptions::default()
+    }
+}
+
+pub struct SolveResult {
+    // Objective value of the best solution, the makespan for MDDR
+    pub value: u32,
+
+    pub makespan: u32,
+
+    // Job permutation of the best solution, empty for MDDR
+    pub permutation: Vec<u32>,
+
+    pub solution: Solution,
+
+    // Number of evaluated schedules
+    pub evaluations: u32,
+
+    pub elapsed: Duration,
+}
+
+// Runs the algorithm of the config on the instance, and returns its best solution
+pub fn solve(instance: &Instance, config: SolverConfig) -> SolveResult {
+    let start_time = Instant::now();
+
+    let mut makespan = Makespan::new_incremental(instance, config.decoding);
+    makespan.objective = config.objective;
+    makespan.objective_weights = config.objective_weights.clone();
+    let mut rng = StdRng::seed_from_u64(config.seed);
+    // Evaluations of the GA, which uses its own Makespan
+    let mut ga_evaluations = 0;
+
+    let (permutation, value) = match config.algorithm {
+        Algorithm::Ga => {
+            let ga = ga::solve_instance(
+                instance.clone(),
+                Options {
+                    decoding: config.decoding,
+                    objective: config.objective,
+                    objective_weights: config.objective_weights.clone(),
+                    termination: config.termination.clone(),
+                    seed: config.seed,
+                    ..config.ga
+                },
+            );
+            ga_evaluations = ga.makespan.count;
+            let winner = ga.population.iter().min().unwrap();
+            (winner.jobs.clone(), winner.fitness.unwrap())
+        }
+        Algorithm::Ig => iterated_greedy(
+            &mut makespan,
+            None,
+            Some(IgOptions {
+                termination: config.termination,
+                seed: config.seed,
+                ..config.ig
+            }),
+            &mut rng,
+        ),
+        Algorithm::Sa => simulated_annealing(
+            &mut makespan,
+            None,
+            Some(SaOptions {
+                run: RunOptions {
+                    termination: config.termination,
+                    seed: config.seed,
+                    ..config.sa.run
+                },
+                ..config.sa
+            }),
+            &mut rng,
+        ),
+        Algorithm::Tabu => tabu_search(
+            &mut makespan,
+            None,
+            Some(TabuOptions {
+                run: RunOptions {
+                    termination: config.termination,
+                    seed: config.seed,
+                    ..config.tabu.run
+                },
+                ..config.tabu
+            }),
+            &mut rng,
+        ),
+        Algorithm::Vns => vns(
+            &mut makespan,
+            None,
+            Some(VnsOptions {
+                run: RunOptions {
+                    termination: config.termination,
+                    seed: config.seed,
+                    ..config.vns.run
+                },
+                ..config.vns
+            }),
+            &mut rng,
+        ),
+        Algorithm::Alns => alns(
+            &mut makespan,
+            None,
+            Some(AlnsOptions {
+                run: RunOptions {
+                    termination: config.termination,
+                    seed: config.seed,
+                    ..config.alns.run
+                },
+                ..config.alns
+            }),
+            &mut rng,
+        ),
+        Algorithm::Neh => NEH::neh(&mut makespan),
+        Algorithm::Bnb => {
+            let termination = config.termination.or_default_time(instance);
+            let permutation = branch_and_bound(&mut makespan, &termination).permutation;
+            let value = makespan.evaluate(&permutation);
+            (permutation, value)
+        }
+        Algorithm::Gch => {
+            let c = GCH {
+                makespan: &mut makespan,
+                rng: &mut rng,
+            }
+            .create();
+            let value = c.fitness.unwrap();
+            (c.jobs, value)
+        }
+        Algorithm::Mddr => {
+            let (mks, machine_completions) = MDDR::mddr(&mut makespan);
+            return SolveResult {
+                value: mks,
+                makespan: mks,
+                permutation: Vec::new(),
+                solution: Solution::new(machine_completions, mks, instance),
+                evaluations: makespan.count,
+                elapsed: start_time.elapsed(),
+            };
+        }
+    };
+
+    let evaluations = ga_evaluations + makespan.count;
+    let (mks, machine_completions) = makespan.makespan(&permutation);
+
+    SolveResult {
+        value,
+        makespan: mks,
+        permutation,
+        solution: Solution::new(machine_completions, mks, instance),
+        evaluations,
+        elapsed: start_time.elapsed(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{borrow::Cow, path::PathBuf};
+
+    use crate::{
+        common::{instance::parse, termination::Termination},
+        genetic_algorithm::entities::options::Options,
+    };
+
+    use super::{solve, Algorithm, SolverConfig};
+
+    #[test]
+    fn all_algorithms() {
+        let instance = parse("./instances/ruiz/json/n20m2-43.json").unwrap();
+
+        for algorithm in [
+            Algorithm::Ga,
+            Algorithm::Ig,
+            Algorithm::Sa,
+            Algorithm::Tabu,
+            Algorithm::Vns,
+            Algorithm::Alns,
+            Algorithm::Neh,
+            Algorithm::Mddr,
+            Algorithm::Gch,
+            Algorithm::Bnb,
+        ] {
+            let config = SolverConfig {
+                algorithm,
+                termination: Termination {
+                    time_limit: Some(100),
+                    ..Termination::default()
+                },
+                ga: Options {
+                    pop_size: 20,
+                    write_improvement: false,
+                    ..Options::default()
+                },
+                ..Default::default()
+            };
+            let result = solve(&instance, config);
+
+            assert_eq!(result.value, result.makespan, "{:?}", algorithm);
+            assert!(
+                result.solution.check(&instance).is_empty(),
+                "{:?}",
+                algorithm
+            );
+            match algorithm {
+                Algorithm::Mddr => assert!(result.permutation.is_empty()),
+                _ => assert_eq!(result.permutation.len(), instance.jobs as usize),
+            }
+        }
+    }
+
+    #[test]
+    fn same_seed_same_solution() {
+        let instance = parse("./instances/ruiz/json/n20m2-43.json").unwrap();
+        let config = SolverConfig {
+            algorithm: Algorithm::Gch,
+            seed: 7,
+            ..Default::default()
+        };
+
+        let first = solve(&instance, config.clone());
+        let second = solve(&instance, config);
+        assert_eq!(first.permutation, second.permutation);
+    }
+
+    #[test]
+    fn evaluation_budget_is_reproducible() {
+        let instance = parse("./instances/ruiz/json/n20m2-43.json").unwrap();
+
+        for algorithm in [
+            Algorithm::Ga,
+            Algorithm::Ig,
+            Algorithm::Sa,
+            Algorithm::Tabu,
+            Algorithm::Vns,
+            Algorithm::Alns,
+        ] {
+            let config = SolverConfig {
+                algorithm,
+                termination: Termination {
+                    max_evaluations: Some(2000),
+                    ..Termination::default()
+                },
+                seed: 42,
+                ga: Options {
+                    pop_size: 20,
+                    write_improvement: false,
+                    ..Options::default()
+                },
+                ..Default::default()
+            };
+            assert!(config.termination.is_reproducible());
+
+            let first = solve(&instance, config.clone());
+            let second = solve(&instance, config);
+            assert_eq!(first.permutation, second.permutation, "{:?}", algorithm);
+            assert_eq!(first.value, second.value, "{:?}", algorithm);
+            assert_eq!(first.evaluations, second.evaluations, "{:?}", algorithm);
+        }
+    }
+
+    #[test]
+    fn improvement_in_the_improvement_folder() {
+        let problem_file = "./instances/ruiz/json/n20m2-43.json";
+        let instance = parse(problem_file).unwrap();
+        let folder = std::env::temp_dir().join("tio4905_improvement_folder");
+        let options = Options {
+            problem_file: Cow::Owned(PathBuf::from(problem_file)),
+            pop_size: 20,
+            write_improvement: true,
+            improvement_folder: folder.clone(),
+            termination: Termination {
+                max_evaluations: Some(500),
+                ..Termination::default()
+            },
+            ..Options::default()
+        };
+
+        for (algorithm, file) in [
+            (Algorithm::Ga, "ga/init_gch2"),
+            (Algorithm::Ig, "ig/all"),
+            (Algorithm::Sa, "sa/all"),
+            (Algorithm::Tabu, "tabu/all"),
+            (Algorithm::Vns, "vns/all"),
+            (Algorithm::Alns, "alns/all"),
+        ] {
+            solve(&instance, SolverConfig::from_options(algorithm, &options));
+            assert!(
+                folder.join(file).join("n20m2-43.csv").is_file(),
+                "{:?}",
+                algorithm
+            );
+        }
+        std::fs::remove_dir_all(&folder).unwrap();
+    }
+}
@@ -0,0 +1,76 @@
+use serde_derive::{Deserialize, Serialize};
+
+use crate::common::run::{AlgorithmOptions, RunOptions};
+
+// Moves of the neighborhood, see tabu_search.rs
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum MoveType {
+    // Remove a job and insert it at another position
+    Insertion,
+    // Exchange two jobs
+    Swap,
+    // Both insertion and swap moves
+    Mixed,
+}
+
+// Attribute of the moves kept in the tabu list
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum TabuAttribute {
+    // A moved job may not return to its previous position
+    JobPosition,
+    // A moved job may not return to its previous order with the job it passed
+    JobPair,
+}
+
+// Solution the search starts from
+#[allow(dead_code)]
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize, Serialize)]
+pub enum Start {
+    NEH,
+    // Jobs in order of their first operation in the MDDR schedule
+    MDDR,
+    GCH,
+}
+
+// Set by the [tabu] table of the config file, see genetic_algorithm::entities::config. The run options are
+// taken from the GA options, see solve.rs
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Options {
+    pub start: Start,
+
+    pub move_type: MoveType,
+
+    pub attribute: TabuAttribute,
+
+    // Iterations a move attribute stays tabu
+    pub tenure: usize,
+
+    // Most moves evaluated in an iteration, a random sample of the neighborhood is evaluated if it is larger
+    pub candidates: usize,
+
+    // Problem file, termination criteria, seed and improvement file, see common::run. The generations are the iterations
+    #[serde(skip)]
+    pub run: RunOptions,
+}
+
+impl AlgorithmOptions for Options {
+    fn run_options(&mut self) -> &mut RunOptions {
+        &mut self.run
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            start: Start::NEH,
+            move_type: MoveType::Insertion,
+            attribute: TabuAttribute::JobPosition,
+            tenure: 7,
+            candidates: 500,
+            run: RunOptions::default(),
+        }
+    }
+}
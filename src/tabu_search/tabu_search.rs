@@ -0,0 +1,429 @@
+/*
+Tabu search on the job permutation.
+
+Tabu_Search(Instance) {
+    current = start solution (NEH, MDDR or GCH);
+    best = current;
+    while termination criteria not met {
+        evaluate the moves of the neighborhood of current (a random sample of candidates moves if it is larger);
+        move = the best move that is not tabu, or is tabu but gives a solution better than best (aspiration);
+        make the attributes of move tabu for tenure iterations;
+        current = move(current);           (also if it is worse than current)
+        if current is better than best { best = current }
+    }
+    return best
+}
+
+Moves are given by positions, so the neighborhood is the same in all iterations:
+    Insertion: the job at position from is removed and inserted at position to, (n - 1)^2 moves
+    Swap: the jobs at positions first and second are exchanged, n (n - 1) / 2 moves
+
+Tabu attributes, made tabu by a move and checked against the positions of the jobs after a move:
+    JobPosition: (job, position) for the moved jobs and their positions before the move. A move is tabu if it
+                 puts a job back at such a position.
+    JobPair: (a, b) for the order of the moved job and the first job it passes (or the two swapped jobs) before
+             the move. A move is tabu if it puts a before b again.
+
+If all evaluated moves are tabu and none is aspirated, the best of them is made anyway. Every iteration is one
+generation of the termination criteria (see common::termination), and an iteration evaluates at most the
+evaluations left of max_evaluations.
+*/
+
+use std::{collections::VecDeque, time::Duration};
+
+use rand::{prelude::StdRng, seq::index::sample};
+
+use crate::{
+    common::{
+        construction::{gch::GCH, mddr::MDDR, neh::NEH, solver::Solver, Constructor},
+        makespan::Makespan,
+        run::{self, Improvement},
+        termination::Progress,
+    },
+    iterated_greedy::options::Options as IgOptions,
+};
+
+use super::options::{MoveType, Options, Start, TabuAttribute};
+
+pub struct TabuSearch {}
+
+impl Solver for TabuSearch {
+    fn run(makespan: &mut Makespan, option: Option<IgOptions>) -> u32 {
+        run::run_solver(makespan, option, tabu_search)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Move {
+    Insertion { from: usize, to: usize },
+    Swap { first: usize, second: usize },
+}
+
+impl Move {
+    fn apply(&self, jobs: &mut Vec<u32>) {
+        match *self {
+            Move::Insertion { from, to } => {
+                let job = jobs.remove(from);
+                jobs.insert(to, job);
+            }
+            Move::Swap { first, second } => jobs.swap(first, second),
+        }
+    }
+
+    // Position after the move of the job at the position
+    fn position(&self, position: usize) -> usize {
+        match *self {
+            Move::Insertion { from, to } if position == from => to,
+            Move::Insertion { from, to } if from < to && (from..=to).contains(&position) => {
+                position - 1
+            }
+            Move::Insertion { from, to } if to < from && (to..from).contains(&position) => {
+                position + 1
+            }
+            Move::Swap { first, second } if position == first => second,
+            Move::Swap { first, second } if position == second => first,
+            _ => position,
+        }
+    }
+}
+
+// All moves of a permutation of n jobs
+fn neighborhood(n: usize, move_type: MoveType) -> Vec<Move> {
+    let mut moves = Vec::new();
+
+    if move_type != MoveType::Swap {
+        for from in 0..n {
+            // Moving a job one position back is the same as moving the job before it one position forward
+            for to in (0..n).filter(|&to| to != from && to + 1 != from) {
+                moves.push(Move::Insertion { from, to });
+            }
+        }
+    }
+    if move_type != MoveType::Insertion {
+        for first in 0..n {
+            for second in first + 1..n {
+                moves.push(Move::Swap { first, second });
+            }
+        }
+    }
+
+    moves
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Tabu {
+    // The job may not be put at the position
+    JobPosition(u32, usize),
+    // The first job may not be put before the second
+    JobPair(u32, u32),
+}
+
+// Tabu attributes with the iteration they were added
+struct TabuList {
+    tenure: usize,
+    entries: VecDeque<(usize, Tabu)>,
+}
+
+impl TabuList {
+    // Removes the attributes that are no longer tabu in the iteration
+    fn expire(&mut self, iteration: usize) {
+        while self
+            .entries
+            .front()
+            .is_some_and(|(added, _)| added + self.tenure < iteration)
+        {
+            self.entries.pop_front();
+        }
+    }
+
+    // positions[job] is the position of the job before the move
+    fn is_tabu(&self, m: &Move, positions: &[usize]) -> bool {
+        self.entries.iter().any(|(_, tabu)| match *tabu {
+            Tabu::JobPosition(job, position) => {
+                positions[job as usize] != position
+                    && m.position(positions[job as usize]) == position
+            }
+            Tabu::JobPair(a, b) => {
+                let (a, b) = (positions[a as usize], positions[b as usize]);
+                a > b && m.position(a) < m.position(b)
+            }
+        })
+    }
+
+    // Makes the attributes of the move of the jobs tabu
+    fn add(&mut self, iteration: usize, m: &Move, jobs: &[u32], attribute: TabuAttribute) {
+        let tabu = match (attribute, *m) {
+            (TabuAttribute::JobPosition, Move::Insertion { from, .. }) => {
+                vec![Tabu::JobPosition(jobs[from], from)]
+            }
+            (TabuAttribute::JobPosition, Move::Swap { first, second }) => vec![
+                Tabu::JobPosition(jobs[first], first),
+                Tabu::JobPosition(jobs[second], second),
+            ],
+            (TabuAttribute::JobPair, Move::Insertion { from, to }) if from < to => {
+                vec![Tabu::JobPair(jobs[from], jobs[from + 1])]
+            }
+            (TabuAttribute::JobPair, Move::Insertion { from, .. }) => {
+                vec![Tabu::JobPair(jobs[from - 1], jobs[from])]
+            }
+            (TabuAttribute::JobPair, Move::Swap { first, second }) => {
+                vec![Tabu::JobPair(jobs[first], jobs[second])]
+            }
+        };
+
+        self.entries
+            .extend(tabu.into_iter().map(|tabu| (iteration, tabu)));
+    }
+}
+
+// All schedules are tuples of the schedule and objective value (the makespan by default) of the schedule
+
+pub fn tabu_search(
+    makespan: &mut Makespan,
+    schedule: Option<(Vec<u32>, u32)>,
+    option: Option<Options>,
+    rng: &mut StdRng,
+) -> (Vec<u32>, u32) {
+    let options = option.unwrap_or_default();
+    let mut current = schedule.unwrap_or_else(|| initial_solution(options.start, makespan, rng));
+    let mut best = current.clone();
+
+    let mut improvement = Improvement::new(&options.run, best.1, makespan.count);
+
+    // No other permutation to move to
+    if current.0.len() < 2 {
+        return best;
+    }
+
+    let moves = neighborhood(current.0.len(), options.move_type);
+    let mut tabu_list = TabuList {
+        tenure: options.tenure,
+        entries: VecDeque::new(),
+    };
+
+    let termination = options
+        .run
+        .termination
+        .for_instance(&makespan.instance, makespan.objective);
+    let start_evaluations = makespan.count;
+    let mut progress = Progress::new(&termination, makespan.count, Duration::ZERO);
+
+    while !progress.is_terminated(makespan.count) {
+        let iteration = progress.generation;
+        tabu_list.expire(iteration);
+
+        let mut positions = vec![0; current.0.len()];
+        for (position, &job) in current.0.iter().enumerate() {
+            positions[job as usize] = position;
+        }
+
+        // Candidate list, limited by the evaluations left
+        let mut amount = options.candidates.clamp(1, moves.len());
+        if let Some(max_evaluations) = termination.max_evaluations {
+            let left = max_evaluations.saturating_sub(makespan.count - start_evaluations);
+            amount = amount.min(left.max(1) as usize);
+        }
+        let candidates: Vec<Move> = match amount < moves.len() {
+            true => sample(rng, moves.len(), amount)
+                .into_iter()
+                .map(|i| moves[i])
+                .collect(),
+            false => moves.clone(),
+        };
+
+        // The best admissible move, and the best move in case all moves are tabu
+        let mut admissible: Option<(Move, Vec<u32>, u32)> = None;
+        let mut fallback: Option<(Move, Vec<u32>, u32)> = None;
+        for m in candidates {
+            let mut jobs = current.0.clone();
+            m.apply(&mut jobs);
+            let value = makespan.evaluate(&jobs);
+
+            if value < best.1 || !tabu_list.is_tabu(&m, &positions) {
+                if admissible.as_ref().is_none_or(|a| value < a.2) {
+                    admissible = Some((m, jobs, value));
+                }
+            } else if fallback.as_ref().is_none_or(|f| value < f.2) {
+                fallback = Some((m, jobs, value));
+            }
+        }
+
+        let (m, jobs, value) = admissible.or(fallback).unwrap();
+        tabu_list.add(iteration, &m, &current.0, options.attribute);
+        current = (jobs, value);
+
+        if current.1 < best.1 {
+            best = current.clone();
+            improvement.push(&progress, best.1, makespan.count);
+        }
+
+        progress.next_generation(best.1);
+    }
+
+    improvement.write("tabu", &options.run, &progress, best.1, makespan.count);
+
+    best
+}
+
+// The start solution of the search
+pub fn initial_solution(
+    start: Start,
+    makespan: &mut Makespan,
+    rng: &mut StdRng,
+) -> (Vec<u32>, u32) {
+    match start {
+        Start::NEH => NEH::neh(makespan),
+        Start::GCH => {
+            let c = GCH { makespan, rng }.create();
+            let value = c.fitness.unwrap();
+            (c.jobs, value)
+        }
+        Start::MDDR => {
+            let permutation = mddr_permutation(makespan);
+            let value = makespan.evaluate(&permutation);
+            (permutation, value)
+        }
+    }
+}
+
+// Jobs in order of the first stage they visit, and then of their completion in it, in the MDDR schedule
+fn mddr_permutation(makespan: &mut Makespan) -> Vec<u32> {
+    let (_, machine_completions) = MDDR::mddr(makespan);
+
+    let mut first = vec![(usize::MAX, 0); makespan.instance.jobs as usize];
+    for (stage, machines) in machine_completions.iter().enumerate() {
+        for &(job, completion) in machines.iter().flatten() {
+            if first[job as usize].0 == usize::MAX {
+                first[job as usize] = (stage, completion);
+            }
+        }
+    }
+
+    let mut permutation: Vec<u32> = (0..makespan.instance.jobs).collect();
+    permutation.sort_by_key(|&job| first[job as usize]);
+    permutation
+}
+
+#[cfg(test)]
+mod tabu_tests {
+    use rand::{prelude::StdRng, SeedableRng};
+
+    use crate::{
+        common::{
+            decoding::DecodingType, instance::parse, makespan::Makespan, run::RunOptions,
+            termination::Termination,
+        },
+        tabu_search::options::{MoveType, Options, Start, TabuAttribute},
+    };
+
+    use super::{initial_solution, neighborhood, tabu_search, Move, TabuList};
+
+    #[test]
+    fn moves_and_tabu_attributes() {
+        let jobs = vec![0, 1, 2, 3, 4];
+        let positions = [0, 1, 2, 3, 4];
+
+        // Every move gives a different permutation
+        let mut permutations: Vec<Vec<u32>> = neighborhood(5, MoveType::Mixed)
+            .iter()
+            .map(|m| {
+                let mut moved = jobs.clone();
+                m.apply(&mut moved);
+                assert!((0..5).all(|p| moved[m.position(p)] == jobs[p]), "{:?}", m);
+                moved
+            })
+            .collect();
+        assert_eq!(neighborhood(5, MoveType::Insertion).len(), 16);
+        assert_eq!(neighborhood(5, MoveType::Swap).len(), 10);
+        permutations.sort();
+        permutations.dedup();
+        // Swaps of adjacent jobs are also insertions
+        assert_eq!(permutations.len(), 16 + 10 - 4);
+
+        let insertion = Move::Insertion { from: 1, to: 3 };
+        let back = Move::Insertion { from: 3, to: 1 };
+        for attribute in [TabuAttribute::JobPosition, TabuAttribute::JobPair] {
+            let mut tabu_list = TabuList {
+                tenure: 2,
+                entries: Default::default(),
+            };
+            tabu_list.add(0, &insertion, &jobs, attribute);
+
+            // Job 1 is at position 3 after the insertion, and may not move back before job 2
+            let mut moved = jobs.clone();
+            insertion.apply(&mut moved);
+            let mut moved_positions = [0; 5];
+            for (position, &job) in moved.iter().enumerate() {
+                moved_positions[job as usize] = position;
+            }
+            assert!(
+                tabu_list.is_tabu(&back, &moved_positions),
+                "{:?}",
+                attribute
+            );
+            assert!(!tabu_list.is_tabu(
+                &Move::Swap {
+                    first: 0,
+                    second: 4
+                },
+                &positions
+            ));
+
+            tabu_list.expire(2);
+            assert!(tabu_list.is_tabu(&back, &moved_positions));
+            tabu_list.expire(3);
+            assert!(!tabu_list.is_tabu(&back, &moved_positions));
+        }
+    }
+
+    #[test]
+    fn tabu_search_test() {
+        let instance = parse("./instances/ruiz/json/n20m2-43.json").unwrap();
+
+        for start in [Start::NEH, Start::MDDR, Start::GCH] {
+            for (move_type, attribute) in [
+                (MoveType::Insertion, TabuAttribute::JobPosition),
+                (MoveType::Swap, TabuAttribute::JobPair),
+                (MoveType::Mixed, TabuAttribute::JobPair),
+            ] {
+                let options = Options {
+                    start,
+                    move_type,
+                    attribute,
+                    // Samples of the neighborhood, which has at least 190 moves
+                    candidates: 100,
+                    run: RunOptions {
+                        termination: Termination {
+                            max_evaluations: Some(3000),
+                            ..Termination::default()
+                        },
+                        write_improvement: false,
+                        ..RunOptions::default()
+                    },
+                    ..Options::default()
+                };
+
+                let run = || {
+                    let mut makespan = Makespan::new_incremental(&instance, DecodingType::Fifo);
+                    let mut rng = StdRng::seed_from_u64(7);
+                    let initial = initial_solution(start, &mut makespan, &mut rng);
+                    let mut rng = StdRng::seed_from_u64(7);
+                    let evaluations = makespan.count;
+                    let result = tabu_search(
+                        &mut makespan,
+                        Some(initial.clone()),
+                        Some(options.clone()),
+                        &mut rng,
+                    );
+
+                    assert_eq!(makespan.count, evaluations + 3000);
+                    assert_eq!(makespan.evaluate(&result.0), result.1);
+                    assert!(result.1 <= initial.1, "{:?} {:?}", start, move_type);
+                    result
+                };
+
+                assert_eq!(run(), run(), "{:?} {:?}", start, move_type);
+            }
+        }
+    }
+}
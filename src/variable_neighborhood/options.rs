@@ -0,0 +1,41 @@
+use serde_derive::{Deserialize, Serialize};
+
+use crate::common::{
+    neighborhood::{Exploration, NTYPE},
+    run::{AlgorithmOptions, RunOptions},
+};
+
+// Set by the [vns] table of the config file, see genetic_algorithm::entities::config. The run options are
+// taken from the GA options, see solve.rs
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Options {
+    // Neighborhoods of the VND, in the order they are searched
+    pub neighborhoods: Vec<NTYPE>,
+
+    pub exploration: Exploration,
+
+    // Neighborhoods of the shaking, one for each step of the VNS
+    pub shaking: Vec<NTYPE>,
+
+    // Problem file, termination criteria, seed and improvement file, see common::run. The generations are the shakings
+    #[serde(skip)]
+    pub run: RunOptions,
+}
+
+impl AlgorithmOptions for Options {
+    fn run_options(&mut self) -> &mut RunOptions {
+        &mut self.run
+    }
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            neighborhoods: vec![NTYPE::AdjacentSwap, NTYPE::Insertion, NTYPE::Swap],
+            exploration: Exploration::FirstImprovement,
+            shaking: vec![NTYPE::Swap, NTYPE::BlockMove, NTYPE::Reversal],
+            run: RunOptions::default(),
+        }
+    }
+}
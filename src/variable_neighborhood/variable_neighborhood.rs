@@ -0,0 +1,235 @@
+/*
+Variable neighborhood descent (VND) and general variable neighborhood search (VNS), with the neighborhoods of
+common::neighborhood.
+
+VND(schedule, neighborhoods N_1..N_k) {
+    i = 1;
+    while i <= k {
+        if N_i has a better neighbour of schedule (first or best improvement) {
+            schedule = the neighbour;
+            i = 1;
+        } else {
+            i = i + 1;
+        }
+    }
+    return schedule          (a local optimum of all the neighborhoods)
+}
+
+VNS(Instance) {
+    current = VND(NEH(instance));
+    j = 1;
+    while termination criteria not met {
+        shaken = a random neighbour of current in the shaking neighborhood S_j;
+        candidate = VND(shaken);
+        if candidate is better than current {
+            current = candidate;
+            j = 1;
+        } else {
+            j = j % (number of shaking neighborhoods) + 1;
+        }
+    }
+    return current
+}
+
+Every shaking is one generation of the termination criteria (see common::termination), and the VND stops when
+the criteria are met.
+*/
+
+use std::time::Duration;
+
+use rand::prelude::StdRng;
+
+use crate::{
+    common::{
+        construction::{neh::NEH, solver::Solver},
+        makespan::Makespan,
+        neighborhood::{Exploration, NTYPE},
+        run::{self, Improvement},
+        termination::Progress,
+    },
+    iterated_greedy::options::Options as IgOptions,
+};
+
+use super::options::Options;
+
+pub struct VariableNeighborhoodSearch {}
+
+impl Solver for VariableNeighborhoodSearch {
+    fn run(makespan: &mut Makespan, option: Option<IgOptions>) -> u32 {
+        run::run_solver(makespan, option, vns)
+    }
+}
+
+// Improves the schedule until it is a local optimum of all the neighborhoods, or the criteria are met
+pub fn vnd(
+    makespan: &mut Makespan,
+    schedule: (Vec<u32>, u32),
+    neighborhoods: &[NTYPE],
+    exploration: Exploration,
+    progress: &Progress,
+    rng: &mut StdRng,
+) -> (Vec<u32>, u32) {
+    let mut current = schedule;
+    let mut i = 0;
+
+    while i < neighborhoods.len() && !progress.is_terminated(makespan.count) {
+        match neighborhoods[i].improve(exploration, makespan, &current, progress, rng) {
+            Some(better) => {
+                current = better;
+                i = 0;
+            }
+            None => i += 1,
+        }
+    }
+
+    current
+}
+
+// All schedules are tuples of the schedule and objective value (the makespan by default) of the schedule
+
+pub fn vns(
+    makespan: &mut Makespan,
+    schedule: Option<(Vec<u32>, u32)>,
+    option: Option<Options>,
+    rng: &mut StdRng,
+) -> (Vec<u32>, u32) {
+    let options = option.unwrap_or_default();
+    let schedule = schedule.unwrap_or_else(|| NEH::neh(makespan));
+
+    let mut improvement = Improvement::new(&options.run, schedule.1, makespan.count);
+
+    let termination = options
+        .run
+        .termination
+        .for_instance(&makespan.instance, makespan.objective);
+    let mut progress = Progress::new(&termination, makespan.count, Duration::ZERO);
+
+    let neighborhoods = &options.neighborhoods;
+    let mut current = vnd(
+        makespan,
+        schedule,
+        neighborhoods,
+        options.exploration,
+        &progress,
+        rng,
+    );
+    let mut j = 0;
+
+    while !options.shaking.is_empty() && !progress.is_terminated(makespan.count) {
+        let shaken = options.shaking[j].random_neighbour(&current.0, rng);
+        let value = makespan.evaluate(&shaken);
+        let candidate = vnd(
+            makespan,
+            (shaken, value),
+            neighborhoods,
+            options.exploration,
+            &progress,
+            rng,
+        );
+
+        if candidate.1 < current.1 {
+            current = candidate;
+            j = 0;
+            improvement.push(&progress, current.1, makespan.count);
+        } else {
+            j = (j + 1) % options.shaking.len();
+        }
+
+        progress.next_generation(current.1);
+    }
+
+    improvement.write("vns", &options.run, &progress, current.1, makespan.count);
+
+    current
+}
+
+#[cfg(test)]
+mod vns_tests {
+    use std::time::Duration;
+
+    use rand::{prelude::StdRng, SeedableRng};
+
+    use crate::{
+        common::{
+            construction::neh::NEH,
+            decoding::DecodingType,
+            instance::parse,
+            makespan::Makespan,
+            neighborhood::{Exploration, NTYPE},
+            run::RunOptions,
+            termination::{Progress, Termination},
+        },
+        variable_neighborhood::options::Options,
+    };
+
+    use super::{vnd, vns};
+
+    #[test]
+    fn vnd_is_local_optimum() {
+        let instance = parse("./instances/ruiz/json/n20m2-43.json").unwrap();
+        let mut makespan = Makespan::new_incremental(&instance, DecodingType::Fifo);
+        let mut rng = StdRng::seed_from_u64(123);
+        let progress = Progress::new(&Termination::default(), 0, Duration::ZERO);
+
+        let jobs: Vec<u32> = (0..instance.jobs).collect();
+        let start = (jobs.clone(), makespan.evaluate(&jobs));
+        let neighborhoods = [NTYPE::AdjacentSwap, NTYPE::Insertion, NTYPE::Reversal];
+        let optimum = vnd(
+            &mut makespan,
+            start.clone(),
+            &neighborhoods,
+            Exploration::BestImprovement,
+            &progress,
+            &mut rng,
+        );
+
+        assert!(optimum.1 < start.1);
+        for neighborhood in neighborhoods {
+            assert!(neighborhood
+                .improve(
+                    Exploration::BestImprovement,
+                    &mut makespan,
+                    &optimum,
+                    &progress,
+                    &mut rng
+                )
+                .is_none());
+        }
+    }
+
+    #[test]
+    fn vns_test() {
+        let instance = parse("./instances/ruiz/json/n20m2-43.json").unwrap();
+        let options = Options {
+            run: RunOptions {
+                termination: Termination {
+                    max_evaluations: Some(5000),
+                    ..Termination::default()
+                },
+                write_improvement: false,
+                ..RunOptions::default()
+            },
+            ..Options::default()
+        };
+
+        let run = || {
+            let mut makespan = Makespan::new_incremental(&instance, DecodingType::Fifo);
+            let mut rng = StdRng::seed_from_u64(7);
+            let neh = NEH::neh(&mut makespan);
+            let evaluations = makespan.count;
+            let result = vns(
+                &mut makespan,
+                Some(neh.clone()),
+                Some(options.clone()),
+                &mut rng,
+            );
+
+            assert_eq!(makespan.count, evaluations + 5000);
+            assert!(result.1 <= neh.1);
+            assert_eq!(makespan.evaluate(&result.0), result.1);
+            result
+        };
+
+        assert_eq!(run(), run());
+    }
+}